@@ -1,10 +1,10 @@
 use crate::spotify::{
-    Album, DatedAlbum, Spotify, SpotifyCollection, SpotifyError, SpotifyObject, Track,
+    Album, DatedAlbum, Market, Spotify, SpotifyCollection, SpotifyError, SpotifyObject, Track,
 };
-use crate::srequest::RequestMethod;
+use crate::srequest::{append_query_parameter, RequestMethod};
 use json::JsonValue::Boolean;
 use serde_json::Value;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 
 impl Spotify {
     /// Get an album: <https://developer.spotify.com/documentation/web-api/reference/#/operations/get-an-album>
@@ -13,18 +13,14 @@ impl Spotify {
     ///
     /// # Arguments
     /// * `album_id` - The Spotify ID of the album.
-    /// * `market` - An ISO 3166-1 alpha-2 country code.
+    /// * `market` - An ISO 3166-1 alpha-2 country code, or `Market::FromToken` for the current user's market.
     ///
-    pub fn get_album(
-        &self,
-        album_id: &str,
-        market: Option<&str>,
-    ) -> Result<Album, SpotifyError> {
+    pub fn get_album(&self, album_id: &str, market: Option<Market>) -> Result<Album, SpotifyError> {
         let mut url_extension = format!("albums/{}", album_id); // base url
 
         // if market parameter supplied, add to request as query parameter
         if let Some(market) = market {
-            url_extension.push_str(&format!("?market={}", market));
+            append_query_parameter(&mut url_extension, "market", market.code());
         }
 
         let response = self.spotify_request(&url_extension, RequestMethod::Get)?; // make request
@@ -32,72 +28,144 @@ impl Spotify {
         return Ok(Album::new(&response)); // format and return result
     }
 
+    /// Get an album, controlling how much of its track listing is kept: <https://developer.spotify.com/documentation/web-api/reference/#/operations/get-an-album>
+    /// With `fetch_all_tracks` the album's tracks are paged to completion, so `Album.tracks` holds every track with no next page.
+    /// Without it `Album.tracks` is None.
+    ///
+    /// Required scope: none
+    ///
+    /// # Arguments
+    /// * `album_id` - The Spotify ID of the album.
+    /// * `market` - An ISO 3166-1 alpha-2 country code, or `Market::FromToken` for the current user's market.
+    /// * `fetch_all_tracks` - Whether to fetch every track of the album (true) or drop the tracks entirely (false).
+    ///
+    pub fn get_album_ex(
+        &self,
+        album_id: &str,
+        market: Option<Market>,
+        fetch_all_tracks: bool,
+    ) -> Result<Album, SpotifyError> {
+        let mut album = self.get_album(album_id, market.clone())?; // album with its first page of tracks
+
+        if !fetch_all_tracks {
+            album.tracks = None; // caller doesn't want the tracks
+            return Ok(album);
+        }
+
+        if let Some(tracks) = &mut album.tracks {
+            // continue from the end of the embedded page until there is no next page
+            while tracks.next.is_some() {
+                let offset = tracks.offset as u32 + tracks.items.len() as u32;
+                let page =
+                    self.get_album_tracks(album_id, market.clone(), Some(50), Some(offset))?; // request largest page allowed
+
+                if page.items.is_empty() {
+                    break; // guard against a next link that never ends
+                }
+
+                tracks.items.extend(page.items);
+                tracks.next = page.next;
+            }
+
+            tracks.limit = tracks.items.len() as i32; // collection now holds everything in a single page
+        }
+
+        return Ok(album);
+    }
+
+    /// Get every track of an album grouped by disc number: <https://developer.spotify.com/documentation/web-api/reference/#/operations/get-an-album>
+    /// The album's tracks are paged to completion first (see `get_album_ex`), then grouped as in `Album::tracks_by_disc`.
+    ///
+    /// Required scope: none
+    ///
+    /// # Arguments
+    /// * `album_id` - The Spotify ID of the album.
+    /// * `market` - An ISO 3166-1 alpha-2 country code, or `Market::FromToken` for the current user's market.
+    ///
+    pub fn get_album_tracks_by_disc(
+        &self,
+        album_id: &str,
+        market: Option<Market>,
+    ) -> Result<BTreeMap<i32, Vec<Track>>, SpotifyError> {
+        let album = self.get_album_ex(album_id, market, true)?; // album with every track
+
+        let mut discs: BTreeMap<i32, Vec<Track>> = BTreeMap::new();
+        if let Some(tracks) = album.tracks {
+            for track in tracks.items {
+                discs.entry(track.disc_number).or_default().push(track); // keep track order within each disc
+            }
+        }
+
+        return Ok(discs);
+    }
+
     /// Get several albums: <https://developer.spotify.com/documentation/web-api/reference/#/operations/get-multiple-albums>
+    /// Albums are requested in batches of 20 (the most Spotify allows per request), and are returned in the same order as `album_ids`.
     ///
     /// Required scope: none
     ///
     /// # Arguments
     /// * `album_ids` - A vector of Spotify IDs for the albums.
-    /// * `market` - An ISO 3166-1 alpha-2 country code.
+    /// * `market` - An ISO 3166-1 alpha-2 country code, or `Market::FromToken` for the current user's market.
     ///
     pub fn get_albums(
         &self,
         album_ids: Vec<&str>,
-        market: Option<&str>,
+        market: Option<Market>,
     ) -> Result<Vec<Album>, SpotifyError> {
-        let mut url_extension = format!("albums/?ids={}", album_ids.join(",")); // base url
+        let mut albums = Vec::new(); // create vector to store albums
 
-        // if market parameter supplied, add to request as query parameter
-        if let Some(market) = market {
-            url_extension.push_str(&format!("&market={}", market));
-        }
+        // Spotify returns a batch in the order of its ids, so requesting batches in order keeps the overall order
+        for album_ids_batch in album_ids.chunks(20) {
+            let mut url_extension = format!("albums/?ids={}", album_ids_batch.join(",")); // base url
 
-        let response = self.spotify_request(&url_extension, RequestMethod::Get)?; // make request
+            // if market parameter supplied, add to request as query parameter
+            if let Some(market) = &market {
+                append_query_parameter(&mut url_extension, "market", market.code());
+            }
 
-        let mut albums = Vec::new(); // create vector to store albums
-        for album in response["albums"].members() {
-            albums.push(Album::new(&album)); // format album and push to vector
+            let response = self.spotify_request(&url_extension, RequestMethod::Get)?; // make request
+
+            for album in response["albums"].members() {
+                albums.push(Album::new(&album)); // format album and push to vector
+            }
         }
         return Ok(albums); // return vector of albums
     }
 
     /// Get an album's tracks: <https://developer.spotify.com/documentation/web-api/reference/#/operations/get-an-albums-tracks>
+    /// Note: Spotify returns simplified tracks here, so `album` and `popularity` are None and `external_ids` is empty. Use [get_several_tracks](struct.Spotify.html#method.get_several_tracks) for the full tracks.
     ///
     /// Required scope: none
     ///
     /// # Arguments
     /// * `album_id` - The Spotify ID of the album.
-    /// * `market` - An ISO 3166-1 alpha-2 country code.
+    /// * `market` - An ISO 3166-1 alpha-2 country code, or `Market::FromToken` for the current user's market.
     /// * `limit` - The maximum number of tracks to return. Default: 20. Minimum: 1. Maximum: 50.
     /// * `offset` - The index of the first track to return. Default: 0 (the first object). Use with limit to get the next set of tracks.
     ///
     pub fn get_album_tracks(
         &self,
         album_id: &str,
-        market: Option<&str>,
+        market: Option<Market>,
         limit: Option<u32>,
         offset: Option<u32>,
     ) -> Result<SpotifyCollection<Track>, SpotifyError> {
         let mut url_extension = format!("albums/{}/tracks", album_id); // base url
 
-        // if any parameter is supplied, add to request as query parameter
-        if market != None || limit != None || offset != None {
-            url_extension.push_str("?");
-        }
-
         // if market parameter supplied, add to request as query parameter
         if let Some(market) = market {
-            url_extension.push_str(&format!("&market={}", market));
+            append_query_parameter(&mut url_extension, "market", market.code());
         }
 
         // if limit parameter supplied, add to request as query parameter
         if let Some(limit) = limit {
-            url_extension.push_str(&format!("&limit={}", limit));
+            append_query_parameter(&mut url_extension, "limit", limit);
         }
 
         // if offset parameter supplied, add to request as query parameter
         if let Some(offset) = offset {
-            url_extension.push_str(&format!("&offset={}", offset));
+            append_query_parameter(&mut url_extension, "offset", offset);
         }
 
         let response = self.spotify_request(&url_extension, RequestMethod::Get)?; // make request
@@ -105,6 +173,38 @@ impl Spotify {
         return Ok(SpotifyCollection::<Track>::new(&response)); // format and return result
     }
 
+    /// Get all of an album's tracks, paging through the album until there is no next page: <https://developer.spotify.com/documentation/web-api/reference/#/operations/get-an-albums-tracks>
+    /// Note: these are simplified tracks, so their `album` is None.
+    ///
+    /// Required scope: none
+    ///
+    /// # Arguments
+    /// * `album_id` - The Spotify ID of the album.
+    /// * `market` - An ISO 3166-1 alpha-2 country code, or `Market::FromToken` for the current user's market.
+    ///
+    pub fn get_all_album_tracks(
+        &self,
+        album_id: &str,
+        market: Option<Market>,
+    ) -> Result<Vec<Track>, SpotifyError> {
+        let mut tracks = Vec::new(); // create vector to store tracks
+        let mut offset = 0;
+
+        loop {
+            let page = self.get_album_tracks(album_id, market.clone(), Some(50), Some(offset))?; // request largest page allowed
+
+            offset += page.items.len() as u32;
+            let is_last_page = page.next.is_none() || page.items.is_empty(); // stop when there is no next page
+            tracks.extend(page.items);
+
+            if is_last_page {
+                break;
+            }
+        }
+
+        Ok(tracks)
+    }
+
     /// Get albums saved in user's library: <https://developer.spotify.com/documentation/web-api/reference/#/operations/get-users-saved-albums>
     ///
     /// Required scope: user-library-read
@@ -116,31 +216,26 @@ impl Spotify {
     pub fn get_saved_albums(
         &self,
         limit: Option<u32>,
-        market: Option<&str>,
+        market: Option<Market>,
         offset: Option<u32>,
     ) -> Result<SpotifyCollection<DatedAlbum>, SpotifyError> {
         let mut url_extension = String::from("me/albums"); // base url
 
         self.check_scope("user-library-read")?; // check scope
 
-        // if any parameter is supplied, add to request as query parameter
-        if market != None || limit != None || offset != None {
-            url_extension.push_str("?");
-        }
-
         // if market parameter supplied, add to request as query parameter
         if let Some(market) = market {
-            url_extension.push_str(&format!("&market={}", market));
+            append_query_parameter(&mut url_extension, "market", market.code());
         }
 
         // if limit parameter supplied, add to request as query parameter
         if let Some(limit) = limit {
-            url_extension.push_str(&format!("&limit={}", limit));
+            append_query_parameter(&mut url_extension, "limit", limit);
         }
 
         // if offset parameter supplied, add to request as query parameter
         if let Some(offset) = offset {
-            url_extension.push_str(&format!("&offset={}", offset));
+            append_query_parameter(&mut url_extension, "offset", offset);
         }
 
         let response = self.spotify_request(&url_extension, RequestMethod::Get)?; // make request
@@ -156,6 +251,10 @@ impl Spotify {
     /// * `album_ids` - A vector of Spotify IDs for the albums.
     ///
     pub fn save_albums(&self, album_ids: Vec<&str>) -> Result<(), SpotifyError> {
+        if album_ids.is_empty() {
+            return Ok(()); // nothing to request
+        }
+
         let album_ids_string = album_ids.join(","); // join album ids into string seperated by commas
 
         let url_extension = format!("me/albums?ids={}", album_ids_string); // base url with album ids to add
@@ -187,6 +286,10 @@ impl Spotify {
     /// * `album_ids` - A vector of Spotify IDs for the albums.
     ///
     pub fn remove_albums(&self, album_ids: Vec<&str>) -> Result<(), SpotifyError> {
+        if album_ids.is_empty() {
+            return Ok(()); // nothing to request
+        }
+
         let album_ids_string = album_ids.join(","); // join album ids into string seperated by commas
 
         let url_extension = format!("me/albums?ids={}", album_ids_string); // base url with album ids to remove
@@ -218,6 +321,10 @@ impl Spotify {
     /// * `album_ids` - A vector of Spotify IDs for the albums.
     ///
     pub fn check_saved_albums(&self, album_ids: Vec<&str>) -> Result<Vec<bool>, SpotifyError> {
+        if album_ids.is_empty() {
+            return Ok(Vec::new()); // nothing to request
+        }
+
         let album_ids_string = album_ids.join(","); // join album ids into string seperated by commas
 
         let url_extension = format!("me/albums/contains?ids={}", album_ids_string); // base url with album ids to check
@@ -254,24 +361,19 @@ impl Spotify {
     ) -> Result<SpotifyCollection<Album>, SpotifyError> {
         let mut url_extension = String::from("browse/new-releases"); // base url
 
-        // if any parameter is supplied, add to request as query parameter
-        if country != None || limit != None || offset != None {
-            url_extension.push_str("?");
-        }
-
         // if country parameter supplied, add to request as query parameter
         if let Some(country) = country {
-            url_extension.push_str(&format!("&country={}", country));
+            append_query_parameter(&mut url_extension, "country", country);
         }
 
         // if limit parameter supplied, add to request as query parameter
         if let Some(limit) = limit {
-            url_extension.push_str(&format!("&limit={}", limit));
+            append_query_parameter(&mut url_extension, "limit", limit);
         }
 
         // if offset parameter supplied, add to request as query parameter
         if let Some(offset) = offset {
-            url_extension.push_str(&format!("&offset={}", offset));
+            append_query_parameter(&mut url_extension, "offset", offset);
         }
 
         let response = self.spotify_request(&url_extension, RequestMethod::Get)?; // make request
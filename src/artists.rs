@@ -1,5 +1,7 @@
-use crate::spotify::{Album, Artist, Spotify, SpotifyCollection, SpotifyError, SpotifyObject};
-use crate::srequest::RequestMethod;
+use crate::spotify::{
+    Album, Artist, Market, Spotify, SpotifyCollection, SpotifyError, SpotifyObject,
+};
+use crate::srequest::{append_query_parameter, RequestMethod};
 
 impl Spotify {
     /// Get information on a single aritst: <https://developer.spotify.com/documentation/web-api/reference/#/operations/get-an-artist>
@@ -28,6 +30,10 @@ impl Spotify {
         &self,
         artist_ids: Vec<&str>,
     ) -> Result<Vec<Artist>, SpotifyError> {
+        if artist_ids.is_empty() {
+            return Ok(Vec::new()); // nothing to request
+        }
+
         let url_extension = format!("artists/?ids={}", artist_ids.join(",")); // base url with artist ids added
 
         let response = self.spotify_request(&url_extension, RequestMethod::Get)?; // make request
@@ -46,7 +52,7 @@ impl Spotify {
     /// # Arguments
     /// * `artist_id` - The Spotify ID of the artist.
     /// * `include_groups` - A list of keywords that will be used to filter the response. If not supplied, all album types will be returned. Valid values: album, single, appears_on, compilation.
-    /// * `market` - An ISO 3166-1 alpha-2 country code.
+    /// * `market` - An ISO 3166-1 alpha-2 country code, or `Market::FromToken` for the current user's market.
     /// * `limit` - The maximum number of items to return. Default: 20. Minimum: 1. Maximum: 50.
     /// * `offset` - The index of the first item to return. Default: 0 (the first object). Use with limit to get the next set of items.
     ///
@@ -55,34 +61,33 @@ impl Spotify {
         artist_id: &str,
         include_groups: Option<Vec<&str>>,
         limit: Option<u32>,
-        market: Option<&str>,
+        market: Option<Market>,
         offset: Option<u32>,
     ) -> Result<SpotifyCollection<Album>, SpotifyError> {
         let mut url_extension = format!("artists/{}/albums", artist_id); // base url
 
-        if include_groups != None || limit != None || market != None || offset != None {
-            // if any optional parameters are set, add query question mark
-            url_extension.push_str("?");
-        }
-
         if let Some(include_groups) = include_groups {
             // if include_groups is set, add to url
-            url_extension.push_str(&format!("&include_groups={}", include_groups.join(",")));
+            append_query_parameter(
+                &mut url_extension,
+                "include_groups",
+                include_groups.join(","),
+            );
         }
 
         if let Some(limit) = limit {
             // if limit is set, add to url
-            url_extension.push_str(&format!("&limit={}", limit));
+            append_query_parameter(&mut url_extension, "limit", limit);
         }
 
         if let Some(market) = market {
             // if market is set, add to url
-            url_extension.push_str(&format!("&market={}", market));
+            append_query_parameter(&mut url_extension, "market", market.code());
         }
 
         if let Some(offset) = offset {
             // if offset is set, add to url
-            url_extension.push_str(&format!("&offset={}", offset));
+            append_query_parameter(&mut url_extension, "offset", offset);
         }
 
         let response = self.spotify_request(&url_extension, RequestMethod::Get)?; // make request
@@ -96,14 +101,14 @@ impl Spotify {
     ///
     /// # Arguments
     /// * `artist_id` - The Spotify ID of the artist.
-    /// * `market` - An ISO 3166-1 alpha-2 country code.
+    /// * `market` - An ISO 3166-1 alpha-2 country code, or `Market::FromToken` for the current user's market.
     ///
     pub fn get_artist_top_tracks(
         &self,
         artist_id: &str,
-        market: &str,
+        market: Market,
     ) -> Result<Vec<Artist>, SpotifyError> {
-        let url_extension = format!("artists/{}/top-tracks?market={}", artist_id, market); // base url
+        let url_extension = format!("artists/{}/top-tracks?market={}", artist_id, market.code()); // base url
 
         let response = self.spotify_request(&url_extension, RequestMethod::Get)?; // make request
 
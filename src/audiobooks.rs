@@ -0,0 +1,152 @@
+use crate::spotify::{
+    Audiobook, Chapter, Market, Spotify, SpotifyCollection, SpotifyError, SpotifyObject,
+};
+use crate::srequest::{append_query_parameter, RequestMethod};
+use json::JsonValue::Null;
+
+impl Spotify {
+    /// Get an audiobook: <https://developer.spotify.com/documentation/web-api/reference/#/operations/get-an-audiobook>
+    /// Note: audiobooks are only available in certain markets. If the audiobook isn't available a `SpotifyError::NotFound` is returned
+    ///
+    /// Required scope: none
+    ///
+    /// # Arguments
+    /// * `audiobook_id` - The Spotify ID of the audiobook.
+    /// * `market` - An ISO 3166-1 alpha-2 country code, or `Market::FromToken` for the current user's market.
+    ///
+    pub fn get_audiobook(
+        &self,
+        audiobook_id: &str,
+        market: Option<Market>,
+    ) -> Result<Audiobook, SpotifyError> {
+        let mut url_extension = format!("audiobooks/{}", audiobook_id); // base url
+
+        // if market parameter supplied, add to request as query parameter
+        if let Some(market) = market {
+            append_query_parameter(&mut url_extension, "market", market.code());
+        }
+
+        let response = self.spotify_request(&url_extension, RequestMethod::Get)?; // make request
+
+        if response["id"].is_null() {
+            // no audiobook returned, assume it isn't available
+            return Err(SpotifyError::NotFound(format!(
+                "Audiobook {} is not available",
+                audiobook_id
+            )));
+        }
+
+        return Ok(Audiobook::new(&response)); // format and return result
+    }
+
+    /// Get several audiobooks: <https://developer.spotify.com/documentation/web-api/reference/#/operations/get-multiple-audiobooks>
+    ///
+    /// Required scope: none
+    ///
+    /// # Arguments
+    /// * `audiobook_ids` - A vector of Spotify IDs for the audiobooks. Maximum: 50 IDs.
+    /// * `market` - An ISO 3166-1 alpha-2 country code, or `Market::FromToken` for the current user's market.
+    ///
+    /// Returns a vector in the same order as `audiobook_ids`, with None for audiobooks that aren't available.
+    ///
+    pub fn get_several_audiobooks(
+        &self,
+        audiobook_ids: Vec<&str>,
+        market: Option<Market>,
+    ) -> Result<Vec<Option<Audiobook>>, SpotifyError> {
+        if audiobook_ids.is_empty() {
+            return Ok(Vec::new()); // nothing to request
+        }
+
+        let mut url_extension = format!("audiobooks?ids={}", audiobook_ids.join(",")); // base url
+
+        // if market parameter supplied, add to request as query parameter
+        if let Some(market) = market {
+            append_query_parameter(&mut url_extension, "market", market.code());
+        }
+
+        let response = self.spotify_request(&url_extension, RequestMethod::Get)?; // make request
+
+        let mut audiobooks = Vec::new(); // create vector to store audiobooks
+        for audiobook in response["audiobooks"].members() {
+            match audiobook {
+                Null => audiobooks.push(None), // unavailable audiobooks are returned as null
+                _ => audiobooks.push(Some(Audiobook::new(audiobook))),
+            }
+        }
+        return Ok(audiobooks); // return vector of audiobooks
+    }
+
+    /// Get an audiobook's chapters: <https://developer.spotify.com/documentation/web-api/reference/#/operations/get-audiobook-chapters>
+    ///
+    /// Required scope: none
+    ///
+    /// # Arguments
+    /// * `audiobook_id` - The Spotify ID of the audiobook.
+    /// * `market` - An ISO 3166-1 alpha-2 country code, or `Market::FromToken` for the current user's market.
+    /// * `limit` - The maximum number of chapters to return. Default: 20. Minimum: 1. Maximum: 50.
+    /// * `offset` - The index of the first chapter to return. Default: 0 (the first object). Use with limit to get the next set of chapters.
+    ///
+    pub fn get_audiobook_chapters(
+        &self,
+        audiobook_id: &str,
+        market: Option<Market>,
+        limit: Option<u32>,
+        offset: Option<u32>,
+    ) -> Result<SpotifyCollection<Chapter>, SpotifyError> {
+        let mut url_extension = format!("audiobooks/{}/chapters", audiobook_id); // base url
+
+        // if market parameter supplied, add to request as query parameter
+        if let Some(market) = market {
+            append_query_parameter(&mut url_extension, "market", market.code());
+        }
+
+        // if limit parameter supplied, add to request as query parameter
+        if let Some(limit) = limit {
+            append_query_parameter(&mut url_extension, "limit", limit);
+        }
+
+        // if offset parameter supplied, add to request as query parameter
+        if let Some(offset) = offset {
+            append_query_parameter(&mut url_extension, "offset", offset);
+        }
+
+        let response = self.spotify_request(&url_extension, RequestMethod::Get)?; // make request
+
+        return Ok(SpotifyCollection::<Chapter>::new(&response)); // format and return result
+    }
+
+    /// Get a chapter of an audiobook: <https://developer.spotify.com/documentation/web-api/reference/#/operations/get-a-chapter>
+    /// Note: chapters are only available in certain markets. If the chapter isn't available a `SpotifyError::NotFound` is returned
+    ///
+    /// Required scope: none
+    ///
+    /// # Arguments
+    /// * `chapter_id` - The Spotify ID of the chapter.
+    /// * `market` - An ISO 3166-1 alpha-2 country code, or `Market::FromToken` for the current user's market.
+    ///
+    pub fn get_chapter(
+        &self,
+        chapter_id: &str,
+        market: Option<Market>,
+    ) -> Result<Chapter, SpotifyError> {
+        let mut url_extension = format!("chapters/{}", chapter_id); // base url
+
+        // if market parameter supplied, add to request as query parameter
+        if let Some(market) = market {
+            append_query_parameter(&mut url_extension, "market", market.code());
+        }
+
+        let response = self.spotify_request(&url_extension, RequestMethod::Get)?; // make request
+
+        if response["id"].is_null() {
+            // no chapter returned, assume it isn't available
+            return Err(SpotifyError::NotFound(format!(
+                "Chapter {} is not available",
+                chapter_id
+            )));
+        }
+
+        return Ok(Chapter::new(&response)); // format and return result
+    }
+}
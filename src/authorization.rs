@@ -1,4 +1,5 @@
 use crate::spotify::SpotifyError;
+use crate::srequest::redact_url;
 use base64;
 use getrandom;
 use json;
@@ -8,11 +9,22 @@ use random_string;
 use reqwest;
 use sha2::{Digest, Sha256};
 use std::{
-    io::{prelude::*, BufReader},
+    io::{self, prelude::*, BufReader},
     net::{TcpListener, TcpStream},
+    thread,
+    time::{Duration, Instant},
 };
 use urlencoding::encode;
 
+/// Base url of the Spotify accounts service, which issues and refreshes tokens
+pub(crate) const SPOTIFY_ACCOUNTS_URL: &str = "https://accounts.spotify.com";
+
+/// Default time the user has to complete authorization in the browser
+pub(crate) const DEFAULT_AUTH_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// How often the authorization listener checks for a new connection
+const AUTH_POLL_INTERVAL: Duration = Duration::from_millis(50);
+
 // html to show when authorization is successful
 const AUTHORIZATION_SUCCESSFUL_HTML: &str = r###"<!DOCTYPE html>
 <html lang="en">
@@ -91,6 +103,8 @@ pub fn requesturl_authorization_code(
 /// * `redirect_uri` - redirect_uri for request. Should be: http://localhost:{localhost_port}/callback
 /// * `scope` - scope of permissions for the request. See [Spotify docs](https://developer.spotify.com/documentation/general/guides/scopes/) for more info
 /// * `code_challenge` - code challenge for PKCE. See [Spotify docs](https://developer.spotify.com/documentation/general/guides/authorization-guide/#authorization-code-flow-with-proof-key-for-code-exchange-pkce) for more info
+/// * `timeout` - how long to wait for the user to complete authorization in the browser
+/// * `success_page` - HTML shown in the browser once authorization succeeded. Default: a built-in success page
 ///
 /// # Panics
 /// When browser fails to open authentication url
@@ -101,6 +115,8 @@ pub fn get_authorization_code(
     redirect_uri: &str,
     scope: &str,
     code_challenge: &str,
+    timeout: Duration,
+    success_page: Option<&str>,
 ) -> Result<String, Box<dyn std::error::Error>> {
     // get request url for authorization code
     let (auth_url, state) =
@@ -113,7 +129,7 @@ pub fn get_authorization_code(
     }
 
     // listen for authorization code from redirect uri and parse option result
-    return listen_for_auth_code(localhost_port, &state);
+    return listen_for_auth_code(localhost_port, &state, timeout, success_page);
 }
 
 /// Listens on specified port for the authorization code utilizing `handle_connection()`. This is a modified version of code
@@ -123,28 +139,66 @@ pub fn get_authorization_code(
 ///
 /// * `port` - The port to listen on
 /// * `state` - The state variable used in authorization request (used to authenticate authorization code)
+/// * `timeout` - How long to wait for the authorization code before giving up
+/// * `success_page` - HTML shown in the browser once authorization succeeded. Default: a built-in success page
 ///
 /// # Panics
 /// On any authorization error.
 ///
-fn listen_for_auth_code(port: &str, state: &str) -> Result<String, Box<dyn std::error::Error>> {
+fn listen_for_auth_code(
+    port: &str,
+    state: &str,
+    timeout: Duration,
+    success_page: Option<&str>,
+) -> Result<String, Box<dyn std::error::Error>> {
     let listener = TcpListener::bind(String::from("127.0.0.1:") + &port).unwrap(); // listen on specified port for localhost
 
+    return accept_auth_code(&listener, state, timeout, success_page);
+}
+
+/// Accepts connections on the listener until one carries the authorization code or the timeout passes. Browsers often
+/// send other requests (i.e. for `/favicon.ico`) before or alongside the callback, those are skipped.
+///
+/// # Arguments
+/// * `listener` - The listener the redirect uri points at
+/// * `state` - The state variable used in authorization request (used to authenticate authorization code)
+/// * `timeout` - How long to wait for the authorization code before giving up
+/// * `success_page` - HTML shown in the browser once authorization succeeded. Default: a built-in success page
+///
+fn accept_auth_code(
+    listener: &TcpListener,
+    state: &str,
+    timeout: Duration,
+    success_page: Option<&str>,
+) -> Result<String, Box<dyn std::error::Error>> {
+    let deadline = Instant::now() + timeout;
+    listener.set_nonblocking(true)?; // poll so the deadline can be checked between connections
+
     // on connection, process information for auth code
-    for stream in listener.incoming() {
-        let stream = stream.unwrap();
+    loop {
+        let stream = match listener.accept() {
+            Ok((stream, _)) => stream,
+            Err(e) if e.kind() == io::ErrorKind::WouldBlock => {
+                if Instant::now() >= deadline {
+                    return Err("authentication timed out".into()); // user never completed authorization
+                }
+                thread::sleep(AUTH_POLL_INTERVAL); // no connection yet
+                continue;
+            }
+            Err(e) => return Err(e.into()),
+        };
+        stream.set_nonblocking(false)?; // the connection itself is read blocking
 
-        let auth_code = handle_connection(stream, &state); // handle connection and get auth code
+        let auth_code = handle_connection(stream, &state, success_page); // handle connection and get auth code
 
         match auth_code {
             Some(result) => match result {
                 Ok(code) => return Ok(code),
                 Err(e) => return Err(e),
             },
-            None => continue,
+            None => continue, // not the callback (i.e. the browser asking for /favicon.ico), keep accepting until it arrives
         }
     }
-    Err("Failed to find authorization code.".into())
 }
 
 /// Handles connection to localhost port to do error handling/detection and state validation. Returns authorization code.
@@ -154,60 +208,69 @@ fn listen_for_auth_code(port: &str, state: &str) -> Result<String, Box<dyn std::
 ///
 /// * `stream` - TcpStream object to handle connection
 /// * `state` - the state string used in the authorization request
+/// * `success_page` - HTML to respond with once the authorization code is received. Default: a built-in success page
 ///
-/// # Panics
-/// * When http request parsing is unsuccessful
-/// * On error surrounding sending success webpage to user
+/// Returns None for requests other than the callback (i.e. probes or `/favicon.ico`), and an error for a callback
+/// without a valid state or authorization code.
 ///
 fn handle_connection(
     mut stream: TcpStream,
     state: &str,
+    success_page: Option<&str>,
 ) -> Option<Result<String, Box<dyn std::error::Error>>> {
     let buf_reader = BufReader::new(&mut stream);
 
     // read information from HTTP request and break into lines
-    let http_request = buf_reader.lines().next().unwrap().unwrap(); // Get request line from HTTP request
-
-    let http_request_len = http_request.len(); // get length of http request
-
-    // look for expected request
-    if &http_request[0..13] == "GET /callback"
-        && &http_request[(http_request_len - 9)..] == " HTTP/1.1"
-    {
-        let query = querify(&http_request[14..http_request_len - 9]); // get query parameters from request (from 14 to remove "GET /callback?" and to -9 to remove "HTTP/1.1")
-
-        // check if state matches expected state
-        if query[1].0 == "state" && query[1].1 == state {
-            // check if authorization code is present
-            if query[0].0 == "code" {
-                let authorization_code = String::from(query[0].1); // get authorization code
-
-                let status_line = "HTTP/1.1 200 OK"; // status line for success response
-                let contents = AUTHORIZATION_SUCCESSFUL_HTML.to_string(); // read html file to display to user
-                let content_length = contents.len();
-
-                // create response
-                let response =
-                    format!("{status_line}\r\nContent-Length: {content_length}\r\n\r\n{contents}");
-
-                stream.write_all(response.as_bytes()).unwrap(); // write response to stream
-
-                return Some(Ok(authorization_code)); // return authorization code
-            } else if query[0].0 == "error" {
-                return Some(Err(format!("Authorization error: {}", query[0].1).into()));
-            // return authorization error
-            } else {
-                return Some(Err("Authorization error".into())); // on no code or error present, just error
-            }
-        } else {
+    let http_request = match buf_reader.lines().next() {
+        Some(Ok(http_request)) => http_request, // Get request line from HTTP request
+        _ => return None, // connection closed or unreadable, not the expected request
+    };
+
+    // look for expected request, i.e. "GET /callback?code=...&state=... HTTP/1.1"
+    let query_string = http_request
+        .strip_prefix("GET /callback?")
+        .and_then(|request| request.strip_suffix(" HTTP/1.1"))?; // return None if request is not expected
+
+    let query = querify(query_string); // get query parameters from request
+    let parameter = |name: &str| {
+        query
+            .iter()
+            .find(|(key, _)| *key == name)
+            .map(|(_, value)| *value)
+    };
+
+    // check if state matches expected state
+    match parameter("state") {
+        Some(received_state) if received_state == state => (),
+        Some(received_state) => {
             return Some(Err(format!(
                 "Invalid state. Expected {} got {}. Authorization failed",
-                state, query[1].1
+                state, received_state
             )
             .into())); // on invalid state, invalidate authorization
         }
+        None => return Some(Err("Missing state. Authorization failed".into())),
+    }
+
+    // check if authorization code is present
+    if let Some(authorization_code) = parameter("code") {
+        let authorization_code = String::from(authorization_code); // get authorization code
+
+        let status_line = "HTTP/1.1 200 OK"; // status line for success response
+        let contents = success_page.unwrap_or(AUTHORIZATION_SUCCESSFUL_HTML); // html to display to user
+        let content_length = contents.len();
+
+        // create response
+        let response =
+            format!("{status_line}\r\nContent-Length: {content_length}\r\n\r\n{contents}");
+
+        let _ = stream.write_all(response.as_bytes()); // write response to stream. The code is valid even if the browser already closed the connection
+
+        return Some(Ok(authorization_code)); // return authorization code
+    } else if let Some(error) = parameter("error") {
+        return Some(Err(format!("Authorization error: {}", error).into())); // return authorization error
     } else {
-        return None; // return None if request is not expected
+        return Some(Err("Authorization error".into())); // on no code or error present, just error
     }
 }
 
@@ -246,11 +309,19 @@ pub fn get_access_token(
 
     let query_string = stringify(query_parameters); // stringify query parameters
 
+    let request_url = String::from(request_uri) + &query_string;
+    let started = Instant::now();
     let response = client
-        .post(String::from(request_uri) + &query_string)
+        .post(&request_url)
         .header("Content-Type", "application/x-www-form-urlencoded") // set Content-Type header
         .header("Content-Length", "0") // set Content-Length header
         .send()?; // send request
+    log::debug!(
+        "POST {} -> {} ({} ms)",
+        redact_url(&request_url),
+        response.status().as_u16(),
+        started.elapsed().as_millis()
+    ); // authorization code and code verifier are redacted
 
     if response.status().is_success() {
         // check if response is successful
@@ -263,21 +334,29 @@ pub fn get_access_token(
 
         return Ok((access_token, refresh_token, expires_in)); // return access token, refresh token, and expires in
     } else {
-        return Err(format!("Error: {}", response.status()).into()); // return error if response is not successful
+        let response_code = response.status().as_u16(); // get response code
+
+        // return error if response is not successful
+        return Err(Box::new(process_token_error(
+            response_code,
+            &response.text().unwrap_or_default(),
+        )));
     }
 }
 
-/// Requests new refresh token from Spotify API. Returns new refresh token and time until it expires
+/// Requests new refresh token from Spotify API. Returns new refresh token and time until it expires, along with the granted scope if Spotify sent one
 ///
 /// # Arguments
+/// * `accounts_url` - Base url of the accounts service, normally `https://accounts.spotify.com`
 /// * `refresh_token` - The refresh token used to request a new refresh token
 /// * `client_id` - The client id of the application
 ///
 pub fn refresh_access_token(
+    accounts_url: &str,
     refresh_token: &str,
     client_id: &str,
-) -> Result<(String, i64, String), SpotifyError> {
-    let request_uri = "https://accounts.spotify.com/api/token?"; // token request uri
+) -> Result<(String, i64, String, Option<String>), SpotifyError> {
+    let request_uri = format!("{}/api/token?", accounts_url); // token request uri
 
     let client = reqwest::blocking::Client::new();
 
@@ -289,12 +368,19 @@ pub fn refresh_access_token(
 
     let query_string = stringify(query_parameters); // stringify query parameters
 
+    let request_url = request_uri + &query_string;
+    let started = Instant::now();
     let response = client
-        .post(String::from(request_uri) + &query_string)
+        .post(&request_url)
         .header("Content-Type", "application/x-www-form-urlencoded") // set Content-Type header
         .header("Content-Length", "0") // set Content-Length header
-        .send()
-        .unwrap(); // send request
+        .send()?; // send request, fails i.e. without network connection
+    log::debug!(
+        "POST {} -> {} ({} ms)",
+        redact_url(&request_url),
+        response.status().as_u16(),
+        started.elapsed().as_millis()
+    ); // refresh token is redacted
 
     if response.status().is_success() {
         // check if response is successful
@@ -308,32 +394,159 @@ pub fn refresh_access_token(
             json::JsonValue::Null => refresh_token.to_string(),
             _ => response_body["refresh_token"].to_string(),
         };
+        let scope = match response_body["scope"].as_str() {
+            Some(scope) => Some(scope.to_string()), // scope actually granted, narrower if the user revoked some
+            None => None,
+        };
 
-        return Ok((access_token, expires_in, new_refresh_token)); // return access token and expires in and new refresh token
+        return Ok((access_token, expires_in, new_refresh_token, scope)); // return access token, expires in, new refresh token and granted scope
     } else {
         let response_code = response.status().as_u16(); // get response code
 
-        let response_body = json::parse(&response.text().unwrap()).unwrap(); // get response as json
+        return Err(process_token_error(
+            response_code,
+            &response.text().unwrap_or_default(),
+        ));
+    }
+}
 
-        match response_code {
-            400 => {
-                return Err(SpotifyError::BadRequest(format!(
-                    "Error {}: {}",
-                    response_code, response_body["error_description"]
-                )))
-            }
-            401 => {
-                return Err(SpotifyError::Unauthorized(format!(
-                    "Error {}: {}",
-                    response_code, response_body["error_description"]
-                )))
+/// Turns an error response from the token endpoint (`{"error": ..., "error_description": ...}`) into a SpotifyError.
+/// An `invalid_grant` (expired or revoked authorization code or refresh token) or `invalid_client` error means retrying
+/// won't help, so these become an `AuthenticationError` telling the user to authenticate again.
+///
+/// # Arguments
+/// * `response_code` - The http status code of the response
+/// * `response_body` - The body of the response
+///
+fn process_token_error(response_code: u16, response_body: &str) -> SpotifyError {
+    let response_body = json::parse(response_body).unwrap_or(json::JsonValue::Null); // body may not be json
+
+    let description = match response_body["error_description"].as_str() {
+        Some(description) => description.to_string(),
+        None => String::from("no description"), // default to no description
+    };
+
+    match response_body["error"].as_str() {
+        Some("invalid_grant") => SpotifyError::AuthenticationError(format!(
+            "Invalid grant ({}). The authorization code or refresh token is expired or revoked, authenticate again",
+            description
+        )),
+        Some("invalid_client") => SpotifyError::AuthenticationError(format!(
+            "Invalid client ({}). Check the client id, then authenticate again",
+            description
+        )),
+        _ => match response_code {
+            400 => SpotifyError::BadRequest(format!("Error {}: {}", response_code, description)),
+            401 => SpotifyError::Unauthorized(format!("Error {}: {}", response_code, description)),
+            _ => SpotifyError::GeneralError(format!("Error: {}", response_code)),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Sends a raw request to a local listener and hands the server side of the connection to `handle_connection`
+    fn handle_raw_request(request: &str) -> Option<Result<String, Box<dyn std::error::Error>>> {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap(); // let the os pick a free port
+        let mut client = TcpStream::connect(listener.local_addr().unwrap()).unwrap();
+        client.write_all(request.as_bytes()).unwrap();
+        client.shutdown(std::net::Shutdown::Write).unwrap(); // nothing more to send
+
+        let (stream, _) = listener.accept().unwrap();
+        handle_connection(stream, "state", None)
+    }
+
+    #[test]
+    fn malformed_callback_requests() {
+        assert!(handle_raw_request("GET /\r\n\r\n").is_none()); // shorter than the expected prefix
+        assert!(handle_raw_request("").is_none());
+        assert!(handle_raw_request("GET /callback?code=abc HTTP/1.0\r\n\r\n").is_none());
+
+        let missing_state = handle_raw_request("GET /callback?code=abc HTTP/1.1\r\n\r\n");
+        assert!(matches!(missing_state, Some(Err(_))));
+
+        let wrong_state = handle_raw_request("GET /callback?code=abc&state=other HTTP/1.1\r\n\r\n");
+        assert!(matches!(wrong_state, Some(Err(_))));
+
+        let code = handle_raw_request("GET /callback?state=state&code=abc HTTP/1.1\r\n\r\n");
+        assert_eq!(code.unwrap().unwrap(), "abc");
+    }
+
+    #[test]
+    fn auth_listener_skips_favicon_requests() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap(); // let the os pick a free port
+        let address = listener.local_addr().unwrap();
+
+        let browser = thread::spawn(move || {
+            for request in [
+                "GET /favicon.ico HTTP/1.1\r\n\r\n",
+                "GET /callback?code=abc&state=state HTTP/1.1\r\n\r\n",
+            ] {
+                let mut connection = TcpStream::connect(address).unwrap();
+                connection.write_all(request.as_bytes()).unwrap();
+                let mut response = String::new();
+                let _ = connection.read_to_string(&mut response); // wait for the listener to be done with it
             }
-            _ => {
-                return Err(SpotifyError::GeneralError(format!(
-                    "Error: {}",
-                    response_code
-                )))
+        });
+
+        let code = accept_auth_code(&listener, "state", Duration::from_secs(5), None).unwrap();
+        browser.join().unwrap();
+
+        assert_eq!(code, "abc");
+    }
+
+    #[test]
+    fn custom_success_page() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap(); // let the os pick a free port
+        let mut client = TcpStream::connect(listener.local_addr().unwrap()).unwrap();
+        client
+            .write_all(b"GET /callback?code=abc&state=state HTTP/1.1\r\n\r\n")
+            .unwrap();
+
+        let (stream, _) = listener.accept().unwrap();
+        let code = handle_connection(stream, "state", Some("<h1>Welcome to my app</h1>"));
+        assert_eq!(code.unwrap().unwrap(), "abc");
+
+        let mut response = String::new();
+        client.read_to_string(&mut response).unwrap();
+        assert!(response.starts_with("HTTP/1.1 200 OK"));
+        assert!(response.ends_with("\r\n\r\n<h1>Welcome to my app</h1>"));
+    }
+
+    #[test]
+    fn auth_listener_times_out() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap(); // let the os pick a free port
+        let started = Instant::now();
+
+        let result = accept_auth_code(&listener, "state", Duration::from_millis(200), None);
+
+        assert_eq!(result.unwrap_err().to_string(), "authentication timed out");
+        assert!(started.elapsed() >= Duration::from_millis(200));
+        assert!(started.elapsed() < Duration::from_secs(5));
+    }
+
+    #[test]
+    fn token_error_parsing() {
+        let error = process_token_error(
+            400,
+            r#"{"error": "invalid_grant", "error_description": "Refresh token revoked"}"#,
+        );
+        match error {
+            SpotifyError::AuthenticationError(message) => {
+                assert!(message.contains("Refresh token revoked"))
             }
+            error => panic!("expected authentication error, got {:?}", error),
         }
+
+        let error = process_token_error(
+            400,
+            r#"{"error": "invalid_request", "error_description": "code_verifier was incorrect"}"#,
+        );
+        assert!(matches!(error, SpotifyError::BadRequest(_)));
+
+        let error = process_token_error(503, "<html>Service unavailable</html>");
+        assert!(matches!(error, SpotifyError::GeneralError(_)));
     }
 }
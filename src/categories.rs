@@ -1,5 +1,5 @@
 use crate::spotify::{Category, Spotify, SpotifyCollection, SpotifyError, SpotifyObject};
-use crate::srequest::RequestMethod;
+use crate::srequest::{append_query_parameter, RequestMethod};
 
 impl Spotify {
     /// Get a set of categories used to tag items in Spotify: <https://developer.spotify.com/documentation/web-api/reference/#/operations/get-categories>
@@ -13,7 +13,7 @@ impl Spotify {
     /// * `offset` - The index of the first category to return. Default: 0 (the first object). Use with limit to get the next set of categories.
     ///
     pub fn get_several_browse_categories(
-        &mut self,
+        &self,
         country: Option<&str>,
         locale: Option<&str>,
         limit: Option<i32>,
@@ -21,25 +21,20 @@ impl Spotify {
     ) -> Result<SpotifyCollection<Category>, SpotifyError> {
         let mut url_extension = String::from("browse/categories"); // base url
 
-        if !country.is_none() || !locale.is_none() || !limit.is_none() || !offset.is_none() {
-            // if any optional arguments are present
-            url_extension.push_str("?"); // add ? to url
-        }
-
         if let Some(country) = country {
-            url_extension.push_str(&format!("country={}&", country)); // add country to url
+            append_query_parameter(&mut url_extension, "country", country); // add country to url
         }
 
         if let Some(locale) = locale {
-            url_extension.push_str(&format!("locale={}&", locale)); // add locale to url
+            append_query_parameter(&mut url_extension, "locale", locale); // add locale to url
         }
 
         if let Some(limit) = limit {
-            url_extension.push_str(&format!("limit={}&", limit)); // add limit to url
+            append_query_parameter(&mut url_extension, "limit", limit); // add limit to url
         }
 
         if let Some(offset) = offset {
-            url_extension.push_str(&format!("offset={}&", offset)); // add offset to url
+            append_query_parameter(&mut url_extension, "offset", offset); // add offset to url
         }
 
         let response = self.spotify_request(&url_extension, RequestMethod::Get)?; // send request
@@ -55,26 +50,46 @@ impl Spotify {
     /// * `category_id` - The Spotify category ID for the category.
     /// * `country` - An ISO 3166-1 alpha-2 country code.
     /// * `locale` - The desired language, consisting of an ISO 639 language code and an ISO 3166-1 alpha-2 country code, joined by an underscore.
+    /// * `fallback_to_default_locale` - If Spotify doesn't have the category in `locale` (404 or 400), request it again without a locale, i.e. in Spotify's default language (American English).
     ///
     pub fn get_single_browse_category(
-        &mut self,
+        &self,
         category_id: &str,
         country: Option<&str>,
         locale: Option<&str>,
+        fallback_to_default_locale: bool,
     ) -> Result<Category, SpotifyError> {
-        let mut url_extension = format!("browse/categories/{}", category_id); // base url
-
-        if !country.is_none() || !locale.is_none() {
-            // if any optional arguments are present
-            url_extension.push_str("?"); // add ? to url
+        match self.browse_category_request(category_id, country, locale) {
+            Err(SpotifyError::NotFound(_) | SpotifyError::MalformedRequest(_))
+                if fallback_to_default_locale && locale.is_some() =>
+            {
+                self.browse_category_request(category_id, country, None) // retry in default locale
+            }
+            result => result,
         }
+    }
+
+    /// Requests a single Spotify category in the given locale
+    ///
+    /// # Arguments
+    /// * `category_id` - The Spotify category ID for the category.
+    /// * `country` - An ISO 3166-1 alpha-2 country code.
+    /// * `locale` - The desired language. None for Spotify's default language.
+    ///
+    fn browse_category_request(
+        &self,
+        category_id: &str,
+        country: Option<&str>,
+        locale: Option<&str>,
+    ) -> Result<Category, SpotifyError> {
+        let mut url_extension = format!("browse/categories/{}", category_id); // base url
 
         if let Some(country) = country {
-            url_extension.push_str(&format!("country={}&", country)); // add country to url
+            append_query_parameter(&mut url_extension, "country", country); // add country to url
         }
 
         if let Some(locale) = locale {
-            url_extension.push_str(&format!("locale={}&", locale)); // add locale to url
+            append_query_parameter(&mut url_extension, "locale", locale); // add locale to url
         }
 
         let response = self.spotify_request(&url_extension, RequestMethod::Get)?; // send request
@@ -1,6 +1,51 @@
-use crate::spotify::{Spotify, SpotifyError};
+use crate::spotify::{Genre, Spotify, SpotifyError};
 use crate::srequest::RequestMethod;
 
+/// Genres Spotify accepted as recommendation seeds when this list was written (as returned by the available genre seeds endpoint).
+/// Only used as an offline fallback when the genre seeds haven't been fetched, as the live list can change
+#[rustfmt::skip]
+const GENRE_SEEDS: &[&str] = &[
+    "acoustic", "afrobeat", "alt-rock", "alternative", "ambient", "anime", "black-metal",
+    "bluegrass", "blues", "bossanova", "brazil", "breakbeat", "british", "cantopop",
+    "chicago-house", "children", "chill", "classical", "club", "comedy", "country", "dance",
+    "dancehall", "death-metal", "deep-house", "detroit-techno", "disco", "disney",
+    "drum-and-bass", "dub", "dubstep", "edm", "electro", "electronic", "emo", "folk", "forro",
+    "french", "funk", "garage", "german", "gospel", "goth", "grindcore", "groove", "grunge",
+    "guitar", "happy", "hard-rock", "hardcore", "hardstyle", "heavy-metal", "hip-hop",
+    "holidays", "honky-tonk", "house", "idm", "indian", "indie", "indie-pop", "industrial",
+    "iranian", "j-dance", "j-idol", "j-pop", "j-rock", "jazz", "k-pop", "kids", "latin",
+    "latino", "malay", "mandopop", "metal", "metal-misc", "metalcore", "minimal-techno",
+    "movies", "mpb", "new-age", "new-release", "opera", "pagode", "party", "philippines-opm",
+    "piano", "pop", "pop-film", "post-dubstep", "power-pop", "progressive-house", "psych-rock",
+    "punk", "punk-rock", "r-n-b", "rainy-day", "reggae", "reggaeton", "road-trip", "rock",
+    "rock-n-roll", "rockabilly", "romance", "sad", "salsa", "samba", "sertanejo", "show-tunes",
+    "singer-songwriter", "ska", "sleep", "songwriter", "soul", "soundtracks", "spanish", "study",
+    "summer", "swedish", "synth-pop", "tango", "techno", "trance", "trip-hop", "turkish",
+    "work-out", "world-music",
+];
+
+impl TryFrom<&str> for Genre {
+    type Error = SpotifyError;
+
+    /// Creates a genre without making a request, checking it against the built in list of genre seeds.
+    /// This is an offline fallback: the built in list can be out of date, so prefer `Spotify::genre`, which checks against the
+    /// genre seeds Spotify currently returns.
+    ///
+    /// # Arguments
+    /// * `genre` - The name of the genre, i.e. `"hip-hop"`
+    ///
+    fn try_from(genre: &str) -> Result<Genre, SpotifyError> {
+        if GENRE_SEEDS.contains(&genre) {
+            Ok(Genre(genre.to_string()))
+        } else {
+            Err(SpotifyError::InvalidRequest(format!(
+                "{} is not an available genre seed",
+                genre
+            )))
+        }
+    }
+}
+
 impl Spotify {
     /// Gets set of available genres: <https://developer.spotify.com/documentation/web-api/reference/#/operations/get-recommendation-genres>
     ///
@@ -20,4 +65,71 @@ impl Spotify {
 
         Ok(genres)
     }
+
+    /// Gets set of available genres, only requesting them from Spotify the first time. Later calls return the cached list.
+    ///
+    /// Requires scope: none
+    ///
+    pub fn genre_seeds(&self) -> Result<Vec<String>, SpotifyError> {
+        if let Some(genre_seeds) = self.cached_genre_seeds() {
+            return Ok(genre_seeds); // already fetched
+        }
+
+        let genre_seeds = self.get_available_genre_seeds()?;
+        self.cache_genre_seeds(genre_seeds.clone()); // store for later calls
+
+        Ok(genre_seeds)
+    }
+
+    /// Creates a genre, checking that it is one of the available genre seeds. The genre seeds are requested from Spotify the
+    /// first time and cached after that (see `genre_seeds`).
+    ///
+    /// Requires scope: none
+    ///
+    /// # Arguments
+    /// * `genre` - The name of the genre, i.e. `"hip-hop"`
+    ///
+    pub fn genre(&self, genre: &str) -> Result<Genre, SpotifyError> {
+        let genre_seeds = self.genre_seeds()?; // fetched once, then cached
+
+        if genre_seeds.iter().any(|seed| seed == genre) {
+            Ok(Genre(genre.to_string()))
+        } else {
+            Err(SpotifyError::InvalidRequest(format!(
+                "{} is not an available genre seed",
+                genre
+            )))
+        }
+    }
+
+    /// Checks that genres are valid recommendation seeds. Checks against the cached genre seeds if they were already fetched,
+    /// otherwise against the built in list, so this never makes a request. The error names every unknown genre.
+    ///
+    /// # Arguments
+    /// * `genres` - The genres to check
+    ///
+    pub(crate) fn check_genre_seeds(&self, genres: &[&str]) -> Result<(), SpotifyError> {
+        let cached_genre_seeds = self.cached_genre_seeds();
+
+        let unknown_genres: Vec<&str> = genres
+            .iter()
+            .filter(|genre| match &cached_genre_seeds {
+                Some(genre_seeds) => !genre_seeds.iter().any(|seed| seed == *genre),
+                None => !GENRE_SEEDS.contains(genre), // not fetched, fall back to built in list
+            })
+            .copied()
+            .collect();
+
+        match unknown_genres.len() {
+            0 => Ok(()),
+            1 => Err(SpotifyError::InvalidRequest(format!(
+                "{} is not an available genre seed",
+                unknown_genres[0]
+            ))),
+            _ => Err(SpotifyError::InvalidRequest(format!(
+                "{} are not available genre seeds",
+                unknown_genres.join(", ")
+            ))),
+        }
+    }
 }
@@ -0,0 +1,34 @@
+use crate::spotify::SpotifyError;
+
+/// Length of a Spotify ID
+const ID_LENGTH: usize = 22;
+
+/// Checks that a string is a well formed Spotify ID: a 22 character base-62 string (i.e. `6rqhFgbbKwnb9MLmUQDhG6`)
+///
+/// # Arguments
+/// * `id` - The ID to check
+/// * `kind` - What the ID is for, used in the error message (i.e. `"track"`)
+///
+pub(crate) fn check_id(id: &str, kind: &str) -> Result<(), SpotifyError> {
+    if id.len() == ID_LENGTH && id.chars().all(|c| c.is_ascii_alphanumeric()) {
+        Ok(())
+    } else {
+        Err(SpotifyError::InvalidRequest(format!(
+            "{} is not a valid {} id",
+            id, kind
+        )))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::check_id;
+
+    #[test]
+    fn id_format() {
+        assert!(check_id("6rqhFgbbKwnb9MLmUQDhG6", "track").is_ok());
+        assert!(check_id("6rqhFgbbKwnb9MLmUQDhG", "track").is_err()); // too short
+        assert!(check_id("spotify:track:6rqhFgbbKwnb9MLmUQDhG6", "track").is_err()); // uri, not id
+        assert!(check_id("6rqhFgbbKwnb9MLmUQDh-6", "track").is_err()); // not base-62
+    }
+}
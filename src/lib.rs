@@ -51,9 +51,9 @@
 //!     scope,
 //!     code_verifier,
 //!     redirect_uri,
-//! );
+//! )?;
 //! ```
-//! This Spotify object will handle refreshing the access token and is how you will interact with the API.
+//! This fails with `SpotifyError::AuthenticationError` if Spotify rejects the auth code (i.e. it was already used). The Spotify object will handle refreshing the access token and is how you will interact with the API.
 //!
 //! # Examples
 //! We can get information on a specific artist:
@@ -70,28 +70,44 @@
 //!
 //! See the [Spotify struct](struct.Spotify.html) for a full list of supported endpoints.
 //!
+//! # Logging
+//! Every request is logged through the [`log`](https://docs.rs/log) facade at `debug` level: the method, url, status code and how long it took. Retries are logged at `trace` level.
+//! Install any logger (i.e. `env_logger`) to see them. Access tokens, refresh tokens and authorization codes are never logged.
+//!
 
 mod albums;
 mod artists;
+mod audiobooks;
 mod authorization;
 mod categories;
 mod genres;
+mod ids;
+mod links;
 mod markets;
 mod object_formatting;
 mod player;
 mod playlist;
+mod scopes;
+mod search;
+mod shows;
 mod spotify;
 mod srequest;
 mod tracks;
 mod users;
 
+pub use search::SearchStream; // iterator returned by search_iter
 pub use spotify::{
-    Album, AlbumType, AnalysisTrack, Artist, Bar, Beat, Category, DatedAlbum, DatedTrack, Device,
-    ExternalTrackIds, FeatureTrack, Playback, PlaybackActions, PlayedTrack, Playlist,
-    PlaylistTrack, ReleaseDatePrecision, RepeatState, RestrictionReason, Section, Segment, Spotify,
-    SpotifyCollection, SpotifyContext, SpotifyError, SpotifyImage, SpotifyObject, Tatum, TimeRange,
-    Track, User,
+    Album, AlbumType, AnalysisTrack, Artist, Audiobook, Bar, Beat, Category, Chapter, Copyright,
+    CopyrightType, Cursors, DatedAlbum, DatedTrack, Device, Episode, ExternalTrackIds,
+    FeatureSummary, FeatureTrack, Followers, Genre, ItemType, LinkedTrack, Market, PlayableItem,
+    Playback, PlaybackActions, PlaybackOffset, PlayedTrack, Playlist, PlaylistDiff,
+    PlaylistSummary, PlaylistTrack, PlaylistTracks, RecentlyPlayed, RecommendationParams,
+    RecommendationSeed, Recommendations, ReleaseDatePrecision, RepeatState, ResolvedItem,
+    RestrictionReason, Scope, SearchItem, SearchType, Section, Segment, Show, SnapshotId, Spotify,
+    SpotifyBuilder, SpotifyCollection, SpotifyContext, SpotifyError, SpotifyImage, SpotifyObject,
+    Tatum, TimeRange, TopItemKind, Track, User,
 }; // re-export relevant structs and enums
+pub use srequest::RequestMethod; // for making requests to endpoints without a wrapper
 
 // export if manual authentication feature is active
 #[cfg(feature = "manual_auth")]
@@ -0,0 +1,113 @@
+use crate::ids::check_id;
+use crate::spotify::{ResolvedItem, Spotify, SpotifyError};
+
+/// Host of Spotify share links, i.e. `https://open.spotify.com/track/...`
+const SHARE_LINK_HOST: &str = "open.spotify.com";
+
+/// Splits a share link (`https://open.spotify.com/track/{id}?si=...`) or a Spotify URI (`spotify:track:{id}`) into the kind of
+/// object and its id. Query parameters like the `si` tracking parameter are ignored.
+///
+/// # Arguments
+/// * `link` - The share link or URI
+///
+fn parse_link(link: &str) -> Result<(String, String), SpotifyError> {
+    let invalid_link = || SpotifyError::InvalidRequest(format!("{} is not a Spotify link", link));
+
+    let link = link.trim();
+    let link = link.split(['?', '#']).next().unwrap_or(link); // drop query string (i.e. ?si=...) and fragment
+
+    let segments: Vec<&str> = match link.strip_prefix("spotify:") {
+        Some(uri) => uri.split(':').collect(), // spotify:track:{id}
+        None => {
+            let without_scheme = link
+                .strip_prefix("https://")
+                .or_else(|| link.strip_prefix("http://"))
+                .unwrap_or(link);
+            let path = match without_scheme.strip_prefix(SHARE_LINK_HOST) {
+                Some(path) => path,
+                None => return Err(invalid_link()),
+            };
+
+            path.split('/')
+                .filter(|segment| !segment.is_empty())
+                .skip_while(|segment| segment.starts_with("intl-") || *segment == "embed") // i.e. /intl-de/track/{id}
+                .collect()
+        }
+    };
+
+    match segments.as_slice() {
+        [kind, id] | [kind, id, ..] => {
+            match *kind {
+                "track" | "album" | "artist" | "playlist" | "episode" | "show" => (),
+                _ => return Err(invalid_link()), // i.e. a user or genre page
+            }
+            check_id(id, kind)?;
+
+            Ok((kind.to_string(), id.to_string()))
+        }
+        _ => Err(invalid_link()),
+    }
+}
+
+impl Spotify {
+    /// Gets the object a share link (i.e. `https://open.spotify.com/track/6rqhFgbbKwnb9MLmUQDhG6?si=...`) or Spotify URI points to.
+    /// Tracks, albums, artists, playlists, episodes and shows are supported. Shows and episodes are requested in the user's market
+    /// when authenticated as a user, as Spotify leaves out market restricted ones otherwise.
+    ///
+    /// Required scope: none
+    ///
+    /// # Arguments
+    /// * `url` - The share link or URI, as copied from Spotify
+    ///
+    pub fn resolve_link(&self, url: &str) -> Result<ResolvedItem, SpotifyError> {
+        let (kind, id) = parse_link(url)?;
+
+        let not_found = || SpotifyError::NotFound(format!("{} {} is not available", kind, id));
+
+        let item = match kind.as_str() {
+            "track" => ResolvedItem::Track(self.get_track(&id)?),
+            "album" => ResolvedItem::Album(self.get_album(&id, None)?),
+            "artist" => ResolvedItem::Artist(self.get_artist(&id)?),
+            "playlist" => ResolvedItem::Playlist(self.get_playlist(&id, None, None)?),
+            "episode" => match self.get_several_episodes(vec![&id], None)?.pop().flatten() {
+                Some(episode) => ResolvedItem::Episode(episode),
+                None => return Err(not_found()), // unavailable episodes are returned as null
+            },
+            "show" => match self.get_several_shows(vec![&id], None)?.pop().flatten() {
+                Some(show) => ResolvedItem::Show(show),
+                None => return Err(not_found()), // unavailable shows are returned as null
+            },
+            _ => unreachable!("parse_link only returns supported kinds"),
+        };
+
+        return Ok(item);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse_link;
+
+    #[test]
+    fn link_parsing() {
+        let parsed = |link: &str| parse_link(link).map(|(kind, id)| format!("{}:{}", kind, id));
+
+        assert_eq!(
+            parsed("https://open.spotify.com/track/6rqhFgbbKwnb9MLmUQDhG6?si=a1b2c3d4e5f6")
+                .unwrap(),
+            "track:6rqhFgbbKwnb9MLmUQDhG6"
+        );
+        assert_eq!(
+            parsed("open.spotify.com/intl-de/album/6JWc4iAiJ9FjyK0B59ABb4").unwrap(),
+            "album:6JWc4iAiJ9FjyK0B59ABb4"
+        );
+        assert_eq!(
+            parsed("spotify:show:5CfCWKI5pZ28U0uOzXkDHe").unwrap(),
+            "show:5CfCWKI5pZ28U0uOzXkDHe"
+        );
+        assert!(parsed("https://example.com/track/6rqhFgbbKwnb9MLmUQDhG6").is_err()); // not a Spotify link
+        assert!(parsed("https://open.spotify.com/user/smedjan").is_err()); // unsupported kind
+        assert!(parsed("https://open.spotify.com/track/").is_err()); // missing id
+        assert!(parsed("https://open.spotify.com/track/not-an-id").is_err()); // malformed id
+    }
+}
@@ -1,6 +1,47 @@
-use crate::spotify::{Spotify, SpotifyError};
+use crate::spotify::{Market, Spotify, SpotifyError, User};
 use crate::srequest::RequestMethod;
 
+/// Country codes of the markets where Spotify is available (as returned by the available markets endpoint)
+const MARKETS: &[&str] = &[
+    "AD", "AE", "AG", "AL", "AM", "AO", "AR", "AT", "AU", "AZ", "BA", "BB", "BD", "BE", "BF", "BG",
+    "BH", "BI", "BJ", "BN", "BO", "BR", "BS", "BT", "BW", "BY", "BZ", "CA", "CD", "CG", "CH", "CI",
+    "CL", "CM", "CO", "CR", "CV", "CW", "CY", "CZ", "DE", "DJ", "DK", "DM", "DO", "DZ", "EC", "EE",
+    "EG", "ES", "ET", "FI", "FJ", "FM", "FR", "GA", "GB", "GD", "GE", "GH", "GM", "GN", "GQ", "GR",
+    "GT", "GW", "GY", "HK", "HN", "HR", "HT", "HU", "ID", "IE", "IL", "IN", "IQ", "IS", "IT", "JM",
+    "JO", "JP", "KE", "KG", "KH", "KI", "KM", "KN", "KR", "KW", "KZ", "LA", "LB", "LC", "LI", "LK",
+    "LR", "LS", "LT", "LU", "LV", "LY", "MA", "MC", "MD", "ME", "MG", "MH", "MK", "ML", "MN", "MO",
+    "MR", "MT", "MU", "MV", "MW", "MX", "MY", "MZ", "NA", "NE", "NG", "NI", "NL", "NO", "NP", "NR",
+    "NZ", "OM", "PA", "PE", "PG", "PH", "PK", "PL", "PR", "PS", "PT", "PW", "PY", "QA", "RO", "RS",
+    "RW", "SA", "SB", "SC", "SE", "SG", "SI", "SK", "SL", "SM", "SN", "SR", "ST", "SV", "SZ", "TD",
+    "TG", "TH", "TJ", "TL", "TN", "TO", "TR", "TT", "TV", "TW", "TZ", "UA", "UG", "US", "UY", "UZ",
+    "VC", "VE", "VN", "VU", "WS", "XK", "ZA", "ZM", "ZW",
+];
+
+impl TryFrom<&str> for Market {
+    type Error = SpotifyError;
+
+    /// Creates a market from a country code (case insensitive) or `"from_token"`, checking that Spotify is available there
+    ///
+    /// # Arguments
+    /// * `market` - An ISO 3166-1 alpha-2 country code, i.e. `"US"`, or `"from_token"`
+    ///
+    fn try_from(market: &str) -> Result<Market, SpotifyError> {
+        if market == "from_token" {
+            return Ok(Market::FromToken);
+        }
+
+        let code = market.to_uppercase(); // codes are sent uppercase
+        if MARKETS.contains(&code.as_str()) {
+            Ok(Market::Country(code))
+        } else {
+            Err(SpotifyError::InvalidRequest(format!(
+                "{} is not a market where Spotify is available",
+                market
+            )))
+        }
+    }
+}
+
 impl Spotify {
     /// Gets a vector of all markets where Spotify is available: <https://developer.spotify.com/documentation/web-api/reference/#/operations/get-available-markets>
     ///
@@ -20,4 +61,41 @@ impl Spotify {
 
         Ok(markets)
     }
+
+    /// Gets the market of the current user from their profile country. The profile is only requested the first time (<https://developer.spotify.com/documentation/web-api/reference/#/operations/get-current-users-profile>), later calls return the cached market.
+    /// Returns `None` if the profile has no country, which is the case without the user-read-private scope.
+    ///
+    /// Requires scope: user-read-private
+    ///
+    pub fn user_market(&self) -> Result<Option<Market>, SpotifyError> {
+        if let Some(user_market) = self.cached_user_market() {
+            return Ok(user_market); // already fetched
+        }
+
+        let response = self.spotify_request("me", RequestMethod::Get)?; // make request
+        let user = User::new(&response);
+
+        let user_market = match user.country {
+            Some(country) => Some(Market::try_from(country.as_str())?),
+            None => None, // no country without the user-read-private scope
+        };
+        self.cache_user_id(user.id); // profile also carries the user id
+        self.cache_user_market(user_market.clone()); // store for later calls
+
+        Ok(user_market)
+    }
+
+    /// Returns the market to send with a request: the given market, or `Market::FromToken` if none was given and the
+    /// access token belongs to a user. Without a market Spotify leaves out market restricted items.
+    ///
+    /// # Arguments
+    /// * `market` - The market passed by the caller
+    ///
+    pub(crate) fn market_or_user_default(&self, market: Option<Market>) -> Option<Market> {
+        match market {
+            Some(market) => Some(market), // explicit market always wins
+            None if self.is_user_authenticated() => Some(Market::FromToken),
+            None => None,
+        }
+    }
 }
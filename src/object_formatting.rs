@@ -1,11 +1,14 @@
-use chrono::{NaiveDate, NaiveDateTime};
+use chrono::{DateTime, NaiveDate, NaiveDateTime};
 use json::JsonValue::{self, Array, Null};
+use std::collections::HashMap;
 use std::fmt::Debug;
 
 use crate::spotify::{
-    Album, AlbumType, AnalysisTrack, Artist, Bar, Beat, Category, DatedAlbum, DatedTrack, Device,
-    ExternalTrackIds, FeatureTrack, Playback, PlaybackActions, PlayedTrack, Playlist,
-    PlaylistTrack, ReleaseDatePrecision, RepeatState, RestrictionReason, Section, Segment,
+    Album, AlbumType, AnalysisTrack, Artist, Audiobook, Bar, Beat, Category, Chapter, Copyright,
+    CopyrightType, Cursors, DatedAlbum, DatedTrack, Device, Episode, ExternalTrackIds,
+    FeatureTrack, Followers, LinkedTrack, PlayableItem, Playback, PlaybackActions, PlayedTrack,
+    Playlist, PlaylistSummary, PlaylistTrack, RecommendationSeed, Recommendations,
+    ReleaseDatePrecision, RepeatState, RestrictionReason, SearchItem, Section, Segment, Show,
     SpotifyCollection, SpotifyContext, SpotifyError, SpotifyImage, SpotifyObject, Tatum, Track,
     User,
 };
@@ -59,6 +62,98 @@ impl ExternalTrackIds {
     }
 }
 
+impl Followers {
+    /// Takes JsonValue object representing followers and formats it into Followers struct
+    ///
+    /// # Arguments
+    /// * `followers` - JsonValue object representing followers
+    ///
+    fn new(followers: &JsonValue) -> Followers {
+        Followers {
+            href: match followers["href"].as_str() {
+                Some(href) => Some(href.to_string()),
+                None => None,
+            },
+            total: match followers["total"].as_i32() {
+                Some(total) => total,
+                None => 0, // default to 0
+            },
+        }
+    }
+}
+
+impl Copyright {
+    /// Takes JsonValue object representing a copyright statement and formats it into Copyright struct
+    ///
+    /// # Arguments
+    /// * `raw_object` - JsonValue object representing a copyright statement
+    ///
+    fn new(raw_object: &JsonValue) -> Copyright {
+        let text = match raw_object["text"].as_str() {
+            Some(text) => text,
+            None => "", // default to empty string
+        };
+
+        let copyright_type = match raw_object["type"].as_str() {
+            Some("P") => CopyrightType::Performance,
+            Some(_) => CopyrightType::Copyright, // default to copyright
+            None => CopyrightType::Copyright,    // default to copyright
+        };
+
+        Copyright {
+            text: text.to_string(),
+            copyright_type,
+        }
+    }
+}
+
+/// Collects the external urls of an object (i.e. `{"spotify": "https://open.spotify.com/..."}`) into a map from url type to url
+///
+/// # Arguments
+/// * `external_urls` - JsonValue object holding the external urls
+///
+fn format_external_urls(external_urls: &JsonValue) -> HashMap<String, String> {
+    external_urls
+        .entries()
+        .filter_map(|(key, url)| url.as_str().map(|url| (key.to_string(), url.to_string()))) // skip non string values
+        .collect()
+}
+
+/// Parses a release date string based on its precision. Returns None if the date is missing or unable to be parsed
+///
+/// # Arguments
+/// * `date_string` - JsonValue holding the release date string
+/// * `precision` - The precision with which the release date is known
+///
+fn format_release_date(
+    date_string: &JsonValue,
+    precision: &ReleaseDatePrecision,
+) -> Option<NaiveDate> {
+    if date_string.is_null() {
+        return None; // default to no date
+    }
+
+    let date_string_temp = match precision {
+        ReleaseDatePrecision::Year => {
+            Some(NaiveDate::parse_from_str(&date_string.to_string(), "%Y"))
+        }
+        ReleaseDatePrecision::Month => {
+            Some(NaiveDate::parse_from_str(&date_string.to_string(), "%Y-%m"))
+        }
+        ReleaseDatePrecision::Day => Some(NaiveDate::parse_from_str(
+            &date_string.to_string(),
+            "%Y-%m-%d",
+        )),
+        ReleaseDatePrecision::None => None, // default to no date
+    };
+
+    match date_string_temp {
+        Some(Ok(date)) => Some(date),
+        Some(Err(_)) => None, // default to no date
+        None => None,         // pass through none
+    }
+}
+
 impl SpotifyObject for Album {
     /// Takes JsonValue object representing album and formats it into struct for ease of use
     ///
@@ -79,12 +174,17 @@ impl SpotifyObject for Album {
             None => 0, // default to 0
         };
 
-        let available_markets: Vec<String> = match &raw_object["avaliable_markets"] {
+        let available_markets: Vec<String> = match &raw_object["available_markets"] {
             Array(markets) => markets.iter().map(|market| market.to_string()).collect(), // turn JsonValue Array type to vec of Strings
             _ => vec![], // default to empty vec
         };
 
-        let spotify_url = &raw_object["external_urls"]["spotify"].to_string();
+        let copyrights: Vec<Copyright> = match &raw_object["copyrights"] {
+            Array(copyrights) => copyrights.iter().map(Copyright::new).collect(), // turn JsonValue Array type to vec of Copyright objects
+            _ => vec![], // default to empty vec
+        };
+
+        let external_urls = format_external_urls(&raw_object["external_urls"]);
 
         let href = &raw_object["href"].to_string();
 
@@ -108,39 +208,16 @@ impl SpotifyObject for Album {
             None => ReleaseDatePrecision::None,    // default to none
         };
 
-        let release_date = {
-            // parse string to date based on precision. Panic if unable to do so for whatever reason (hopefully this doens't happen, but it will be a problem for later me if it does)
-            match &raw_object["release_date"] {
-                Null => None, // default to no date
-                date_string => {
-                    let date_string_temp = match release_date_precision {
-                        ReleaseDatePrecision::Year => {
-                            Some(NaiveDate::parse_from_str(&date_string.to_string(), "%Y"))
-                        }
-                        ReleaseDatePrecision::Month => {
-                            Some(NaiveDate::parse_from_str(&date_string.to_string(), "%Y-%m"))
-                        }
-                        ReleaseDatePrecision::Day => Some(NaiveDate::parse_from_str(
-                            &date_string.to_string(),
-                            "%Y-%m-%d",
-                        )),
-                        ReleaseDatePrecision::None => None, // default to no date
-                    };
-                    match date_string_temp {
-                        Some(Ok(date)) => Some(date),
-                        Some(Err(_)) => None, // default to no date
-                        None => None,         // pass through none
-                    }
-                }
-            }
-        };
+        let release_date =
+            format_release_date(&raw_object["release_date"], &release_date_precision);
 
         // This very well might not work becuase I didn't invest the time to find an album that actually had restrictions to test this
         let restriction_reason = match &raw_object["restrictions"]["reason"].as_str() {
             Some("market") => RestrictionReason::Market,
             Some("product") => RestrictionReason::Product,
             Some("explicit") => RestrictionReason::Explicit,
-            _ => RestrictionReason::None, // default to none
+            Some(reason) => RestrictionReason::Other(reason.to_string()), // keep reasons added by Spotify later
+            None => RestrictionReason::None,                              // default to none
         };
 
         let uri = &raw_object["uri"].to_string();
@@ -159,7 +236,8 @@ impl SpotifyObject for Album {
             album_type,
             total_tracks,
             available_markets,
-            spotify_url: spotify_url.to_string(),
+            copyrights,
+            external_urls,
             href: href.to_string(),
             id: id.to_string(),
             images,
@@ -206,12 +284,9 @@ impl SpotifyObject for Artist {
     /// * `raw_object` - JsonValue object representing artist from API request
     ///
     fn new(raw_object: &JsonValue) -> Artist {
-        let spotify_url = &raw_object["external_urls"]["spotify"].to_string();
+        let external_urls = format_external_urls(&raw_object["external_urls"]);
 
-        let followers = match raw_object["followers"]["total"].as_i32() {
-            Some(followers) => followers,
-            None => 0, // default to 0
-        };
+        let followers = Followers::new(&raw_object["followers"]);
 
         let genres: Vec<String> = match &raw_object["genres"] {
             Array(genres) => genres.iter().map(|genre| genre.to_string()).collect(), // turn JsonValue Array type to vec of Strings
@@ -240,8 +315,8 @@ impl SpotifyObject for Artist {
         let uri = &raw_object["uri"].to_string();
 
         Artist {
-            spotify_url: spotify_url.to_string(),
-            total_followers: followers,
+            external_urls,
+            followers,
             genres,
             href: href.to_string(),
             id: id.to_string(),
@@ -280,7 +355,7 @@ impl SpotifyObject for Track {
             None => 0, // default to 0
         };
 
-        let duration = match raw_object["duration_ms"].as_i32() {
+        let duration = match raw_object["duration_ms"].as_i64() {
             Some(duration) => duration,
             None => 0, // default to 0
         };
@@ -299,25 +374,26 @@ impl SpotifyObject for Track {
             _ => ExternalTrackIds::new(&raw_object["external_ids"]), // if external_ids object exists, format it
         };
 
-        let spotify_url = &raw_object["external_urls"]["spotify"].to_string();
+        let external_urls = format_external_urls(&raw_object["external_urls"]);
 
         let href = &raw_object["href"].to_string();
 
-        let id = &raw_object["id"].to_string();
+        let id = match raw_object["id"].as_str() {
+            Some(id) => id.to_string(),
+            None => String::new(), // local files have no id
+        };
 
         let restriction_reason = match raw_object["restrictions"]["reason"].as_str() {
             Some("market") => RestrictionReason::Market,
             Some("product") => RestrictionReason::Product,
             Some("explicit") => RestrictionReason::Explicit,
-            _ => RestrictionReason::None, // default to none
+            Some(reason) => RestrictionReason::Other(reason.to_string()), // keep reasons added by Spotify later
+            None => RestrictionReason::None,                              // default to none
         };
 
         let name = &raw_object["name"].to_string();
 
-        let popularity = match raw_object["popularity"].as_i32() {
-            Some(popularity) => popularity,
-            None => 0, // default to 0
-        };
+        let popularity = raw_object["popularity"].as_i32(); // not part of simplified tracks
 
         let preview_url = match raw_object["preview_url"] {
             Null => None,
@@ -336,6 +412,13 @@ impl SpotifyObject for Track {
             None => false, // default to false
         };
 
+        let is_playable = raw_object["is_playable"].as_bool(); // only sent when market is specified
+
+        let linked_from = match raw_object["linked_from"] {
+            Null => None,
+            _ => Some(LinkedTrack::new(&raw_object["linked_from"])), // only sent when track was relinked
+        };
+
         Track {
             album,
             artists,
@@ -344,9 +427,9 @@ impl SpotifyObject for Track {
             duration,
             explicit,
             external_ids,
-            spotify_url: spotify_url.to_string(),
+            external_urls,
             href: href.to_string(),
-            id: id.to_string(),
+            id,
             restriction_reason,
             name: name.to_string(),
             popularity,
@@ -354,6 +437,25 @@ impl SpotifyObject for Track {
             track_number,
             uri: uri.to_string(),
             is_local,
+            is_playable,
+            linked_from,
+        }
+    }
+}
+
+impl SpotifyObject for LinkedTrack {
+    /// Format the linked_from object of a relinked track into struct for ease of use
+    ///
+    /// # Arguments
+    /// * `raw_object` - JsonValue object representing linked track from API request
+    ///
+    fn new(raw_object: &JsonValue) -> LinkedTrack {
+        LinkedTrack {
+            external_urls: format_external_urls(&raw_object["external_urls"]),
+            href: raw_object["href"].to_string(),
+            id: raw_object["id"].to_string(),
+            object_type: raw_object["type"].to_string(),
+            uri: raw_object["uri"].to_string(),
         }
     }
 }
@@ -382,6 +484,486 @@ impl SpotifyObject for DatedTrack {
     }
 }
 
+impl SpotifyObject for Episode {
+    /// Format a single episode in the form of a JsonValue from API request into struct for ease of use
+    ///
+    /// # Arguments
+    /// * `raw_object` - JsonValue object representing episode from API request
+    ///
+    fn new(raw_object: &JsonValue) -> Episode {
+        let audio_preview_url = match raw_object["audio_preview_url"].as_str() {
+            Some(audio_preview_url) => Some(audio_preview_url.to_string()),
+            None => None, // default to None
+        };
+
+        let description = match raw_object["description"].as_str() {
+            Some(description) => description.to_string(),
+            None => String::new(), // default to empty string
+        };
+
+        let duration = match raw_object["duration_ms"].as_i64() {
+            Some(duration) => duration,
+            None => 0, // default to 0
+        };
+
+        let explicit = match raw_object["explicit"].as_bool() {
+            Some(explicit) => explicit,
+            None => false, // default to false
+        };
+
+        let external_urls = format_external_urls(&raw_object["external_urls"]);
+
+        let href = match raw_object["href"].as_str() {
+            Some(href) => href.to_string(),
+            None => String::new(), // default to empty string
+        };
+
+        let id = match raw_object["id"].as_str() {
+            Some(id) => id.to_string(),
+            None => String::new(), // default to empty string
+        };
+
+        let images = match &raw_object["images"] {
+            Array(images) => images.iter().map(SpotifyImage::new).collect(), // turn JsonValue Array type to vec of SpotifyImage objects
+            _ => vec![],                                                     // default to empty vec
+        };
+
+        let is_externally_hosted = match raw_object["is_externally_hosted"].as_bool() {
+            Some(is_externally_hosted) => is_externally_hosted,
+            None => false, // default to false
+        };
+
+        let is_playable = raw_object["is_playable"].as_bool();
+
+        let languages: Vec<String> = match &raw_object["languages"] {
+            Array(languages) => languages
+                .iter()
+                .map(|language| language.to_string())
+                .collect(), // turn JsonValue Array type to vec of Strings
+            _ => vec![], // default to empty vec
+        };
+
+        let name = match raw_object["name"].as_str() {
+            Some(name) => name.to_string(),
+            None => String::new(), // default to empty string
+        };
+
+        let release_date_precision = match raw_object["release_date_precision"].as_str() {
+            Some("year") => ReleaseDatePrecision::Year,
+            Some("month") => ReleaseDatePrecision::Month,
+            Some("day") => ReleaseDatePrecision::Day,
+            Some(_) => ReleaseDatePrecision::None, // default to none
+            None => ReleaseDatePrecision::None,    // default to none
+        };
+
+        let release_date =
+            format_release_date(&raw_object["release_date"], &release_date_precision);
+
+        let restriction_reason = match raw_object["restrictions"]["reason"].as_str() {
+            Some("market") => RestrictionReason::Market,
+            Some("product") => RestrictionReason::Product,
+            Some("explicit") => RestrictionReason::Explicit,
+            Some(reason) => RestrictionReason::Other(reason.to_string()), // keep reasons added by Spotify later
+            None => RestrictionReason::None,                              // default to none
+        };
+
+        let uri = match raw_object["uri"].as_str() {
+            Some(uri) => uri.to_string(),
+            None => String::new(), // default to empty string
+        };
+
+        Episode {
+            audio_preview_url,
+            description,
+            duration,
+            explicit,
+            external_urls,
+            href,
+            id,
+            images,
+            is_externally_hosted,
+            is_playable,
+            languages,
+            name,
+            release_date,
+            release_date_precision,
+            restriction_reason,
+            uri,
+        }
+    }
+}
+
+impl SpotifyObject for Show {
+    /// Format a single show in the form of a JsonValue from API request into struct for ease of use
+    ///
+    /// # Arguments
+    /// * `raw_object` - JsonValue object representing show from API request
+    ///
+    fn new(raw_object: &JsonValue) -> Show {
+        let available_markets: Vec<String> = match &raw_object["available_markets"] {
+            Array(markets) => markets.iter().map(|market| market.to_string()).collect(), // turn JsonValue Array type to vec of Strings
+            _ => vec![], // default to empty vec
+        };
+
+        let description = match raw_object["description"].as_str() {
+            Some(description) => description.to_string(),
+            None => String::new(), // default to empty string
+        };
+
+        let explicit = match raw_object["explicit"].as_bool() {
+            Some(explicit) => explicit,
+            None => false, // default to false
+        };
+
+        let external_urls = format_external_urls(&raw_object["external_urls"]);
+
+        let href = match raw_object["href"].as_str() {
+            Some(href) => href.to_string(),
+            None => String::new(), // default to empty string
+        };
+
+        let id = match raw_object["id"].as_str() {
+            Some(id) => id.to_string(),
+            None => String::new(), // default to empty string
+        };
+
+        let images = match &raw_object["images"] {
+            Array(images) => images.iter().map(SpotifyImage::new).collect(), // turn JsonValue Array type to vec of SpotifyImage objects
+            _ => vec![],                                                     // default to empty vec
+        };
+
+        let is_externally_hosted = match raw_object["is_externally_hosted"].as_bool() {
+            Some(is_externally_hosted) => is_externally_hosted,
+            None => false, // default to false
+        };
+
+        let languages: Vec<String> = match &raw_object["languages"] {
+            Array(languages) => languages
+                .iter()
+                .map(|language| language.to_string())
+                .collect(), // turn JsonValue Array type to vec of Strings
+            _ => vec![], // default to empty vec
+        };
+
+        let media_type = match raw_object["media_type"].as_str() {
+            Some(media_type) => media_type.to_string(),
+            None => String::new(), // default to empty string
+        };
+
+        let name = match raw_object["name"].as_str() {
+            Some(name) => name.to_string(),
+            None => String::new(), // default to empty string
+        };
+
+        let publisher = match raw_object["publisher"].as_str() {
+            Some(publisher) => publisher.to_string(),
+            None => String::new(), // default to empty string
+        };
+
+        let total_episodes = match raw_object["total_episodes"].as_i32() {
+            Some(total_episodes) => total_episodes,
+            None => 0, // default to 0
+        };
+
+        let uri = match raw_object["uri"].as_str() {
+            Some(uri) => uri.to_string(),
+            None => String::new(), // default to empty string
+        };
+
+        Show {
+            available_markets,
+            description,
+            explicit,
+            external_urls,
+            href,
+            id,
+            images,
+            is_externally_hosted,
+            languages,
+            media_type,
+            name,
+            publisher,
+            total_episodes,
+            uri,
+        }
+    }
+}
+
+/// Formats the names of an array of named objects (i.e. authors or narrators) into a vector of strings
+///
+/// # Arguments
+/// * `raw_array` - JsonValue array of objects with a name field
+///
+fn format_names(raw_array: &JsonValue) -> Vec<String> {
+    match raw_array {
+        Array(objects) => objects
+            .iter()
+            .filter_map(|object| object["name"].as_str())
+            .map(|name| name.to_string())
+            .collect(), // take the name of each object
+        _ => vec![], // default to empty vec
+    }
+}
+
+impl SpotifyObject for Audiobook {
+    /// Format a single audiobook in the form of a JsonValue from API request into struct for ease of use
+    ///
+    /// # Arguments
+    /// * `raw_object` - JsonValue object representing audiobook from API request
+    ///
+    fn new(raw_object: &JsonValue) -> Audiobook {
+        let authors = format_names(&raw_object["authors"]);
+
+        let available_markets: Vec<String> = match &raw_object["available_markets"] {
+            Array(markets) => markets.iter().map(|market| market.to_string()).collect(), // turn JsonValue Array type to vec of Strings
+            _ => vec![], // default to empty vec
+        };
+
+        let description = match raw_object["description"].as_str() {
+            Some(description) => description.to_string(),
+            None => String::new(), // default to empty string
+        };
+
+        let edition = match raw_object["edition"].as_str() {
+            Some(edition) => Some(edition.to_string()),
+            None => None, // default to None
+        };
+
+        let explicit = match raw_object["explicit"].as_bool() {
+            Some(explicit) => explicit,
+            None => false, // default to false
+        };
+
+        let external_urls = format_external_urls(&raw_object["external_urls"]);
+
+        let href = match raw_object["href"].as_str() {
+            Some(href) => href.to_string(),
+            None => String::new(), // default to empty string
+        };
+
+        let id = match raw_object["id"].as_str() {
+            Some(id) => id.to_string(),
+            None => String::new(), // default to empty string
+        };
+
+        let images = match &raw_object["images"] {
+            Array(images) => images.iter().map(SpotifyImage::new).collect(), // turn JsonValue Array type to vec of SpotifyImage objects
+            _ => vec![],                                                     // default to empty vec
+        };
+
+        let languages: Vec<String> = match &raw_object["languages"] {
+            Array(languages) => languages
+                .iter()
+                .map(|language| language.to_string())
+                .collect(), // turn JsonValue Array type to vec of Strings
+            _ => vec![], // default to empty vec
+        };
+
+        let media_type = match raw_object["media_type"].as_str() {
+            Some(media_type) => media_type.to_string(),
+            None => String::new(), // default to empty string
+        };
+
+        let name = match raw_object["name"].as_str() {
+            Some(name) => name.to_string(),
+            None => String::new(), // default to empty string
+        };
+
+        let narrators = format_names(&raw_object["narrators"]);
+
+        let publisher = match raw_object["publisher"].as_str() {
+            Some(publisher) => publisher.to_string(),
+            None => String::new(), // default to empty string
+        };
+
+        let total_chapters = match raw_object["total_chapters"].as_i32() {
+            Some(total_chapters) => total_chapters,
+            None => 0, // default to 0
+        };
+
+        let chapters = match &raw_object["chapters"] {
+            Null => None, // chapters are only included when getting a single audiobook
+            chapters => Some(SpotifyCollection::<Chapter>::new(chapters)),
+        };
+
+        let uri = match raw_object["uri"].as_str() {
+            Some(uri) => uri.to_string(),
+            None => String::new(), // default to empty string
+        };
+
+        Audiobook {
+            authors,
+            available_markets,
+            description,
+            edition,
+            explicit,
+            external_urls,
+            href,
+            id,
+            images,
+            languages,
+            media_type,
+            name,
+            narrators,
+            publisher,
+            total_chapters,
+            chapters,
+            uri,
+        }
+    }
+}
+
+impl SpotifyObject for Chapter {
+    /// Format a single chapter in the form of a JsonValue from API request into struct for ease of use
+    ///
+    /// # Arguments
+    /// * `raw_object` - JsonValue object representing chapter from API request
+    ///
+    fn new(raw_object: &JsonValue) -> Chapter {
+        let audio_preview_url = match raw_object["audio_preview_url"].as_str() {
+            Some(audio_preview_url) => Some(audio_preview_url.to_string()),
+            None => None, // default to None
+        };
+
+        let available_markets: Vec<String> = match &raw_object["available_markets"] {
+            Array(markets) => markets.iter().map(|market| market.to_string()).collect(), // turn JsonValue Array type to vec of Strings
+            _ => vec![], // default to empty vec
+        };
+
+        let chapter_number = match raw_object["chapter_number"].as_i32() {
+            Some(chapter_number) => chapter_number,
+            None => 0, // default to 0
+        };
+
+        let description = match raw_object["description"].as_str() {
+            Some(description) => description.to_string(),
+            None => String::new(), // default to empty string
+        };
+
+        let duration = match raw_object["duration_ms"].as_i64() {
+            Some(duration) => duration,
+            None => 0, // default to 0
+        };
+
+        let explicit = match raw_object["explicit"].as_bool() {
+            Some(explicit) => explicit,
+            None => false, // default to false
+        };
+
+        let external_urls = format_external_urls(&raw_object["external_urls"]);
+
+        let href = match raw_object["href"].as_str() {
+            Some(href) => href.to_string(),
+            None => String::new(), // default to empty string
+        };
+
+        let id = match raw_object["id"].as_str() {
+            Some(id) => id.to_string(),
+            None => String::new(), // default to empty string
+        };
+
+        let images = match &raw_object["images"] {
+            Array(images) => images.iter().map(SpotifyImage::new).collect(), // turn JsonValue Array type to vec of SpotifyImage objects
+            _ => vec![],                                                     // default to empty vec
+        };
+
+        let languages: Vec<String> = match &raw_object["languages"] {
+            Array(languages) => languages
+                .iter()
+                .map(|language| language.to_string())
+                .collect(), // turn JsonValue Array type to vec of Strings
+            _ => vec![], // default to empty vec
+        };
+
+        let name = match raw_object["name"].as_str() {
+            Some(name) => name.to_string(),
+            None => String::new(), // default to empty string
+        };
+
+        let release_date_precision = match raw_object["release_date_precision"].as_str() {
+            Some("year") => ReleaseDatePrecision::Year,
+            Some("month") => ReleaseDatePrecision::Month,
+            Some("day") => ReleaseDatePrecision::Day,
+            Some(_) => ReleaseDatePrecision::None, // default to none
+            None => ReleaseDatePrecision::None,    // default to none
+        };
+
+        let release_date =
+            format_release_date(&raw_object["release_date"], &release_date_precision);
+
+        let restriction_reason = match raw_object["restrictions"]["reason"].as_str() {
+            Some("market") => RestrictionReason::Market,
+            Some("product") => RestrictionReason::Product,
+            Some("explicit") => RestrictionReason::Explicit,
+            Some(reason) => RestrictionReason::Other(reason.to_string()), // keep reasons added by Spotify later
+            None => RestrictionReason::None,                              // default to none
+        };
+
+        let uri = match raw_object["uri"].as_str() {
+            Some(uri) => uri.to_string(),
+            None => String::new(), // default to empty string
+        };
+
+        let audiobook = match &raw_object["audiobook"] {
+            Null => None, // audiobook isn't included when getting an audiobook's chapters
+            audiobook => Some(Audiobook::new(audiobook)),
+        };
+
+        Chapter {
+            audio_preview_url,
+            available_markets,
+            chapter_number,
+            description,
+            duration,
+            explicit,
+            external_urls,
+            href,
+            id,
+            images,
+            languages,
+            name,
+            release_date,
+            release_date_precision,
+            restriction_reason,
+            uri,
+            audiobook,
+        }
+    }
+}
+
+impl SpotifyObject for PlayableItem {
+    /// Takes JsonValue representing either a track or an episode and formats it based on its `type` field
+    ///
+    /// # Arguments
+    /// * `raw_object` - JsonValue object representing track or episode from API request
+    ///
+    fn new(raw_object: &JsonValue) -> PlayableItem {
+        match raw_object["type"].as_str() {
+            Some("episode") => PlayableItem::Episode(Box::new(Episode::new(raw_object))),
+            _ => PlayableItem::Track(Box::new(Track::new(raw_object))), // default to track
+        }
+    }
+}
+
+impl SpotifyObject for SearchItem {
+    /// Takes JsonValue representing a search result and formats it based on its `type` field
+    ///
+    /// # Arguments
+    /// * `raw_object` - JsonValue object representing a search result from API request
+    ///
+    fn new(raw_object: &JsonValue) -> SearchItem {
+        match raw_object["type"].as_str() {
+            Some("album") => SearchItem::Album(Album::new(raw_object)),
+            Some("artist") => SearchItem::Artist(Artist::new(raw_object)),
+            Some("playlist") => SearchItem::Playlist(Playlist::new(raw_object)),
+            Some("show") => SearchItem::Show(Show::new(raw_object)),
+            Some("episode") => SearchItem::Episode(Episode::new(raw_object)),
+            Some("audiobook") => SearchItem::Audiobook(Audiobook::new(raw_object)),
+            _ => SearchItem::Track(Track::new(raw_object)), // default to track
+        }
+    }
+}
+
 impl FeatureTrack {
     /// Takes JsonValue representing audio features for a track and formats it into FeatureTrack struct
     ///
@@ -401,7 +983,7 @@ impl FeatureTrack {
             None => 0.0, // default to 0.0
         };
 
-        let duration = match raw_object["duration_ms"].as_i32() {
+        let duration = match raw_object["duration_ms"].as_i64() {
             Some(duration) => duration,
             None => 0, // default to 0
         };
@@ -885,15 +1467,14 @@ impl User {
             None => None, // default to None
         };
 
-        let spotify_url = match raw_object["external_urls"]["spotify"].as_str() {
-            Some(spotify_url) => spotify_url,
-            None => "", // default to empty string
+        let email = match raw_object["email"].as_str() {
+            Some(email) => Some(email.to_string()),
+            None => None, // not returned without the user-read-email scope
         };
 
-        let total_followers = match raw_object["followers"]["total"].as_i32() {
-            Some(followers) => followers,
-            None => 0, // default to 0
-        };
+        let external_urls = format_external_urls(&raw_object["external_urls"]);
+
+        let followers = Followers::new(&raw_object["followers"]);
 
         let href = match raw_object["href"].as_str() {
             Some(href) => href,
@@ -926,8 +1507,9 @@ impl User {
         User {
             country,
             display_name,
-            spotify_url: spotify_url.to_string(),
-            total_followers,
+            email,
+            external_urls,
+            followers,
             href: href.to_string(),
             id: id.to_string(),
             images,
@@ -954,12 +1536,9 @@ impl SpotifyObject for Playlist {
             None => None, // default to None
         };
 
-        let spotify_url = match raw_object["external_urls"]["spotify"].as_str() {
-            Some(spotify_url) => spotify_url.to_string(),
-            None => String::new(), // default to empty string
-        };
+        let external_urls = format_external_urls(&raw_object["external_urls"]);
 
-        let total_followers = raw_object["followers"]["total"].as_i32().unwrap_or(0); // default to 0
+        let followers = Followers::new(&raw_object["followers"]);
 
         let href = match raw_object["href"].as_str() {
             Some(href) => String::from(href),
@@ -986,6 +1565,11 @@ impl SpotifyObject for Playlist {
 
         let owner = User::new(&raw_object["owner"]);
 
+        let primary_color = match raw_object["primary_color"].as_str() {
+            Some(primary_color) => Some(primary_color.to_string()),
+            None => None, // default to None
+        };
+
         let public = match raw_object["public"].as_bool() {
             Some(public) => Some(public),
             None => None, // default to none
@@ -1001,6 +1585,11 @@ impl SpotifyObject for Playlist {
             tracks => Some(SpotifyCollection::<PlaylistTrack>::new(tracks)), // format tracks if they exist
         };
 
+        let tracks_href = match raw_object["tracks"]["href"].as_str() {
+            Some(tracks_href) => Some(tracks_href.to_string()),
+            None => None, // default to None
+        };
+
         let uri = match raw_object["uri"].as_str() {
             Some(uri) => String::from(uri),
             None => String::new(), // default to empty string
@@ -1009,21 +1598,53 @@ impl SpotifyObject for Playlist {
         Playlist {
             collaborative,
             description,
-            spotify_url,
-            total_followers,
+            external_urls,
+            followers,
             href,
             id,
             images,
             name,
             owner,
+            primary_color,
             public,
             snapshot_id,
             tracks,
+            tracks_href,
             uri,
         }
     }
 }
 
+impl SpotifyObject for PlaylistSummary {
+    /// Format the id, name and track count of a playlist in the form of a JsonValue from API request into struct for ease of use
+    ///
+    /// # Arguments
+    /// * `raw_object` - JsonValue object representing playlist from API request
+    ///
+    fn new(raw_object: &JsonValue) -> PlaylistSummary {
+        let id = match raw_object["id"].as_str() {
+            Some(id) => id.to_string(),
+            None => String::new(), // default to empty string
+        };
+
+        let name = match raw_object["name"].as_str() {
+            Some(name) => name.to_string(),
+            None => String::new(), // default to empty string
+        };
+
+        let track_count = match raw_object["tracks"]["total"].as_i32() {
+            Some(track_count) => track_count,
+            None => 0, // default to 0
+        };
+
+        PlaylistSummary {
+            id,
+            name,
+            track_count,
+        }
+    }
+}
+
 impl SpotifyObject for PlaylistTrack {
     /// Takes JsonValue representing a PlaylistTrack and returns the PlaylistTrack Struct
     ///
@@ -1045,7 +1666,7 @@ impl SpotifyObject for PlaylistTrack {
             None => false, // default to false
         };
 
-        let track = Track::new(&raw_object["track"]);
+        let track = PlayableItem::new(&raw_object["track"]); // track or episode
 
         PlaylistTrack {
             added_at,
@@ -1301,7 +1922,7 @@ impl Playback {
             _ => false, // default to false
         };
 
-        let progress = match raw_object["progress_ms"].as_i32() {
+        let progress = match raw_object["progress_ms"].as_i64() {
             Some(progress_ms) => Some(progress_ms),
             None => None, // default to 0
         };
@@ -1313,9 +1934,11 @@ impl Playback {
 
         let track = match &raw_object["item"] {
             Null => None,
-            item => Some(Track::new(item)), // format item if it exists
+            item => Some(PlayableItem::new(item)), // format track or episode if it exists
         };
 
+        let context = SpotifyContext::new(&raw_object["context"]);
+
         let actions = match &raw_object["actions"] {
             Null => None,
             actions => Some(PlaybackActions::new(actions)), // format actions if they exist
@@ -1329,9 +1952,23 @@ impl Playback {
             progress,
             is_playing,
             track,
+            context,
             actions,
         }
     }
+
+    /// Returns how far into the current track playback is, as a fraction between 0 and 1. Useful for progress bars.
+    /// None if the progress or track is missing (or the track has no duration). Clamped to 0..=1 as progress can briefly exceed the duration at track boundaries.
+    pub fn progress_fraction(&self) -> Option<f64> {
+        let progress = self.progress?;
+        let duration = self.track.as_ref()?.duration();
+
+        if duration <= 0 {
+            return None; // no duration to compare against
+        }
+
+        Some((progress as f64 / duration as f64).clamp(0.0, 1.0))
+    }
 }
 
 impl SpotifyObject for PlayedTrack {
@@ -1359,3 +1996,88 @@ impl SpotifyObject for PlayedTrack {
         }
     }
 }
+
+impl SpotifyObject for Cursors {
+    /// Takes JsonValue representing the cursors of a page and returns Cursors struct
+    ///
+    /// # Arguments
+    /// * `raw_object` - JsonValue representing cursors
+    ///
+    fn new(raw_object: &JsonValue) -> Cursors {
+        Cursors {
+            after: cursor_time(&raw_object["after"]),
+            before: cursor_time(&raw_object["before"]),
+        }
+    }
+}
+
+/// Parses a recently played cursor (a unix timestamp in ms, sent as a string) into NaiveDateTime
+///
+/// # Arguments
+/// * `cursor` - JsonValue representing the cursor
+///
+fn cursor_time(cursor: &JsonValue) -> Option<NaiveDateTime> {
+    let timestamp = match cursor.as_str() {
+        Some(cursor) => cursor.parse::<i64>().ok()?,
+        None => cursor.as_i64()?, // in case the cursor is sent as a number
+    };
+
+    DateTime::from_timestamp_millis(timestamp).map(|time| time.naive_utc())
+}
+
+impl SpotifyObject for RecommendationSeed {
+    /// Takes JsonValue representing a recommendation seed and returns RecommendationSeed struct
+    ///
+    /// # Arguments
+    /// * `raw_object` - JsonValue representing a recommendation seed
+    ///
+    fn new(raw_object: &JsonValue) -> RecommendationSeed {
+        let after_filtering_size = raw_object["afterFilteringSize"].as_i32().unwrap_or(0); // default to 0
+
+        let after_relinking_size = raw_object["afterRelinkingSize"].as_i32().unwrap_or(0); // default to 0
+
+        let href = match raw_object["href"].as_str() {
+            Some(href) => Some(href.to_string()),
+            None => None, // default to None (genre seeds have no href)
+        };
+
+        let id = match raw_object["id"].as_str() {
+            Some(id) => id.to_string(),
+            None => String::new(), // default to empty string
+        };
+
+        let initial_pool_size = raw_object["initialPoolSize"].as_i32().unwrap_or(0); // default to 0
+
+        let seed_type = match raw_object["type"].as_str() {
+            Some(seed_type) => seed_type.to_lowercase(), // Spotify sends the type uppercase, i.e. "ARTIST"
+            None => String::new(),                       // default to empty string
+        };
+
+        RecommendationSeed {
+            after_filtering_size,
+            after_relinking_size,
+            href,
+            id,
+            initial_pool_size,
+            seed_type,
+        }
+    }
+}
+
+impl SpotifyObject for Recommendations {
+    /// Takes JsonValue representing a recommendations response and returns Recommendations struct
+    ///
+    /// # Arguments
+    /// * `raw_object` - JsonValue representing recommendations
+    ///
+    fn new(raw_object: &JsonValue) -> Recommendations {
+        let seeds = raw_object["seeds"]
+            .members()
+            .map(RecommendationSeed::new)
+            .collect();
+
+        let tracks = raw_object["tracks"].members().map(Track::new).collect();
+
+        Recommendations { seeds, tracks }
+    }
+}
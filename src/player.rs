@@ -1,29 +1,61 @@
 use crate::spotify::{
-    Device, Playback, PlayedTrack, RepeatState, Spotify, SpotifyCollection, SpotifyContext,
-    SpotifyError, SpotifyObject, Track,
+    Cursors, Device, ItemType, Market, PlayableItem, Playback, PlaybackOffset, PlayedTrack,
+    RecentlyPlayed, RepeatState, Spotify, SpotifyCollection, SpotifyContext, SpotifyError,
+    SpotifyObject,
 };
-use crate::srequest::RequestMethod;
-use chrono::NaiveDateTime;
+use crate::srequest::{append_query_parameter, RequestMethod};
+use chrono::{Duration, NaiveDateTime};
+use json::JsonValue::Null;
 use serde_json::{Map, Number, Value};
 use std::collections::HashMap;
 
+/// Number of tracks above which queueing a whole context logs a warning, as each track is queued with its own request
+const LARGE_QUEUE_SIZE: usize = 100;
+
+/// Converts a position in a track to whole milliseconds, as expected by Spotify
+///
+/// # Arguments
+/// * `position` - The position in the track
+///
+fn position_to_ms(position: Duration) -> Result<i32, SpotifyError> {
+    if position < Duration::zero() {
+        return Err(SpotifyError::InvalidRequest(String::from(
+            "Position can't be negative",
+        )));
+    }
+
+    match i32::try_from(position.num_milliseconds()) {
+        Ok(position_ms) => Ok(position_ms),
+        Err(_) => Err(SpotifyError::InvalidRequest(String::from(
+            "Position is too large",
+        ))),
+    }
+}
+
 impl Spotify {
     /// Gets current playback state of current user: <https://developer.spotify.com/documentation/web-api/reference/#/operations/get-information-about-the-users-current-playback>
-    /// Note: currently only supports Tracks and not Episodes. Unexpected behavior may occur with Episodes.
+    /// Note: episodes are formatted as tracks, so only the fields they share with tracks will be filled in
     ///
     /// Requires scope: user-read-playback-state
     ///
     /// # Arguments
-    /// * `market` - An ISO 3166-1 alpha-2 country code.
+    /// * `market` - An ISO 3166-1 alpha-2 country code, or `Market::FromToken` for the current user's market. Default: the user's market when authenticated as a user.
+    /// * `additional_types` - The item types to include in the response. Default: tracks and episodes.
     ///
-    pub fn get_playback_state(&self, market: Option<&str>) -> Result<Playback, SpotifyError> {
-        let mut url_extension = String::from("me/player?additional_types=track"); // create url extension (Note: only supporting tracks, not episodes)
+    pub fn get_playback_state(
+        &self,
+        market: Option<Market>,
+        additional_types: Option<&[ItemType]>,
+    ) -> Result<Playback, SpotifyError> {
+        let additional_types =
+            ItemType::join(additional_types.unwrap_or(&[ItemType::Track, ItemType::Episode])); // default to tracks and episodes
+        let mut url_extension = format!("me/player?additional_types={}", additional_types); // create url extension
 
         self.check_scope("user-read-playback-state")?; // check scope
 
-        if let Some(market) = market {
-            // if market is Some then add it to url extension
-            url_extension.push_str(&format!("&market={}", market));
+        if let Some(market) = self.market_or_user_default(market) {
+            // if market is Some (or defaulted) then add it to url extension
+            append_query_parameter(&mut url_extension, "market", market.code());
         }
         let response = self.spotify_request(&url_extension, RequestMethod::Get)?; // send request
 
@@ -76,25 +108,61 @@ impl Spotify {
         return Ok(devices); // return vector
     }
 
+    /// Transfers playback to the device with the given name and starts playing on it. A convenience over [get_available_devices](struct.Spotify.html#method.get_available_devices)
+    /// and [transfer_playback](struct.Spotify.html#method.transfer_playback). Device names are compared case-insensitively.
+    ///
+    /// Requires scope: user-read-playback-state user-modify-playback-state
+    ///
+    /// # Arguments
+    /// * `device_name` - The name of the device to play on (ie "My Phone")
+    ///
+    pub fn play_on_device(&self, device_name: &str) -> Result<(), SpotifyError> {
+        self.check_scope("user-read-playback-state user-modify-playback-state")?; // check scope
+
+        let devices = self.get_available_devices()?; // get all available devices
+
+        let device = match devices
+            .iter()
+            .find(|device| device.name.to_lowercase() == device_name.to_lowercase())
+        {
+            Some(device) => device,
+            None => {
+                return Err(SpotifyError::NotFound(format!(
+                    "No available device named {}",
+                    device_name
+                )))
+            } // no device with a matching name
+        };
+
+        self.transfer_playback(&device.id, true) // transfer playback and start playing
+    }
+
     /// Gets the currently playing track: <https://developer.spotify.com/documentation/web-api/reference/#/operations/get-the-users-currently-playing-track>
-    /// Note: Currently this only supports tracks and not episodes. Weird behavior may occur if an episode is being played.
+    /// Note: episodes are formatted as tracks, so only the fields they share with tracks will be filled in
     ///
     /// Requires scope: user-read-currently-playing
     ///
     /// # Arguments
-    /// * `market` - An ISO 3166-1 alpha-2 country code which the returned track should be in the market of
+    /// * `market` - An ISO 3166-1 alpha-2 country code which the returned track should be in the market of, or `Market::FromToken` for the current user's market. Default: the user's market when authenticated as a user.
+    /// * `additional_types` - The item types to include in the response. Default: tracks and episodes.
     ///
     pub fn get_currently_playing_track(
         &self,
-        market: Option<&str>,
+        market: Option<Market>,
+        additional_types: Option<&[ItemType]>,
     ) -> Result<Playback, SpotifyError> {
-        let mut url_extension = String::from("me/player/currently-playing?additional_types=track"); // create url extension. Only supporting tracks right now
+        let additional_types =
+            ItemType::join(additional_types.unwrap_or(&[ItemType::Track, ItemType::Episode])); // default to tracks and episodes
+        let mut url_extension = format!(
+            "me/player/currently-playing?additional_types={}",
+            additional_types
+        ); // create url extension
 
         self.check_scope("user-read-currently-playing")?; // check scope
 
-        if let Some(market) = market {
-            // if market is Some then add it to url extension
-            url_extension.push_str(&format!("&market={}", market));
+        if let Some(market) = self.market_or_user_default(market) {
+            // if market is Some (or defaulted) then add it to url extension
+            append_query_parameter(&mut url_extension, "market", market.code());
         }
 
         let response = self.spotify_request(&url_extension, RequestMethod::Get)?; // send request
@@ -110,27 +178,48 @@ impl Spotify {
     /// * `device_id` - The id of the device to start playback on
     /// * `context` - The context to start playback on. Valid contexts: Album, Artist, Playlist
     /// * `track_ids` - The track ids to start playback on
-    /// * `offset_position` - Indicates where in the context the playback should start. For example, starting on the 2nd song of an album with offset=1.
-    /// * `offset_track` - Indicates which track in context to begin playback on. This is a track id. Note: this will be ignored if offset_position is set.
-    /// * `position_ms` - Where in the song to begin playback
+    /// * `offset` - Indicates where in the context the playback should start: a position (i.e. `PlaybackOffset::Position(1)` to start on the 2nd song of an album), a track id or a complete uri (i.e. `spotify:episode:512ojhOuo1ktJprKbVcKyQ`).
+    /// * `position` - Where in the song to begin playback. Can't be negative
     ///
     pub fn start_resume_playback(
         &self,
         device_id: Option<&str>,
         context: Option<SpotifyContext>,
         track_ids: Option<Vec<&str>>,
-        offset_position: Option<i32>,
-        offset_track: Option<&str>,
+        offset: Option<PlaybackOffset>,
+        position: Option<Duration>,
+    ) -> Result<(), SpotifyError> {
+        let position_ms = match position {
+            Some(position) => Some(position_to_ms(position)?),
+            None => None,
+        };
+
+        return self.start_resume_playback_ms(device_id, context, track_ids, offset, position_ms);
+    }
+
+    /// Start a new context in player or resume playback of a device, with the position given in milliseconds: <https://developer.spotify.com/documentation/web-api/reference/#/operations/start-a-users-playback>
+    ///
+    /// Requires scope: user-modify-playback-state
+    ///
+    /// # Arguments
+    /// * `device_id` - The id of the device to start playback on
+    /// * `context` - The context to start playback on. Valid contexts: Album, Artist, Playlist
+    /// * `track_ids` - The track ids to start playback on
+    /// * `offset` - Indicates where in the context the playback should start: a position (i.e. `PlaybackOffset::Position(1)` to start on the 2nd song of an album), a track id or a complete uri (i.e. `spotify:episode:512ojhOuo1ktJprKbVcKyQ`).
+    /// * `position_ms` - Where in the song to begin playback in milliseconds
+    ///
+    pub fn start_resume_playback_ms(
+        &self,
+        device_id: Option<&str>,
+        context: Option<SpotifyContext>,
+        track_ids: Option<Vec<&str>>,
+        offset: Option<PlaybackOffset>,
         position_ms: Option<i32>,
     ) -> Result<(), SpotifyError> {
-        let mut url_extension = String::from("me/player/play"); // create url extension
+        let url_extension = String::from("me/player/play"); // create url extension
 
         self.check_scope("user-modify-playback-state")?; // check scope
 
-        if let Some(device_id) = device_id {
-            url_extension.push_str(&format!("?device_id={}", device_id)); // if device_id is supplied, then add it to url extension
-        }
-
         let mut body: HashMap<String, Value> = HashMap::new(); // create body
 
         if let Some(context) = context {
@@ -148,26 +237,23 @@ impl Spotify {
             body.insert("uris".to_string(), Value::Array(tracks)); // insert track ids into body
         }
 
-        if let Some(offset) = offset_position {
+        if let Some(offset) = offset {
+            let (key, value) = match offset {
+                PlaybackOffset::Position(position) => {
+                    ("position", Value::Number(Number::from(position)))
+                }
+                PlaybackOffset::Track(track_id) => {
+                    ("uri", Value::String(format!("spotify:track:{}", track_id)))
+                } // track ids are turned into uris
+                PlaybackOffset::Uri(uri) => ("uri", Value::String(uri.to_string())), // uris are sent as is
+            };
+
             let mut m = Map::new();
-            m.insert("position".to_string(), Value::Number(Number::from(offset))); // if offset_position is supplied, then add it to body
+            m.insert(key.to_string(), value);
 
             body.insert("offset".to_string(), Value::Object(m)); // if offset is supplied, then add it to body
         }
 
-        if offset_position.is_none() {
-            //if offset_position wasn't supplied
-            if let Some(track_id) = offset_track {
-                let mut m = Map::new();
-                m.insert(
-                    "uri".to_string(),
-                    Value::String(format!("spotify:track:{}", track_id)),
-                ); // if offset_track is supplied, then add it to body
-
-                body.insert("offset".to_string(), Value::Object(m)); // if offset is supplied, then add it to body
-            }
-        }
-
         if let Some(position_ms) = position_ms {
             body.insert(
                 "position_ms".to_string(),
@@ -175,7 +261,7 @@ impl Spotify {
             ); // if position_ms is supplied, then add it to body
         }
 
-        self.spotify_request(&url_extension, RequestMethod::Put(body))?; // send request
+        self.player_command(url_extension, device_id, RequestMethod::Put(body))?; // send request, on device_id if supplied
 
         return Ok(());
     }
@@ -188,19 +274,41 @@ impl Spotify {
     /// * `device_id` - The id of the device to pause playback on
     ///
     pub fn pause_playback(&self, device_id: Option<&str>) -> Result<(), SpotifyError> {
-        let mut url_extension = String::from("me/player/pause"); // create url extension
+        let url_extension = String::from("me/player/pause"); // create url extension
 
         self.check_scope("user-modify-playback-state")?; // check scope
 
-        if let Some(device_id) = device_id {
-            url_extension.push_str(&format!("?device_id={}", device_id)); // if device_id is supplied, then add it to url extension
-        }
-
-        self.spotify_request(&url_extension, RequestMethod::Put(HashMap::new()))?; // send request
+        self.player_command(url_extension, device_id, RequestMethod::Put(HashMap::new()))?; // send request, on device_id if supplied
 
         return Ok(());
     }
 
+    /// Pauses playback if something is playing, otherwise resumes it. Useful for a single play/pause button: <https://developer.spotify.com/documentation/web-api/reference/#/operations/get-information-about-the-users-current-playback>
+    ///
+    /// Requires scope: user-read-playback-state user-modify-playback-state
+    ///
+    /// # Arguments
+    /// * `device_id` - The id of the device to toggle playback on
+    ///
+    pub fn toggle_playback(&self, device_id: Option<&str>) -> Result<(), SpotifyError> {
+        self.check_scope("user-read-playback-state user-modify-playback-state")?; // check scope
+
+        let playback = self.get_playback_state(None, None)?;
+
+        if playback.device.is_none() {
+            // Spotify returns no content when there is no active playback
+            return Err(SpotifyError::InvalidRequest(String::from(
+                "No active playback to toggle",
+            )));
+        }
+
+        if playback.is_playing {
+            return self.pause_playback(device_id);
+        } else {
+            return self.start_resume_playback(device_id, None, None, None, None);
+        }
+    }
+
     /// Skips the currently playing track to the next track: <https://developer.spotify.com/documentation/web-api/reference/#/operations/skip-users-playback-to-next-track>
     ///
     /// Requires scope: user-modify-playback-state
@@ -209,15 +317,15 @@ impl Spotify {
     /// * `device_id` - The id of the device to skip on
     ///
     pub fn skip_next(&self, device_id: Option<&str>) -> Result<(), SpotifyError> {
-        let mut url_extension = String::from("me/player/next"); // create url extension
+        let url_extension = String::from("me/player/next"); // create url extension
 
         self.check_scope("user-modify-playback-state")?; // check scope
 
-        if let Some(device_id) = device_id {
-            url_extension.push_str(&format!("?device_id={}", device_id)); // if device_id is supplied, then add it to url extension
-        }
-
-        self.spotify_request(&url_extension, RequestMethod::Post(HashMap::new()))?; // send request
+        self.player_command(
+            url_extension,
+            device_id,
+            RequestMethod::Post(HashMap::new()),
+        )?; // send request, on device_id if supplied
 
         return Ok(());
     }
@@ -230,15 +338,15 @@ impl Spotify {
     /// * `device_id` - The id of the device to skip on
     ///
     pub fn skip_previous(&self, device_id: Option<&str>) -> Result<(), SpotifyError> {
-        let mut url_extension = String::from("me/player/previous"); // create url extension
+        let url_extension = String::from("me/player/previous"); // create url extension
 
         self.check_scope("user-modify-playback-state")?; // check scope
 
-        if let Some(device_id) = device_id {
-            url_extension.push_str(&format!("?device_id={}", device_id)); // if device_id is supplied, then add it to url extension
-        }
-
-        self.spotify_request(&url_extension, RequestMethod::Post(HashMap::new()))?; // send request
+        self.player_command(
+            url_extension,
+            device_id,
+            RequestMethod::Post(HashMap::new()),
+        )?; // send request, on device_id if supplied
 
         return Ok(());
     }
@@ -248,23 +356,35 @@ impl Spotify {
     /// Requires scope: user-modify-playback-state
     ///
     /// # Arguments
-    /// * `position` - The position in milliseconds to seek to
+    /// * `position` - The position to seek to. Can't be negative
     /// * `device_id` - The id of the device to seek on
     ///
     pub fn seek_position(
         &self,
-        position: i32,
+        position: Duration,
         device_id: Option<&str>,
     ) -> Result<(), SpotifyError> {
-        let mut url_extension = format!("me/player/seek?position_ms={}", position); // create url extension
+        return self.seek_position_ms(position_to_ms(position)?, device_id);
+    }
 
-        self.check_scope("user-modify-playback-state")?; // check scope
+    /// Seeks to specified position, given in milliseconds, in currently playing track: <https://developer.spotify.com/documentation/web-api/reference/#/operations/seek-to-position-in-currently-playing-track>
+    ///
+    /// Requires scope: user-modify-playback-state
+    ///
+    /// # Arguments
+    /// * `position_ms` - The position in milliseconds to seek to
+    /// * `device_id` - The id of the device to seek on
+    ///
+    pub fn seek_position_ms(
+        &self,
+        position_ms: i32,
+        device_id: Option<&str>,
+    ) -> Result<(), SpotifyError> {
+        let url_extension = format!("me/player/seek?position_ms={}", position_ms); // create url extension
 
-        if let Some(device_id) = device_id {
-            url_extension.push_str(&format!("&device_id={}", device_id)); // if device_id is supplied, then add it to url extension
-        }
+        self.check_scope("user-modify-playback-state")?; // check scope
 
-        self.spotify_request(&url_extension, RequestMethod::Put(HashMap::new()))?; // send request
+        self.player_command(url_extension, device_id, RequestMethod::Put(HashMap::new()))?; // send request, on device_id if supplied
 
         return Ok(());
     }
@@ -282,15 +402,11 @@ impl Spotify {
         state: RepeatState,
         device_id: Option<&str>,
     ) -> Result<(), SpotifyError> {
-        let mut url_extension = format!("me/player/repeat?state={}", state.to_string()); // create url extension
+        let url_extension = format!("me/player/repeat?state={}", state.to_string()); // create url extension
 
         self.check_scope("user-modify-playback-state")?; // check scope
 
-        if let Some(device_id) = device_id {
-            url_extension.push_str(&format!("&device_id={}", device_id)); // if device_id is supplied, then add it to url extension
-        }
-
-        self.spotify_request(&url_extension, RequestMethod::Put(HashMap::new()))?; // send request
+        self.player_command(url_extension, device_id, RequestMethod::Put(HashMap::new()))?; // send request, on device_id if supplied
 
         return Ok(());
     }
@@ -308,19 +424,55 @@ impl Spotify {
         volume: i32,
         device_id: Option<&str>,
     ) -> Result<(), SpotifyError> {
-        let mut url_extension = format!("me/player/volume?volume_percent={}", volume); // create url extension
+        let url_extension = format!("me/player/volume?volume_percent={}", volume); // create url extension
 
         self.check_scope("user-modify-playback-state")?; // check scope
 
-        if let Some(device_id) = device_id {
-            url_extension.push_str(&format!("&device_id={}", device_id)); // if device_id is supplied, then add it to url extension
-        }
-
-        self.spotify_request(&url_extension, RequestMethod::Put(HashMap::new()))?; // send request
+        self.player_command(url_extension, device_id, RequestMethod::Put(HashMap::new()))?; // send request, on device_id if supplied
 
         return Ok(());
     }
 
+    /// Sets the volume for the user's playback after checking that the device accepts volume changes (see [Device::supports_volume](struct.Device.html#method.supports_volume)).
+    /// Returns an `InvalidRequest` error instead of sending a request Spotify would reject with a 403: <https://developer.spotify.com/documentation/web-api/reference/#/operations/set-volume-for-users-playback>
+    ///
+    /// Requires scope: user-modify-playback-state user-read-playback-state
+    ///
+    /// # Arguments
+    /// * `volume` - The volume to set. Must be a value from 0 to 100 inclusive
+    /// * `device_id` - The id of the device to set volume on. Defaults to the active device
+    ///
+    pub fn set_playback_volume_checked(
+        &self,
+        volume: i32,
+        device_id: Option<&str>,
+    ) -> Result<(), SpotifyError> {
+        self.check_scope("user-modify-playback-state user-read-playback-state")?; // check scope
+
+        let devices = self.get_available_devices()?;
+        let device = match device_id {
+            Some(device_id) => devices.iter().find(|device| device.id == device_id),
+            None => devices.iter().find(|device| device.is_active), // default to active device
+        };
+
+        match device {
+            Some(device) if !device.supports_volume() => {
+                return Err(SpotifyError::InvalidRequest(format!(
+                    "Device {} does not support changing the volume",
+                    device.name
+                )))
+            }
+            Some(_) => (),
+            None => {
+                return Err(SpotifyError::NotFound(String::from(
+                    "No matching device found",
+                )))
+            }
+        }
+
+        return self.set_playback_volume(volume, device_id);
+    }
+
     /// Toggles shuffle state: <https://developer.spotify.com/documentation/web-api/reference/#/operations/toggle-shuffle-for-users-playback>
     ///
     /// Requires scope: user-modify-playback-state
@@ -334,20 +486,17 @@ impl Spotify {
         state: bool,
         device_id: Option<&str>,
     ) -> Result<(), SpotifyError> {
-        let mut url_extension = format!("me/player/shuffle?state={}", state); // create url extension
+        let url_extension = format!("me/player/shuffle?state={}", state); // create url extension
 
         self.check_scope("user-modify-playback-state")?; // check scope
 
-        if let Some(device_id) = device_id {
-            url_extension.push_str(&format!("&device_id={}", device_id)); // if device_id is supplied, then add it to url extension
-        }
-
-        self.spotify_request(&url_extension, RequestMethod::Put(HashMap::new()))?; // send request
+        self.player_command(url_extension, device_id, RequestMethod::Put(HashMap::new()))?; // send request, on device_id if supplied
 
         return Ok(());
     }
 
     /// Returns user's recently played items: <https://developer.spotify.com/documentation/web-api/reference/#/operations/get-recently-played>
+    /// The returned cursors can be passed as `before` (or `after`) to request the next older (or newer) page.
     ///
     /// Requires scope: user-read-recently-played
     ///
@@ -361,58 +510,103 @@ impl Spotify {
         after: Option<NaiveDateTime>,
         before: Option<NaiveDateTime>,
         limit: Option<i32>,
-    ) -> Result<SpotifyCollection<PlayedTrack>, SpotifyError> {
+    ) -> Result<RecentlyPlayed, SpotifyError> {
         let mut url_extension = String::from("me/player/recently-played"); // create url extension
 
         self.check_scope("user-read-recently-played")?; // check scope
 
-        if !after.is_none() || !before.is_none() || !limit.is_none() {
-            url_extension.push_str("?"); // if any of the optional arguments are supplied, then add a ? to the url extension
-        }
-
         if let Some(after) = after {
-            url_extension.push_str(&format!("&after={}", after.timestamp_millis()));
+            append_query_parameter(&mut url_extension, "after", after.timestamp_millis());
             // if after is supplied, then add it to url extension
         }
 
         if after.is_none() {
             // only if after isn't supplied consider before
             if let Some(before) = before {
-                url_extension.push_str(&format!("&before={}", before.timestamp_millis()));
+                append_query_parameter(&mut url_extension, "before", before.timestamp_millis());
                 // if before is supplied, then add it to url extension
             }
         }
 
         if let Some(limit) = limit {
-            url_extension.push_str(&format!("&limit={}", limit)); // if limit is supplied, then add it to url extension
+            append_query_parameter(&mut url_extension, "limit", limit); // if limit is supplied, then add it to url extension
         }
 
         let response = self.spotify_request(&url_extension, RequestMethod::Get)?; // send request
 
-        return Ok(SpotifyCollection::<PlayedTrack>::new(&response)); // return response
+        return Ok(RecentlyPlayed {
+            tracks: SpotifyCollection::<PlayedTrack>::new(&response),
+            cursors: Cursors::new(&response["cursors"]), // null when there are no items
+        }); // return response
+    }
+
+    /// Returns every item the user played after the given time, oldest first. Pages forward through the recently played items
+    /// with the `after` cursor until caught up: <https://developer.spotify.com/documentation/web-api/reference/#/operations/get-recently-played>
+    /// Note: Spotify only keeps the last 50 played items, so older items can't be returned.
+    ///
+    /// Requires scope: user-read-recently-played
+    ///
+    /// # Arguments
+    /// * `after` - Only return items played after this time, i.e. the `played_at` of the last item already handled.
+    ///
+    pub fn recently_played_since(
+        &self,
+        after: NaiveDateTime,
+    ) -> Result<Vec<PlayedTrack>, SpotifyError> {
+        let mut played_tracks = Vec::new(); // create vector to store played tracks
+        let mut cursor = after;
+
+        loop {
+            let page = self.get_recently_played_tracks(Some(cursor), None, Some(50))?; // request largest page allowed
+
+            if page.tracks.items.is_empty() {
+                break; // caught up
+            }
+            played_tracks.extend(page.tracks.items);
+
+            match page.cursors.after {
+                Some(next_cursor) if next_cursor > cursor => cursor = next_cursor,
+                _ => break, // cursor exhausted, i.e. nothing newer
+            }
+        }
+
+        played_tracks.retain(|played_track| {
+            played_track
+                .played_at
+                .is_none_or(|played_at| played_at > after)
+        }); // only items newer than the given time
+        played_tracks.sort_by_key(|played_track| played_track.played_at); // chronological order
+
+        Ok(played_tracks)
     }
 
-    /// Returns the user's currently playing track and queue: <https://developer.spotify.com/documentation/web-api/reference/#/operations/get-queue>
-    /// Note: This wrapper currently only supports tracks and unexpected errors may occur if the queue contains episodes
+    /// Returns the user's currently playing item and queue: <https://developer.spotify.com/documentation/web-api/reference/#/operations/get-queue>
+    /// Both the currently playing item and the items in the queue can be either tracks or episodes. The currently playing
+    /// item is None when nothing is playing.
     ///
     /// Requires scope: user-read-currently-playing user-read-playback-state
     ///
-    pub fn get_users_queue(&self) -> Result<(Track, Vec<Track>), SpotifyError> {
+    pub fn get_users_queue(
+        &self,
+    ) -> Result<(Option<PlayableItem>, Vec<PlayableItem>), SpotifyError> {
         let url_extension = String::from("me/player/queue"); // create url extension
 
         self.check_scope("user-read-currently-playing user-read-playback-state")?; // check scope
 
         let response = self.spotify_request(&url_extension, RequestMethod::Get)?; // send request
 
-        let track = Track::new(&response["currently_playing"]); // get and format currently playing field
+        let currently_playing = match &response["currently_playing"] {
+            Null => None, // nothing playing (or empty response)
+            currently_playing => Some(PlayableItem::new(currently_playing)), // get and format currently playing field
+        };
 
-        let mut tracks = Vec::new(); // create vector to store tracks
+        let mut queue = Vec::new(); // create vector to store queued items
 
-        for track in response["queue"].members() {
-            tracks.push(Track::new(&track)); // add track to vector
+        for item in response["queue"].members() {
+            queue.push(PlayableItem::new(item)); // add track or episode to vector
         }
 
-        return Ok((track, tracks)); // return response
+        Ok((currently_playing, queue)) // return response
     }
 
     /// Adds specified item to the playback queue: <https://developer.spotify.com/documentation/web-api/reference/#/operations/add-to-queue>
@@ -429,16 +623,116 @@ impl Spotify {
         track_id: &str,
         device_id: Option<&str>,
     ) -> Result<(), SpotifyError> {
-        let mut url_extension = format!("me/player/queue?uri=spotify:track:{}", track_id); // create url extension (specifically with track as input)
+        let url_extension = format!("me/player/queue?uri=spotify:track:{}", track_id); // create url extension (specifically with track as input)
 
         self.check_scope("user-modify-playback-state")?; // check scope
 
-        if let Some(device_id) = device_id {
-            url_extension.push_str(&format!("&device_id={}", device_id)); // if device_id is supplied, then add it to url extension
-        };
-
-        self.spotify_request(&url_extension, RequestMethod::Post(HashMap::new()))?; // send request
+        self.player_command(
+            url_extension,
+            device_id,
+            RequestMethod::Post(HashMap::new()),
+        )?; // send request, on device_id if supplied
 
         return Ok(()); // return response
     }
+
+    /// Adds every track (and, for playlists, episode) of an album or playlist to the playback queue, in order: <https://developer.spotify.com/documentation/web-api/reference/#/operations/add-to-queue>
+    /// The queue endpoint takes a single item, so this makes one request per item (after paging through the context's items).
+    /// If a request fails, the items before it stay queued. Artists aren't supported, as they don't have a track listing.
+    ///
+    /// Requires scope: user-modify-playback-state
+    ///
+    /// # Arguments
+    /// * `context` - The album or playlist to queue
+    /// * `device_id` - The id of the device to add the tracks to
+    ///
+    pub fn queue_context(
+        &self,
+        context: SpotifyContext,
+        device_id: Option<&str>,
+    ) -> Result<(), SpotifyError> {
+        self.check_scope("user-modify-playback-state")?; // check scope
+
+        let uris: Vec<String> = match &context {
+            SpotifyContext::Album(album_id) => self
+                .get_all_album_tracks(album_id, None)?
+                .iter()
+                .filter_map(|track| track.spotify_uri())
+                .map(|uri| uri.to_string())
+                .collect(),
+            SpotifyContext::Playlist(playlist_id) => self
+                .all_playlist_tracks(playlist_id)?
+                .iter()
+                .filter_map(|item| item.spotify_uri()) // tracks and episodes
+                .map(|uri| uri.to_string())
+                .collect(),
+            SpotifyContext::Artist(_) => {
+                return Err(SpotifyError::InvalidRequest(String::from(
+                    "Only albums and playlists can be queued",
+                )))
+            }
+        }; // local files have no Spotify URI and can't be queued
+
+        if uris.len() > LARGE_QUEUE_SIZE {
+            log::warn!(
+                "queueing {} items from {}, one request per item",
+                uris.len(),
+                context.uri()
+            );
+        }
+
+        for uri in &uris {
+            let url_extension = format!("me/player/queue?uri={}", uri); // queue by uri, so episodes are queued as episodes
+
+            self.player_command(
+                url_extension,
+                device_id,
+                RequestMethod::Post(HashMap::new()),
+            )?; // send request, on device_id if supplied
+        }
+
+        return Ok(());
+    }
+
+    /// Sends a player command, on the device with `device_id` if supplied. If no device id is supplied and Spotify reports that no
+    /// device is active, the command is retried on the first available device when enabled with `set_auto_select_device`
+    ///
+    /// # Arguments
+    /// * `url_extension` - The url extension of the command, without a device id
+    /// * `device_id` - The id of the device to send the command to
+    /// * `request_method` - The type of request, along with its body
+    ///
+    fn player_command(
+        &self,
+        mut url_extension: String,
+        device_id: Option<&str>,
+        request_method: RequestMethod,
+    ) -> Result<(), SpotifyError> {
+        if let Some(device_id) = device_id {
+            append_query_parameter(&mut url_extension, "device_id", device_id); // if device_id is supplied, then add it to url extension
+        }
+
+        match self.spotify_request(&url_extension, request_method.clone()) {
+            Err(SpotifyError::NoActiveDevice(_))
+                if device_id.is_none() && self.auto_select_device() =>
+            {
+                let devices = self.get_available_devices()?;
+                let device = match devices.iter().find(|device| !device.is_restricted) {
+                    Some(device) => device,
+                    None => {
+                        return Err(SpotifyError::NoActiveDevice(String::from(
+                            "No available device to select",
+                        )))
+                    }
+                };
+
+                append_query_parameter(&mut url_extension, "device_id", &device.id);
+                self.spotify_request(&url_extension, request_method)?; // retry on the selected device
+
+                Ok(())
+            }
+            Err(error) => Err(error),
+            Ok(_) => Ok(()),
+        }
+    }
 }
@@ -1,31 +1,96 @@
 use crate::spotify::{
-    Playlist, PlaylistTrack, Spotify, SpotifyCollection, SpotifyError, SpotifyImage, SpotifyObject,
+    largest_image, ItemType, Market, PlayableItem, Playlist, PlaylistDiff, PlaylistSummary,
+    PlaylistTrack, PlaylistTracks, SnapshotId, Spotify, SpotifyCollection, SpotifyError,
+    SpotifyImage, SpotifyObject,
 };
-use crate::srequest::RequestMethod;
+use crate::srequest::{append_query_parameter, RequestMethod};
 use chrono::NaiveDateTime;
+use json::JsonValue;
 use serde_json::{Map, Number, Value};
 use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::thread;
+
+/// Largest page of playlist items Spotify returns per request
+const PLAYLIST_PAGE_SIZE: i32 = 100;
+
+/// Items of a requested page of a playlist, or the error requesting it
+type PlaylistPage = Result<Vec<PlaylistTrack>, SpotifyError>;
+
+/// Whether two playlist items are the same: tracks are compared with `Track::same_recording`, episodes by their URI
+///
+/// # Arguments
+/// * `a` - The first item
+/// * `b` - The second item
+///
+fn same_item(a: &PlayableItem, b: &PlayableItem) -> bool {
+    match (a, b) {
+        (PlayableItem::Track(a), PlayableItem::Track(b)) => a.same_recording(b),
+        _ => a.uri() == b.uri(),
+    }
+}
+
+/// Checks that a playlist isn't made both collaborative and public, which Spotify rejects
+///
+/// # Arguments
+/// * `public` - Whether the playlist will be public
+/// * `collaborative` - Whether the playlist will be collaborative
+///
+fn check_collaborative_private(
+    public: Option<bool>,
+    collaborative: Option<bool>,
+) -> Result<(), SpotifyError> {
+    if public == Some(true) && collaborative == Some(true) {
+        return Err(SpotifyError::InvalidRequest(String::from(
+            "collaborative playlists must be private",
+        )));
+    }
+
+    Ok(())
+}
+
+/// Reads the new snapshot id from the response to a playlist modification
+///
+/// # Arguments
+/// * `response` - The response to the modification
+///
+fn snapshot_id_from(response: &JsonValue) -> Result<SnapshotId, SpotifyError> {
+    match response["snapshot_id"].as_str() {
+        Some(snapshot_id) => Ok(SnapshotId(String::from(snapshot_id))),
+        None => Err(SpotifyError::RequestError(String::from(
+            "No snapshot id returned",
+        ))),
+    }
+}
 
 impl Spotify {
     /// Get a playlist owned by a Spotify user: <https://developer.spotify.com/documentation/web-api/reference/#/operations/get-playlist>
-    /// Note: no support for episodes at the moment so unexpected results may occur with playlists that contain episodes
+    /// Note: episodes are formatted as tracks, so only the fields they share with tracks will be filled in
     ///
     /// Required scope: none
     ///
     /// # Arguments
     /// * `playlist_id` - The Spotify ID of the playlist.
-    /// * `market` - An ISO 3166-1 alpha-2 country code.
+    /// * `market` - An ISO 3166-1 alpha-2 country code, or `Market::FromToken` for the current user's market.
+    /// * `additional_types` - The item types to include in the response. Default: tracks and episodes.
     ///
     pub fn get_playlist(
         &self,
         playlist_id: &str,
-        market: Option<&str>,
+        market: Option<Market>,
+        additional_types: Option<&[ItemType]>,
     ) -> Result<Playlist, SpotifyError> {
-        let mut url_extension = format!("playlists/{}?additional_types=track", playlist_id); // base url. Currently this only supports tracks, not episodes
+        let additional_types =
+            ItemType::join(additional_types.unwrap_or(&[ItemType::Track, ItemType::Episode])); // default to tracks and episodes
+        let mut url_extension = format!(
+            "playlists/{}?additional_types={}",
+            playlist_id, additional_types
+        ); // base url
 
         if let Some(market) = market {
             // if market is set, add to url
-            url_extension.push_str(&format!("&market={}", market));
+            append_query_parameter(&mut url_extension, "market", market.code());
         }
 
         let response = self.spotify_request(&url_extension, RequestMethod::Get)?; // make request
@@ -66,6 +131,8 @@ impl Spotify {
             ));
         }
 
+        check_collaborative_private(public, collaborative)?;
+
         if let Some(name) = name {
             body.insert(String::from("name"), Value::String(String::from(name)));
         }
@@ -90,44 +157,100 @@ impl Spotify {
         Ok(())
     }
 
+    /// Make a playlist public or private, leaving its other details untouched: <https://developer.spotify.com/documentation/web-api/reference/#/operations/change-playlist-details>
+    ///
+    /// Required scope: playlist-modify-public playlist-modify-private
+    ///
+    /// # Arguments
+    /// * `playlist_id` - The Spotify ID of the playlist.
+    /// * `public` - If true the playlist will be public, if false it will be private.
+    ///
+    pub fn set_playlist_public(&self, playlist_id: &str, public: bool) -> Result<(), SpotifyError> {
+        return self.change_playlist_details(playlist_id, None, Some(public), None, None);
+    }
+
+    /// Rename a playlist, leaving its other details untouched: <https://developer.spotify.com/documentation/web-api/reference/#/operations/change-playlist-details>
+    ///
+    /// Required scope: playlist-modify-public playlist-modify-private
+    ///
+    /// # Arguments
+    /// * `playlist_id` - The Spotify ID of the playlist.
+    /// * `name` - The new name for the playlist.
+    ///
+    pub fn rename_playlist(&self, playlist_id: &str, name: &str) -> Result<(), SpotifyError> {
+        return self.change_playlist_details(playlist_id, Some(name), None, None, None);
+    }
+
+    /// Change the description of a playlist, leaving its other details untouched: <https://developer.spotify.com/documentation/web-api/reference/#/operations/change-playlist-details>
+    ///
+    /// Required scope: playlist-modify-public playlist-modify-private
+    ///
+    /// # Arguments
+    /// * `playlist_id` - The Spotify ID of the playlist.
+    /// * `description` - Value for playlist description as displayed in Spotify Clients and in the Web API.
+    ///
+    pub fn set_playlist_description(
+        &self,
+        playlist_id: &str,
+        description: &str,
+    ) -> Result<(), SpotifyError> {
+        return self.change_playlist_details(playlist_id, None, None, None, Some(description));
+    }
+
     /// Get all items in playlist: <https://developer.spotify.com/documentation/web-api/reference/#/operations/get-playlists-tracks>
-    /// Note: no support for episodes at the moment so unexpected results may occur with playlists that contain episodes
+    /// Note: episodes are formatted as tracks, so only the fields they share with tracks will be filled in
     ///
     /// Required scope: none
     ///
     /// # Arguments
     /// * `playlist_id` - The Spotify ID of the playlist.
-    /// * `market` - An ISO 3166-1 alpha-2 country code.
+    /// * `market` - An ISO 3166-1 alpha-2 country code, or `Market::FromToken` for the current user's market.
     /// * `limit` - The maximum number of items to return. Default: 100. Minimum: 0. Maximum: 100.
     /// * `offset` - The index of the first item to return. Default: 0 (the first object). Use with limit to get the next set of items.
+    /// * `additional_types` - The item types to include in the response. Default: tracks and episodes.
+    ///
+    /// Returns the requested page along with the total number of items in the playlist, so the playlist's size is known without requesting the playlist itself.
+    /// To keep only items added within a time range, use `filter_added` on the returned page.
     ///
     pub fn get_playlist_tracks(
         &self,
         playlist_id: &str,
-        market: Option<&str>,
+        market: Option<Market>,
         limit: Option<i32>,
         offset: Option<i32>,
-    ) -> Result<SpotifyCollection<PlaylistTrack>, SpotifyError> {
-        let mut url_extension = format!("playlists/{}/tracks?additional_types=track", playlist_id); // base url. Currently this only supports tracks, not episodes
+        additional_types: Option<&[ItemType]>,
+    ) -> Result<PlaylistTracks, SpotifyError> {
+        let additional_types =
+            ItemType::join(additional_types.unwrap_or(&[ItemType::Track, ItemType::Episode])); // default to tracks and episodes
+        let mut url_extension = format!(
+            "playlists/{}/tracks?additional_types={}",
+            playlist_id, additional_types
+        ); // base url
 
         if let Some(market) = market {
             // if market is set, add to url
-            url_extension.push_str(&format!("&market={}", market));
+            append_query_parameter(&mut url_extension, "market", market.code());
         }
 
         if let Some(limit) = limit {
             // if limit is set, add to url
-            url_extension.push_str(&format!("&limit={}", limit));
+            append_query_parameter(&mut url_extension, "limit", limit);
         }
 
         if let Some(offset) = offset {
             // if offset is set, add to url
-            url_extension.push_str(&format!("&offset={}", offset));
+            append_query_parameter(&mut url_extension, "offset", offset);
         }
 
         let response = self.spotify_request(&url_extension, RequestMethod::Get)?; // make request
 
-        return Ok(SpotifyCollection::<PlaylistTrack>::new(&response)); // format and return result
+        let playlist_tracks = SpotifyCollection::<PlaylistTrack>::new(&response); // format result
+        let total = playlist_tracks.total; // size of the whole playlist, not of the page
+
+        return Ok(PlaylistTracks {
+            tracks: playlist_tracks,
+            total,
+        });
     }
 
     /// Add one or more tracks to a user's playlist: <https://developer.spotify.com/documentation/web-api/reference/#/operations/add-tracks-to-playlist>
@@ -140,12 +263,14 @@ impl Spotify {
     /// * `track_ids` - A list of Spotify track URIs to add, can be a maximum of 100.
     /// * `position` - The position to insert the tracks, a zero-based index. For example, to insert the tracks in the first position: position=0; to insert the tracks in the third position: position=2. If omitted, the tracks will be appended to the playlist.
     ///
+    /// Returns the new snapshot ID of the playlist.
+    ///
     pub fn add_tracks_to_playlist(
         &self,
         playlist_id: &str,
         track_ids: Vec<&str>,
         position: Option<i32>,
-    ) -> Result<(), SpotifyError> {
+    ) -> Result<SnapshotId, SpotifyError> {
         let url_extension = format!("playlists/{}/tracks", playlist_id); // base url
 
         self.check_scope("playlist-modify-public playlist-modify-private")?;
@@ -177,9 +302,9 @@ impl Spotify {
             );
         }
 
-        self.spotify_request(&url_extension, RequestMethod::Post(body))?; // make request
+        let response = self.spotify_request(&url_extension, RequestMethod::Post(body))?; // make request
 
-        Ok(())
+        return snapshot_id_from(&response); // return snapshot id
     }
 
     /// Replace tracks in user's playlist. A derivative of: <https://developer.spotify.com/documentation/web-api/reference/#/operations/reorder-or-replace-playlists-tracks>
@@ -196,7 +321,7 @@ impl Spotify {
         &self,
         playlist_id: &str,
         track_ids: Vec<&str>,
-    ) -> Result<String, SpotifyError> {
+    ) -> Result<SnapshotId, SpotifyError> {
         let url_extension = format!("playlists/{}/tracks", playlist_id); // base url
 
         self.check_scope("playlist-modify-public playlist-modify-private")?;
@@ -214,13 +339,7 @@ impl Spotify {
 
         let response = self.spotify_request(&url_extension, RequestMethod::Put(body))?; // make request
 
-        return match response["snapshot_id"].as_str() {
-            // return snapshot id
-            Some(snapshot_id) => Ok(String::from(snapshot_id)),
-            None => Err(SpotifyError::RequestError(String::from(
-                "No snapshot id returned",
-            ))),
-        };
+        return snapshot_id_from(&response); // return snapshot id
     }
 
     /// Reorder tracks in user's playlist. A derivative of: <https://developer.spotify.com/documentation/web-api/reference/#/operations/reorder-or-replace-playlists-tracks>
@@ -233,7 +352,7 @@ impl Spotify {
     /// * `range_start` - The position of the first track to be reordered.
     /// * `insert_before` - The position where the tracks should be inserted.
     /// * `range_length` - The amount of tracks to be reordered. Defaults to 1 if not set.
-    /// * `snapshot_id` - The playlist's snapshot ID against which you want to make the changes.
+    /// * `snapshot_id` - The playlist's snapshot ID against which you want to make the changes. If the playlist changed since, a `SpotifyError::SnapshotMismatch` is returned.
     ///
     pub fn reorder_playlist_tracks(
         &self,
@@ -242,7 +361,7 @@ impl Spotify {
         insert_before: i32,
         range_length: Option<i32>,
         snapshot_id: Option<&str>,
-    ) -> Result<String, SpotifyError> {
+    ) -> Result<SnapshotId, SpotifyError> {
         let url_extension = format!("playlists/{}/tracks", playlist_id); // base url
 
         self.check_scope("playlist-modify-public playlist-modify-private")?;
@@ -276,13 +395,66 @@ impl Spotify {
 
         let response = self.spotify_request(&url_extension, RequestMethod::Put(body))?; // make request
 
-        return match response["snapshot_id"].as_str() {
-            // return snapshot id
-            Some(snapshot_id) => Ok(String::from(snapshot_id)),
-            None => Err(SpotifyError::RequestError(String::from(
-                "No snapshot id returned",
-            ))),
+        return snapshot_id_from(&response); // return snapshot id
+    }
+
+    /// Move a single track of a playlist to a new position, looking up its current position by uri. Pages through the playlist's
+    /// items to find the track, then reorders it: <https://developer.spotify.com/documentation/web-api/reference/#/operations/reorder-or-replace-playlists-tracks>
+    /// Returns the new snapshot ID of the playlist. If the track appears more than once, its first occurrence is moved.
+    ///
+    /// Required scope: playlist-modify-public playlist-modify-private
+    ///
+    /// # Arguments
+    /// * `playlist_id` - The Spotify ID of the playlist.
+    /// * `track_uri` - The Spotify URI of the track to move.
+    /// * `new_position` - The position the track should end up at, a zero-based index.
+    ///
+    pub fn move_playlist_track(
+        &self,
+        playlist_id: &str,
+        track_uri: &str,
+        new_position: i32,
+    ) -> Result<SnapshotId, SpotifyError> {
+        self.check_scope("playlist-modify-public playlist-modify-private")?;
+
+        let mut offset = 0;
+        let current_position = loop {
+            let page = self
+                .get_playlist_tracks(playlist_id, None, Some(100), Some(offset), None)? // request largest page allowed
+                .tracks;
+
+            // search all items rather than uris(), which skips local files and would shift the position
+            if let Some(index) = page
+                .items
+                .iter()
+                .position(|item| item.track.uri() == track_uri)
+            {
+                break offset + index as i32; // found track
+            }
+
+            if page.next.is_none() || page.items.is_empty() {
+                return Err(SpotifyError::NotFound(format!(
+                    "{} is not in playlist {}",
+                    track_uri, playlist_id
+                ))); // reached end of playlist without finding track
+            }
+
+            offset += page.items.len() as i32;
+        };
+
+        let insert_before = if new_position > current_position {
+            new_position + 1 // the track is removed from before the insertion point, so insert one further along
+        } else {
+            new_position
         };
+
+        return self.reorder_playlist_tracks(
+            playlist_id,
+            current_position,
+            insert_before,
+            None,
+            None,
+        );
     }
 
     /// Remove tracks from user's playlist: <https://developer.spotify.com/documentation/web-api/reference/#/operations/remove-tracks-playlist>
@@ -294,14 +466,14 @@ impl Spotify {
     /// # Arguments
     /// * `playlist_id` - The Spotify ID of the playlist.
     /// * `track_ids` - A list of Spotify track URIs to remove, can be a maximum of 100.
-    /// * `snapshot_id` - The playlist's snapshot ID against which you want to make the changes.
+    /// * `snapshot_id` - The playlist's snapshot ID against which you want to make the changes. If the playlist changed since, a `SpotifyError::SnapshotMismatch` is returned.
     ///
     pub fn remove_playlist_tracks(
         &self,
         playlist_id: &str,
         track_ids: Vec<&str>,
         snapshot_id: Option<&str>,
-    ) -> Result<String, SpotifyError> {
+    ) -> Result<SnapshotId, SpotifyError> {
         let url_extension = format!("playlists/{}/tracks", playlist_id); // base url
 
         self.check_scope("playlist-modify-public playlist-modify-private")?;
@@ -339,13 +511,7 @@ impl Spotify {
 
         let response = self.spotify_request(&url_extension, RequestMethod::Delete(body))?; // make request
 
-        return match response["snapshot_id"].as_str() {
-            // return snapshot id
-            Some(snapshot_id) => Ok(String::from(snapshot_id)),
-            None => Err(SpotifyError::RequestError(String::from(
-                "No snapshot id returned",
-            ))),
-        };
+        return snapshot_id_from(&response); // return snapshot id
     }
 
     /// Get current user's playlists: <https://developer.spotify.com/documentation/web-api/reference/#/operations/get-a-list-of-current-users-playlists>
@@ -355,29 +521,31 @@ impl Spotify {
     /// # Arguments
     /// * `limit` - The maximum number of playlists to return. Default: 20. Minimum: 1. Maximum: 50.
     /// * `offset` - The index of the first playlist to return. Default: 0 (the first object). Use with limit to get the next set of playlists.
+    /// * `fields` - Filters for the query: a comma-separated list of the fields to return, i.e. `items(id,name,tracks.total),next`. If omitted, all fields are returned. Fields left out are given their default values.
     ///
     pub fn get_current_users_playlists(
         &self,
         limit: Option<i32>,
         offset: Option<i32>,
+        fields: Option<&str>,
     ) -> Result<SpotifyCollection<Playlist>, SpotifyError> {
         let mut url_extension = String::from("me/playlists"); // base url
 
         self.check_scope("playlist-read-private playlist-read-collaborative")?;
 
-        if !limit.is_none() || !offset.is_none() {
-            // if one optional parameter is specified
-            url_extension.push_str("?"); // add ? to url
-        }
-
         if let Some(limit) = limit {
             // if limit is set, add to url
-            url_extension.push_str(&format!("limit={}&", limit));
+            append_query_parameter(&mut url_extension, "limit", limit);
         }
 
         if let Some(offset) = offset {
             // if offset is set, add to url
-            url_extension.push_str(&format!("offset={}&", offset));
+            append_query_parameter(&mut url_extension, "offset", offset);
+        }
+
+        if let Some(fields) = fields {
+            // if fields is set, add to url
+            append_query_parameter(&mut url_extension, "fields", fields);
         }
 
         let response = self.spotify_request(&url_extension, RequestMethod::Get)?; // make request
@@ -385,6 +553,82 @@ impl Spotify {
         return Ok(SpotifyCollection::<Playlist>::new(&response)); // return playlists
     }
 
+    /// Get a summary (id, name and track count) of every one of the current user's playlists. Pages through all of the
+    /// user's playlists: <https://developer.spotify.com/documentation/web-api/reference/#/operations/get-a-list-of-current-users-playlists>
+    ///
+    /// Required scope: playlist-read-private playlist-read-collaborative
+    ///
+    pub fn list_playlists_summary(&self) -> Result<Vec<PlaylistSummary>, SpotifyError> {
+        self.check_scope("playlist-read-private playlist-read-collaborative")?;
+
+        let mut playlists = Vec::new(); // create vector to store playlist summaries
+        let mut offset = 0;
+
+        loop {
+            let url_extension = format!("me/playlists?limit=50&offset={}", offset); // request largest page allowed
+
+            let response = self.spotify_request(&url_extension, RequestMethod::Get)?; // make request
+
+            let page = SpotifyCollection::<PlaylistSummary>::new(&response);
+            offset += page.items.len();
+            let is_last_page = page.next.is_none() || page.items.is_empty(); // stop when there is no next page
+            playlists.extend(page.items);
+
+            if is_last_page {
+                break;
+            }
+        }
+
+        Ok(playlists)
+    }
+
+    /// Get every one of the current user's playlists that they own, paging through all of their playlists: <https://developer.spotify.com/documentation/web-api/reference/#/operations/get-a-list-of-current-users-playlists>
+    /// Unlike followed playlists, these can always be edited by the user.
+    ///
+    /// Required scope: playlist-read-private playlist-read-collaborative
+    ///
+    pub fn get_owned_playlists(&self) -> Result<Vec<Playlist>, SpotifyError> {
+        let user_id = self.current_user_id()?; // cached after the first call
+
+        let mut playlists = self.get_all_current_users_playlists()?;
+        playlists.retain(|playlist| playlist.owner.id == user_id); // keep playlists owned by the user
+
+        Ok(playlists)
+    }
+
+    /// Get every playlist the current user follows but doesn't own, paging through all of their playlists: <https://developer.spotify.com/documentation/web-api/reference/#/operations/get-a-list-of-current-users-playlists>
+    ///
+    /// Required scope: playlist-read-private playlist-read-collaborative
+    ///
+    pub fn get_followed_playlists(&self) -> Result<Vec<Playlist>, SpotifyError> {
+        let user_id = self.current_user_id()?; // cached after the first call
+
+        let mut playlists = self.get_all_current_users_playlists()?;
+        playlists.retain(|playlist| playlist.owner.id != user_id); // keep playlists owned by someone else
+
+        Ok(playlists)
+    }
+
+    /// Pages through all of the current user's playlists, both owned and followed
+    fn get_all_current_users_playlists(&self) -> Result<Vec<Playlist>, SpotifyError> {
+        let mut playlists = Vec::new(); // create vector to store playlists
+        let mut offset = 0;
+
+        loop {
+            let page = self.get_current_users_playlists(Some(50), Some(offset), None)?; // request largest page allowed
+
+            offset += page.items.len() as i32;
+            let is_last_page = page.next.is_none() || page.items.is_empty(); // stop when there is no next page
+            playlists.extend(page.items);
+
+            if is_last_page {
+                break;
+            }
+        }
+
+        Ok(playlists)
+    }
+
     /// Get a specified user's playlists: <https://developer.spotify.com/documentation/web-api/reference/#/operations/get-list-users-playlists>
     ///
     /// Required scope: playlist-read-private playlist-read-collaborative
@@ -393,30 +637,32 @@ impl Spotify {
     /// * `user_id` - The user's Spotify user ID.
     /// * `limit` - The maximum number of playlists to return. Default: 20. Minimum: 1. Maximum: 50.
     /// * `offset` - The index of the first playlist to return. Default: 0 (the first object). Use with limit to get the next set of playlists.
+    /// * `fields` - Filters for the query: a comma-separated list of the fields to return, i.e. `items(id,name,tracks.total),next`. If omitted, all fields are returned. Fields left out are given their default values.
     ///
     pub fn get_users_playlists(
         &self,
         user_id: &str,
         limit: Option<i32>,
         offset: Option<i32>,
+        fields: Option<&str>,
     ) -> Result<SpotifyCollection<Playlist>, SpotifyError> {
         let mut url_extension = format!("users/{}/playlists", user_id); // base url
 
         self.check_scope("playlist-read-private playlist-read-collaborative")?;
 
-        if !limit.is_none() || !offset.is_none() {
-            // if one optional parameter is specified
-            url_extension.push_str("?"); // add ? to url
-        }
-
         if let Some(limit) = limit {
             // if limit is set, add to url
-            url_extension.push_str(&format!("limit={}&", limit));
+            append_query_parameter(&mut url_extension, "limit", limit);
         }
 
         if let Some(offset) = offset {
             // if offset is set, add to url
-            url_extension.push_str(&format!("offset={}&", offset));
+            append_query_parameter(&mut url_extension, "offset", offset);
+        }
+
+        if let Some(fields) = fields {
+            // if fields is set, add to url
+            append_query_parameter(&mut url_extension, "fields", fields);
         }
 
         let response = self.spotify_request(&url_extension, RequestMethod::Get)?; // make request
@@ -447,6 +693,8 @@ impl Spotify {
 
         self.check_scope("playlist-modify-public playlist-modify-private")?;
 
+        check_collaborative_private(Some(public.unwrap_or(true)), collaborative)?; // playlists are public unless set otherwise
+
         let mut body: HashMap<String, Value> = HashMap::new(); // create body
 
         body.insert(String::from("name"), Value::String(String::from(name))); // insert name into body
@@ -474,7 +722,222 @@ impl Spotify {
         return Ok(Playlist::new(&response)); // return playlist
     }
 
-    /// Gets playlists featured in Browse tab: <https://developer.spotify.com/documentation/web-api/reference/#/operations/get-featured-playlists>
+    /// Create a playlist for the current user, without having to know their user id (see [current_user_id](struct.Spotify.html#method.current_user_id)): <https://developer.spotify.com/documentation/web-api/reference/#/operations/create-playlist>
+    ///
+    /// Required scope: playlist-modify-public playlist-modify-private
+    ///
+    /// # Arguments
+    /// * `name` - The name for the new playlist
+    /// * `public` - Defaults to true. If true the playlist will be public, if false it will be private.
+    /// * `collaborative` - Defaults to false. If true the playlist will be collaborative. Note that to create a collaborative playlist you must also set public to false.
+    /// * `description` - Value for playlist description as displayed in Spotify Clients and in the Web API.
+    ///
+    pub fn create_playlist_for_me(
+        &self,
+        name: &str,
+        public: Option<bool>,
+        collaborative: Option<bool>,
+        description: Option<&str>,
+    ) -> Result<Playlist, SpotifyError> {
+        self.check_scope("playlist-modify-public playlist-modify-private")?; // check before fetching the user id
+
+        let user_id = self.current_user_id()?; // get (cached) id of current user
+
+        self.create_playlist(&user_id, name, public, collaborative, description)
+    }
+
+    /// Create a playlist for the current user and add tracks to it. The tracks are added in batches of 100 (<https://developer.spotify.com/documentation/web-api/reference/#/operations/add-tracks-to-playlist>).
+    /// Returns the playlist as it is after the tracks were added. If adding tracks fails, the playlist is deleted again (unfollowed)
+    /// and the error is returned. Should deleting fail too, the playlist is left with the tracks added so far.
+    ///
+    /// Required scope: playlist-modify-public playlist-modify-private
+    ///
+    /// # Arguments
+    /// * `name` - The name for the new playlist
+    /// * `track_uris` - The Spotify URIs of the tracks (or episodes) to add, in order. Any number of URIs.
+    /// * `public` - Defaults to true. If true the playlist will be public, if false it will be private.
+    /// * `description` - Value for playlist description as displayed in Spotify Clients and in the Web API.
+    ///
+    pub fn create_playlist_with_tracks(
+        &self,
+        name: &str,
+        track_uris: Vec<&str>,
+        public: Option<bool>,
+        description: Option<&str>,
+    ) -> Result<Playlist, SpotifyError> {
+        let playlist = self.create_playlist_for_me(name, public, None, description)?; // create empty playlist
+
+        if track_uris.is_empty() {
+            return Ok(playlist); // nothing to add
+        }
+
+        let url_extension = format!("playlists/{}/tracks", playlist.id); // base url
+
+        // Spotify adds at most 100 items per request, adding batches in order keeps the overall order
+        for track_uris_batch in track_uris.chunks(100) {
+            let mut body: HashMap<String, Value> = HashMap::new(); // create body
+            body.insert(
+                String::from("uris"),
+                Value::Array(
+                    track_uris_batch
+                        .iter()
+                        .map(|uri| Value::String(uri.to_string()))
+                        .collect(),
+                ),
+            ); // add track uris to body
+
+            if let Err(error) = self.spotify_request(&url_extension, RequestMethod::Post(body)) {
+                let _ = self.unfollow_playlist(&playlist.id); // roll back, the error to report is the one adding tracks
+                return Err(error);
+            }
+        }
+
+        self.get_playlist(&playlist.id, None, None) // get playlist with its tracks
+    }
+
+    /// Compares the tracks of two playlists, paging through both playlists completely (<https://developer.spotify.com/documentation/web-api/reference/#/operations/get-playlists-tracks>).
+    /// Tracks are compared with `Track::same_recording`, so the same recording released in different markets isn't reported as a difference.
+    /// Episodes are compared by their URI.
+    /// Each list keeps the order of its playlist and contains duplicates as often as the playlist does.
+    ///
+    /// Required scope: none
+    ///
+    /// # Arguments
+    /// * `a` - The Spotify ID of the first playlist.
+    /// * `b` - The Spotify ID of the second playlist.
+    ///
+    pub fn diff_playlists(&self, a: &str, b: &str) -> Result<PlaylistDiff, SpotifyError> {
+        let items_a = self.all_playlist_tracks(a)?;
+        let items_b = self.all_playlist_tracks(b)?;
+
+        let mut diff = PlaylistDiff {
+            only_in_a: Vec::new(),
+            only_in_b: Vec::new(),
+            common: Vec::new(),
+        };
+
+        for item in &items_a {
+            if items_b.iter().any(|other| same_item(item, other)) {
+                diff.common.push(item.uri().to_string()); // uri as it is in playlist a
+            } else {
+                diff.only_in_a.push(item.uri().to_string());
+            }
+        }
+
+        for item in &items_b {
+            if !items_a.iter().any(|other| same_item(item, other)) {
+                diff.only_in_b.push(item.uri().to_string());
+            }
+        }
+
+        Ok(diff)
+    }
+
+    /// Gets every item (track or episode) of a playlist, paging through the playlist until there is no next page
+    ///
+    /// # Arguments
+    /// * `playlist_id` - The Spotify ID of the playlist.
+    ///
+    pub(crate) fn all_playlist_tracks(
+        &self,
+        playlist_id: &str,
+    ) -> Result<Vec<PlayableItem>, SpotifyError> {
+        let mut tracks = Vec::new(); // create vector to store tracks
+        let mut offset = 0;
+
+        loop {
+            let page = self
+                .get_playlist_tracks(playlist_id, None, Some(100), Some(offset), None)?
+                .tracks; // request largest page allowed
+
+            offset += page.items.len() as i32;
+            let is_last_page = page.next.is_none() || page.items.is_empty(); // stop when there is no next page
+            tracks.extend(page.items.into_iter().map(|item| item.track));
+
+            if is_last_page {
+                break;
+            }
+        }
+
+        Ok(tracks)
+    }
+
+    /// Get every item in a playlist, requesting pages concurrently: <https://developer.spotify.com/documentation/web-api/reference/#/operations/get-playlists-tracks>
+    /// The first page gives the size of the playlist, so the offsets of the remaining pages are known up front and up to
+    /// `concurrency` of them are requested at the same time. Much faster than paging one page at a time for large playlists.
+    ///
+    /// Required scope: none
+    ///
+    /// # Arguments
+    /// * `playlist_id` - The Spotify ID of the playlist.
+    /// * `concurrency` - The maximum number of pages requested at the same time. Minimum: 1.
+    ///
+    /// Returns the items in playlist order. If a page fails, no further pages are requested and the error of the first failed
+    /// page is returned.
+    ///
+    pub fn get_all_playlist_tracks_concurrent(
+        &self,
+        playlist_id: &str,
+        concurrency: usize,
+    ) -> Result<Vec<PlaylistTrack>, SpotifyError> {
+        if concurrency == 0 {
+            return Err(SpotifyError::InvalidRequest(String::from(
+                "Concurrency must be at least 1",
+            )));
+        }
+
+        let first_page =
+            self.get_playlist_tracks(playlist_id, None, Some(PLAYLIST_PAGE_SIZE), Some(0), None)?;
+        let offsets: Vec<i32> = (PLAYLIST_PAGE_SIZE..first_page.total)
+            .step_by(PLAYLIST_PAGE_SIZE as usize)
+            .collect(); // offsets of the remaining pages
+
+        let next_page = AtomicUsize::new(0); // index into offsets of the next page to request
+        let failed = AtomicBool::new(false); // set once a page fails, so no more pages are requested
+        let pages: Mutex<Vec<Option<PlaylistPage>>> =
+            Mutex::new(offsets.iter().map(|_| None).collect());
+
+        thread::scope(|scope| {
+            for _ in 0..concurrency.min(offsets.len()) {
+                scope.spawn(|| loop {
+                    if failed.load(Ordering::SeqCst) {
+                        return; // an earlier page failed
+                    }
+
+                    let index = next_page.fetch_add(1, Ordering::SeqCst);
+                    if index >= offsets.len() {
+                        return; // every page was requested
+                    }
+
+                    let page = self
+                        .get_playlist_tracks(
+                            playlist_id,
+                            None,
+                            Some(PLAYLIST_PAGE_SIZE),
+                            Some(offsets[index]),
+                            None,
+                        )
+                        .map(|page| page.tracks.items);
+                    if page.is_err() {
+                        failed.store(true, Ordering::SeqCst);
+                    }
+
+                    pages.lock().unwrap()[index] = Some(page);
+                });
+            }
+        });
+
+        let mut items = first_page.tracks.items;
+
+        // pages are requested in offset order, so pages that weren't requested (None) come after the failed page whose error is returned
+        for page in pages.into_inner().unwrap().into_iter().flatten() {
+            items.extend(page?);
+        }
+
+        return Ok(items);
+    }
+
+    /// Gets playlists featured in Browse tab, along with the editorial message shown above them (i.e. "Monday morning vibes"): <https://developer.spotify.com/documentation/web-api/reference/#/operations/get-featured-playlists>
     ///
     /// Required scope: none
     ///
@@ -492,50 +955,49 @@ impl Spotify {
         limit: Option<i32>,
         offset: Option<i32>,
         timestamp: Option<NaiveDateTime>,
-    ) -> Result<SpotifyCollection<Playlist>, SpotifyError> {
+    ) -> Result<(String, SpotifyCollection<Playlist>), SpotifyError> {
         let mut url_extension = String::from("browse/featured-playlists"); // base url
 
-        if !country.is_none()
-            || !locale.is_none()
-            || !limit.is_none()
-            || !offset.is_none()
-            || !timestamp.is_none()
-        {
-            // if one optional parameter is specified
-            url_extension.push_str("?"); // add ? to url
-        }
-
         if let Some(country) = country {
             // if country is set, add to url
-            url_extension.push_str(&format!("country={}&", country));
+            append_query_parameter(&mut url_extension, "country", country);
         }
 
         if let Some(locale) = locale {
             // if locale is set, add to url
-            url_extension.push_str(&format!("locale={}&", locale));
+            append_query_parameter(&mut url_extension, "locale", locale);
         }
 
         if let Some(limit) = limit {
             // if limit is set, add to url
-            url_extension.push_str(&format!("limit={}&", limit));
+            append_query_parameter(&mut url_extension, "limit", limit);
         }
 
         if let Some(offset) = offset {
             // if offset is set, add to url
-            url_extension.push_str(&format!("offset={}&", offset));
+            append_query_parameter(&mut url_extension, "offset", offset);
         }
 
         if let Some(timestamp) = timestamp {
             // if timestamp is set, add to url
-            url_extension.push_str(&format!(
-                "timestamp={}&",
-                timestamp.format("%Y-%m-%dT%H:%M:%S")
-            ));
+            append_query_parameter(
+                &mut url_extension,
+                "timestamp",
+                timestamp.format("%Y-%m-%dT%H:%M:%S"),
+            );
         }
 
         let response = self.spotify_request(&url_extension, RequestMethod::Get)?; // make request
 
-        return Ok(SpotifyCollection::<Playlist>::new(&response["playlists"])); // return playlists (or albums?)
+        let message = match response["message"].as_str() {
+            Some(message) => message.to_string(),
+            None => String::new(), // default to empty string
+        };
+
+        return Ok((
+            message,
+            SpotifyCollection::<Playlist>::new(&response["playlists"]),
+        )); // return editorial message and playlists
     }
 
     /// Get a set of Spotify playlsits tagged with a particular category: <https://developer.spotify.com/documentation/web-api/reference/#/operations/get-a-categories-playlists>
@@ -557,24 +1019,19 @@ impl Spotify {
     ) -> Result<SpotifyCollection<Playlist>, SpotifyError> {
         let mut url_extension = format!("browse/categories/{}/playlists", category_id); // base url
 
-        if !country.is_none() || !limit.is_none() || !offset.is_none() {
-            // if one optional parameter is specified
-            url_extension.push_str("?"); // add ? to url
-        }
-
         if let Some(country) = country {
             // if country is set, add to url
-            url_extension.push_str(&format!("country={}&", country));
+            append_query_parameter(&mut url_extension, "country", country);
         }
 
         if let Some(limit) = limit {
             // if limit is set, add to url
-            url_extension.push_str(&format!("limit={}&", limit));
+            append_query_parameter(&mut url_extension, "limit", limit);
         }
 
         if let Some(offset) = offset {
             // if offset is set, add to url
-            url_extension.push_str(&format!("offset={}&", offset));
+            append_query_parameter(&mut url_extension, "offset", offset);
         }
 
         let response = self.spotify_request(&url_extension, RequestMethod::Get)?; // make request
@@ -606,5 +1063,60 @@ impl Spotify {
         return Ok(images); // return images
     }
 
+    /// Gets the URL of the largest image associated with a playlist: <https://developer.spotify.com/documentation/web-api/reference/#/operations/get-playlist-cover>
+    /// Returns None if the playlist has no cover image.
+    ///
+    /// Required scope: none
+    ///
+    /// # Arguments
+    /// * `playlist_id` - The Spotify ID for the playlist.
+    ///
+    pub fn get_playlist_cover_url(
+        &self,
+        playlist_id: &str,
+    ) -> Result<Option<String>, SpotifyError> {
+        let images = self.get_playlist_cover_image(playlist_id)?; // all sizes of the cover
+
+        return Ok(largest_image(&images).map(|image| image.url.clone())); // None if there are no images
+    }
+
     // TODO: implement: https://developer.spotify.com/documentation/web-api/reference/#/operations/upload-custom-playlist-cover. However, unclear how image is uploaded.
 }
+
+impl SpotifyCollection<PlaylistTrack> {
+    /// Returns the Spotify URIs of the tracks in the collection, in order. Local files are skipped, as their URIs can't be used with other endpoints
+    pub fn uris(&self) -> Vec<String> {
+        self.items
+            .iter()
+            .filter_map(|item| item.track.spotify_uri())
+            .map(|uri| uri.to_string())
+            .collect()
+    }
+
+    /// Keeps only the items added within the given (inclusive) time range. Items without an `added_at` time are removed whenever a bound is given.
+    /// This only filters the items already in the collection: `total`, `next` and `previous` still describe the unfiltered playlist, so a page may come back with fewer than `limit` items.
+    ///
+    /// # Arguments
+    /// * `added_after` - Only keep items added at or after this time
+    /// * `added_before` - Only keep items added at or before this time
+    ///
+    pub fn filter_added(
+        mut self,
+        added_after: Option<NaiveDateTime>,
+        added_before: Option<NaiveDateTime>,
+    ) -> SpotifyCollection<PlaylistTrack> {
+        if added_after.is_none() && added_before.is_none() {
+            return self; // nothing to filter
+        }
+
+        self.items.retain(|item| match item.added_at {
+            Some(added_at) => {
+                added_after.is_none_or(|after| added_at >= after)
+                    && added_before.is_none_or(|before| added_at <= before)
+            }
+            None => false, // can't tell when the item was added
+        });
+
+        self
+    }
+}
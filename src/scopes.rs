@@ -0,0 +1,32 @@
+use crate::spotify::{Scope, Spotify, SpotifyError};
+
+impl TryFrom<&str> for Scope {
+    type Error = SpotifyError;
+
+    /// Creates a scope from its name, checking that Spotify defines it
+    ///
+    /// # Arguments
+    /// * `scope` - The name of the scope, i.e. `"user-read-private"`
+    ///
+    fn try_from(scope: &str) -> Result<Scope, SpotifyError> {
+        match Scope::ALL.iter().find(|known| known.as_str() == scope) {
+            Some(known) => Ok(*known),
+            None => Err(SpotifyError::InvalidRequest(format!(
+                "{} is not a Spotify scope",
+                scope
+            ))),
+        }
+    }
+}
+
+impl Spotify {
+    /// Returns the scopes the client was authorized with, i.e. to show which permissions the user granted.
+    /// Scopes unknown to this library are left out. Empty if not authenticated as a user.
+    ///
+    pub fn granted_scopes(&self) -> Vec<Scope> {
+        self.scope_string()
+            .split_whitespace()
+            .filter_map(|scope| Scope::try_from(scope).ok()) // ignore unknown scopes
+            .collect()
+    }
+}
@@ -0,0 +1,102 @@
+use crate::spotify::{Market, SearchItem, SearchType, Spotify, SpotifyCollection, SpotifyError};
+use crate::srequest::{append_query_parameter, RequestMethod};
+use std::collections::VecDeque;
+use urlencoding::encode;
+
+/// Largest offset (including limit) Spotify allows for search results
+const MAX_SEARCH_OFFSET: u32 = 1000;
+
+/// Number of search results requested per page, the most Spotify allows
+const SEARCH_PAGE_SIZE: u32 = 50;
+
+/// Iterator over search results that requests the next page whenever the current one is used up. Stops after the last
+/// result or once Spotify's search offset cap of 1000 is reached. Created with [search_iter](struct.Spotify.html#method.search_iter)
+pub struct SearchStream<'a> {
+    spotify: &'a Spotify,
+    query: String,
+    search_type: SearchType,
+    market: Option<Market>,
+    offset: u32,                 // offset of the next page to request
+    items: VecDeque<SearchItem>, // results of the current page not yet returned
+    finished: bool,              // true when there are no more pages to request
+}
+
+impl<'a> SearchStream<'a> {
+    /// Requests the next page of results and adds them to the buffered items
+    fn request_next_page(&mut self) -> Result<(), SpotifyError> {
+        let limit = SEARCH_PAGE_SIZE.min(MAX_SEARCH_OFFSET - self.offset); // offset + limit can't pass the cap
+
+        let mut url_extension = format!(
+            "search?q={}&type={}",
+            encode(&self.query),
+            self.search_type.as_str()
+        ); // base url
+        append_query_parameter(&mut url_extension, "limit", limit);
+        append_query_parameter(&mut url_extension, "offset", self.offset);
+
+        if let Some(market) = &self.market {
+            // if market is set, add to url
+            append_query_parameter(&mut url_extension, "market", market.code());
+        }
+
+        let response = self
+            .spotify
+            .spotify_request(&url_extension, RequestMethod::Get)?; // make request
+
+        // results are under the plural of the type, i.e. "tracks"
+        let page = SpotifyCollection::<SearchItem>::new(
+            &response[format!("{}s", self.search_type.as_str())],
+        );
+
+        self.offset += page.items.len() as u32;
+        self.finished =
+            page.next.is_none() || page.items.is_empty() || self.offset >= MAX_SEARCH_OFFSET; // stop at last page or offset cap
+        self.items.extend(page.items);
+
+        Ok(())
+    }
+}
+
+impl<'a> Iterator for SearchStream<'a> {
+    type Item = Result<SearchItem, SpotifyError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.items.is_empty() && !self.finished {
+            if let Err(e) = self.request_next_page() {
+                self.finished = true; // don't keep requesting after an error
+                return Some(Err(e));
+            }
+        }
+
+        self.items.pop_front().map(Ok)
+    }
+}
+
+impl Spotify {
+    /// Search for items matching a query, lazily paging through the results: <https://developer.spotify.com/documentation/web-api/reference/#/operations/search>
+    /// Pages are only requested as the returned iterator is consumed. Spotify doesn't return results past an offset of 1000, so at most 1000 items are returned.
+    ///
+    /// Required scope: none
+    ///
+    /// # Arguments
+    /// * `query` - The search query, i.e. `"remaster track:Doxy artist:Miles Davis"`. See the Spotify docs for the supported filters.
+    /// * `search_type` - The type of item to search for.
+    /// * `market` - An ISO 3166-1 alpha-2 country code, or `Market::FromToken` for the current user's market. Only content available in that market is returned.
+    ///
+    pub fn search_iter(
+        &self,
+        query: &str,
+        search_type: SearchType,
+        market: Option<Market>,
+    ) -> SearchStream<'_> {
+        SearchStream {
+            spotify: self,
+            query: query.to_string(),
+            search_type,
+            market,
+            offset: 0,
+            items: VecDeque::new(),
+            finished: false,
+        }
+    }
+}
@@ -0,0 +1,85 @@
+use crate::spotify::{Episode, Market, Show, Spotify, SpotifyError, SpotifyObject};
+use crate::srequest::{append_query_parameter, RequestMethod};
+use json::JsonValue::Null;
+
+impl Spotify {
+    /// Get several shows: <https://developer.spotify.com/documentation/web-api/reference/#/operations/get-multiple-shows>
+    /// Note: without a market Spotify leaves out shows that are market restricted. So when no market is given and the
+    /// access token belongs to a user, the user's market (`from_token`) is used. Pass a market explicitly to opt out.
+    ///
+    /// Required scope: none
+    ///
+    /// # Arguments
+    /// * `show_ids` - A vector of Spotify IDs for the shows. Maximum: 50 IDs.
+    /// * `market` - An ISO 3166-1 alpha-2 country code, or `Market::FromToken` for the current user's market. Default: the user's market when authenticated as a user.
+    ///
+    /// Returns a vector in the same order as `show_ids`, with None for shows that aren't available.
+    ///
+    pub fn get_several_shows(
+        &self,
+        show_ids: Vec<&str>,
+        market: Option<Market>,
+    ) -> Result<Vec<Option<Show>>, SpotifyError> {
+        if show_ids.is_empty() {
+            return Ok(Vec::new()); // nothing to request
+        }
+
+        let mut url_extension = format!("shows?ids={}", show_ids.join(",")); // base url
+
+        // if market parameter supplied (or defaulted), add to request as query parameter
+        if let Some(market) = self.market_or_user_default(market) {
+            append_query_parameter(&mut url_extension, "market", market.code());
+        }
+
+        let response = self.spotify_request(&url_extension, RequestMethod::Get)?; // make request
+
+        let mut shows = Vec::new(); // create vector to store shows
+        for show in response["shows"].members() {
+            match show {
+                Null => shows.push(None), // unavailable shows are returned as null
+                _ => shows.push(Some(Show::new(show))),
+            }
+        }
+        return Ok(shows); // return vector of shows
+    }
+
+    /// Get several episodes: <https://developer.spotify.com/documentation/web-api/reference/#/operations/get-multiple-episodes>
+    /// Note: without a market Spotify leaves out episodes that are market restricted. So when no market is given and the
+    /// access token belongs to a user, the user's market (`from_token`) is used. Pass a market explicitly to opt out.
+    ///
+    /// Required scope: user-read-playback-position (only to get the user's resume point)
+    ///
+    /// # Arguments
+    /// * `episode_ids` - A vector of Spotify IDs for the episodes. Maximum: 50 IDs.
+    /// * `market` - An ISO 3166-1 alpha-2 country code, or `Market::FromToken` for the current user's market. Default: the user's market when authenticated as a user.
+    ///
+    /// Returns a vector in the same order as `episode_ids`, with None for episodes that aren't available.
+    ///
+    pub fn get_several_episodes(
+        &self,
+        episode_ids: Vec<&str>,
+        market: Option<Market>,
+    ) -> Result<Vec<Option<Episode>>, SpotifyError> {
+        if episode_ids.is_empty() {
+            return Ok(Vec::new()); // nothing to request
+        }
+
+        let mut url_extension = format!("episodes?ids={}", episode_ids.join(",")); // base url
+
+        // if market parameter supplied (or defaulted), add to request as query parameter
+        if let Some(market) = self.market_or_user_default(market) {
+            append_query_parameter(&mut url_extension, "market", market.code());
+        }
+
+        let response = self.spotify_request(&url_extension, RequestMethod::Get)?; // make request
+
+        let mut episodes = Vec::new(); // create vector to store episodes
+        for episode in response["episodes"].members() {
+            match episode {
+                Null => episodes.push(None), // unavailable episodes are returned as null
+                _ => episodes.push(Some(Episode::new(episode))),
+            }
+        }
+        return Ok(episodes); // return vector of episodes
+    }
+}
@@ -1,12 +1,18 @@
 use chrono::{DateTime, Duration, NaiveDate, NaiveDateTime, Utc};
 use dotenv;
 use json::JsonValue;
+use reqwest::header::HeaderValue;
+use std::collections::{BTreeMap, HashMap};
 use std::fmt::{self, Debug};
 use std::fs;
-use std::sync::RwLock;
+use std::hash::{Hash, Hasher};
+use std::sync::{Arc, RwLock};
+use std::thread::{self, JoinHandle};
+use std::time;
 
 use crate::authorization::{
     generate_verifier, get_access_token, get_authorization_code, refresh_access_token,
+    DEFAULT_AUTH_TIMEOUT, SPOTIFY_ACCOUNTS_URL,
 };
 
 /// Trait to represent single Spotify objects (i.e. Track, Artist, Album, etc.)
@@ -44,6 +50,7 @@ pub enum RestrictionReason {
     Market,
     Product,
     Explicit,
+    Other(String), // a reason not known to this library, as returned by Spotify
     None,
 }
 
@@ -62,6 +69,50 @@ pub struct ExternalTrackIds {
     pub upc: Option<String>,
 }
 
+/// Struct to represent the followers of an artist, user or playlist
+pub struct Followers {
+    pub href: Option<String>, // A link to the Web API endpoint providing full details of the followers. Currently always None
+    pub total: i32,           // The total number of followers
+}
+
+/// Implements Debug trait for Followers struct
+impl fmt::Debug for Followers {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Followers")
+            .field("href", &self.href)
+            .field("total", &self.total)
+            .finish()
+    }
+}
+
+/// Enum to represent the type of a copyright statement
+pub enum CopyrightType {
+    Copyright,   // C, the copyright
+    Performance, // P, the sound recording (performance) copyright
+}
+
+/// Struct to represent a copyright statement of an album
+pub struct Copyright {
+    pub text: String,                  // The copyright text for this content
+    pub copyright_type: CopyrightType, // The type of copyright: copyright or performance
+}
+
+/// Implements Debug trait for Copyright struct
+impl fmt::Debug for Copyright {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Copyright")
+            .field("text", &self.text)
+            .field(
+                "copyright_type",
+                &match self.copyright_type {
+                    CopyrightType::Copyright => "C",
+                    CopyrightType::Performance => "P",
+                },
+            )
+            .finish()
+    }
+}
+
 /// Enum to represent different spotify contexts
 pub enum SpotifyContext {
     Album(String),
@@ -129,15 +180,41 @@ impl Debug for SpotifyContext {
     }
 }
 
+/// Enum to represent where in a context playback starts. Only one kind of offset can be given at a time
+pub enum PlaybackOffset<'a> {
+    Position(i32), // Zero-based index of the item in the context, i.e. 1 to start on the 2nd song of an album
+    Track(&'a str), // Spotify ID of the track in the context to start on
+    Uri(&'a str), // Complete uri of the item in the context to start on, i.e. `spotify:episode:512ojhOuo1ktJprKbVcKyQ`
+}
+
+/// Implements Debug trait for PlaybackOffset
+impl Debug for PlaybackOffset<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PlaybackOffset::Position(position) => f
+                .debug_struct("PlaybackOffset")
+                .field("position", &position)
+                .finish(),
+            PlaybackOffset::Track(track_id) => f
+                .debug_struct("PlaybackOffset")
+                .field("track", &track_id)
+                .finish(),
+            PlaybackOffset::Uri(uri) => {
+                f.debug_struct("PlaybackOffset").field("uri", &uri).finish()
+            }
+        }
+    }
+}
+
 /// Struct to hold general collection of Spotify objects
 pub struct SpotifyCollection<T: SpotifyObject + Debug> {
-    pub href: String,
-    pub items: Vec<T>,
-    pub limit: i32,
-    pub next: Option<String>,
-    pub offset: i32,
-    pub previous: Option<String>,
-    pub total: i32,
+    pub href: String,             // A link to the Web API endpoint returning this page
+    pub items: Vec<T>,            // The items of this page
+    pub limit: i32,               // The maximum number of items in a page
+    pub next: Option<String>,     // URL of the next page, None if this is the last page
+    pub offset: i32, // The index of the first item of this page in the whole collection
+    pub previous: Option<String>, // URL of the previous page, None if this is the first page
+    pub total: i32, // The number of items in the whole collection (not in this page), i.e. the number of tracks in a playlist
 }
 
 /// Implements Debug trait for SpotifyCollection
@@ -177,7 +254,8 @@ pub struct Album {
     pub album_type: AlbumType, // Type of album: album, single, compilation
     pub total_tracks: i32,     // The number of tracks in album
     pub available_markets: Vec<String>, // The markets in which the album is available: ISO 3166-1 alpha-2 country codes (Note: considered in market if at least 1 song is in that market)
-    pub spotify_url: String,            // The Spotify URL for the album
+    pub copyrights: Vec<Copyright>, // The copyright statements of the album. Empty for simplified albums
+    pub external_urls: HashMap<String, String>, // Known external URLs for the album, keyed by type (i.e. "spotify")
     pub href: String, // A link to the Web API endpoint providing full details of the album
     pub id: String,   // The Spotify ID for the album
     pub images: Vec<SpotifyImage>, // The cover art for the album in various sizes, widest first
@@ -204,6 +282,131 @@ impl fmt::Debug for Album {
     }
 }
 
+impl Album {
+    /// The Spotify URL for the album, or an empty string if Spotify didn't return one
+    pub fn spotify_url(&self) -> &str {
+        match self.external_urls.get("spotify") {
+            Some(spotify_url) => spotify_url,
+            None => "", // default to empty string
+        }
+    }
+
+    /// The URL to open the album in Spotify: `spotify_url` if Spotify returned one, otherwise built from the id
+    pub fn web_url(&self) -> String {
+        web_url(self.spotify_url(), "album", &self.id)
+    }
+
+    /// The markets in which the album is available as `Market`s. Codes that aren't markets where Spotify is available are skipped.
+    pub fn available_markets_typed(&self) -> Vec<Market> {
+        typed_markets(&self.available_markets)
+    }
+
+    /// The album's tracks grouped by disc number, each disc in track order. Only covers the tracks in `tracks`,
+    /// so use `Spotify::get_album_ex` with `fetch_all_tracks` first for albums with more tracks than a single page.
+    pub fn tracks_by_disc(&self) -> BTreeMap<i32, Vec<&Track>> {
+        let mut discs: BTreeMap<i32, Vec<&Track>> = BTreeMap::new();
+
+        if let Some(tracks) = &self.tracks {
+            for track in &tracks.items {
+                discs.entry(track.disc_number).or_default().push(track);
+            }
+        }
+
+        discs
+    }
+
+    /// The image of the album whose size is closest to `px` pixels, or None if there are no images
+    ///
+    /// # Arguments
+    /// * `px` - The desired width (or height, for images without a width) in pixels
+    ///
+    pub fn image_closest_to(&self, px: i32) -> Option<&SpotifyImage> {
+        image_closest_to(&self.images, px)
+    }
+
+    /// The largest image of the album, or None if there are no images
+    pub fn largest_image(&self) -> Option<&SpotifyImage> {
+        largest_image(&self.images)
+    }
+
+    /// The smallest image of the album, or None if there are no images
+    pub fn smallest_image(&self) -> Option<&SpotifyImage> {
+        smallest_image(&self.images)
+    }
+}
+
+/// Converts country codes into markets, skipping codes that aren't markets where Spotify is available
+///
+/// # Arguments
+/// * `markets` - ISO 3166-1 alpha-2 country codes, as returned by Spotify
+///
+fn typed_markets(markets: &[String]) -> Vec<Market> {
+    markets
+        .iter()
+        .filter_map(|market| Market::try_from(market.as_str()).ok())
+        .collect()
+}
+
+/// The size of an image in pixels: its width, or its height if Spotify didn't return a width
+fn image_size(image: &SpotifyImage) -> i32 {
+    match image.width {
+        0 => image.height,
+        width => width,
+    }
+}
+
+/// Returns the image whose size is closest to `px`, preferring the larger image on a tie
+///
+/// # Arguments
+/// * `images` - The images to choose from
+/// * `px` - The desired size in pixels
+///
+fn image_closest_to(images: &[SpotifyImage], px: i32) -> Option<&SpotifyImage> {
+    images
+        .iter()
+        .min_by_key(|image| ((image_size(image) - px).abs(), -image_size(image)))
+}
+
+/// Returns the largest image, the first one if several are equally large (Spotify lists images widest first)
+pub(crate) fn largest_image(images: &[SpotifyImage]) -> Option<&SpotifyImage> {
+    images.iter().rev().max_by_key(|image| image_size(image))
+}
+
+/// Returns the smallest image, the last one if several are equally small (Spotify lists images widest first)
+fn smallest_image(images: &[SpotifyImage]) -> Option<&SpotifyImage> {
+    images.iter().rev().min_by_key(|image| image_size(image))
+}
+
+/// Returns the given Spotify URL, or builds `https://open.spotify.com/{kind}/{id}` if it is empty
+///
+/// # Arguments
+/// * `spotify_url` - The Spotify URL returned by Spotify, empty if none was returned
+/// * `kind` - The kind of object in the URL path, i.e. "track"
+/// * `id` - The Spotify ID of the object
+///
+fn web_url(spotify_url: &str, kind: &str, id: &str) -> String {
+    match spotify_url {
+        "" => format!("https://open.spotify.com/{}/{}", kind, id), // derive from the id
+        spotify_url => spotify_url.to_string(),
+    }
+}
+
+/// Two albums are equal if they have the same Spotify ID, regardless of their other fields
+impl PartialEq for Album {
+    fn eq(&self, other: &Self) -> bool {
+        self.id == other.id
+    }
+}
+
+impl Eq for Album {}
+
+/// Hashes only the Spotify ID, consistent with equality
+impl Hash for Album {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.id.hash(state);
+    }
+}
+
 /// Struct to represent Album with "date_added" field
 pub struct DatedAlbum {
     pub album: Album,                      // The album
@@ -222,8 +425,8 @@ impl fmt::Debug for DatedAlbum {
 
 /// Struct to represent Artist
 pub struct Artist {
-    pub spotify_url: String,       // The Spotify URL for the artist
-    pub total_followers: i32,      // The total number of followers
+    pub external_urls: HashMap<String, String>, // Known external URLs for the artist, keyed by type (i.e. "spotify")
+    pub followers: Followers,                   // The followers of the artist
     pub genres: Vec<String>, // A list of the genres the artist is associated with. If not yet classified, the array is empty.
     pub href: String,        // A link to the Web API endpoint providing full details of the artist
     pub id: String,          // The Spotify ID for the artist
@@ -243,25 +446,82 @@ impl fmt::Debug for Artist {
     }
 }
 
+impl Artist {
+    /// The total number of followers of the artist
+    pub fn total_followers(&self) -> i32 {
+        self.followers.total
+    }
+
+    /// The Spotify URL for the artist, or an empty string if Spotify didn't return one
+    pub fn spotify_url(&self) -> &str {
+        match self.external_urls.get("spotify") {
+            Some(spotify_url) => spotify_url,
+            None => "", // default to empty string
+        }
+    }
+
+    /// The URL to open the artist in Spotify: `spotify_url` if Spotify returned one, otherwise built from the id
+    pub fn web_url(&self) -> String {
+        web_url(self.spotify_url(), "artist", &self.id)
+    }
+
+    /// The image of the artist whose size is closest to `px` pixels, or None if there are no images
+    ///
+    /// # Arguments
+    /// * `px` - The desired width (or height, for images without a width) in pixels
+    ///
+    pub fn image_closest_to(&self, px: i32) -> Option<&SpotifyImage> {
+        image_closest_to(&self.images, px)
+    }
+
+    /// The largest image of the artist, or None if there are no images
+    pub fn largest_image(&self) -> Option<&SpotifyImage> {
+        largest_image(&self.images)
+    }
+
+    /// The smallest image of the artist, or None if there are no images
+    pub fn smallest_image(&self) -> Option<&SpotifyImage> {
+        smallest_image(&self.images)
+    }
+}
+
+/// Two artists are equal if they have the same Spotify ID, regardless of their other fields
+impl PartialEq for Artist {
+    fn eq(&self, other: &Self) -> bool {
+        self.id == other.id
+    }
+}
+
+impl Eq for Artist {}
+
+/// Hashes only the Spotify ID, consistent with equality
+impl Hash for Artist {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.id.hash(state);
+    }
+}
+
 /// Struct to represent Track
 pub struct Track {
-    pub album: Option<Album>,           // The album on which the track appears.
-    pub artists: Option<Vec<Artist>>,   // The artists who performed the track.
+    pub album: Option<Album>, // The album on which the track appears. None for simplified tracks (i.e. album tracks)
+    pub artists: Option<Vec<Artist>>, // The artists who performed the track.
     pub available_markets: Vec<String>, // A list of the countries in which the track can be played, identified by their ISO 3166-1 alpha-2 code.
     pub disc_number: i32, // The disc number (usually 1 unless the album consists of more than one disc)
-    pub duration: i32,    // The track length in milliseconds
+    pub duration: i64,    // The track length in milliseconds
     pub explicit: bool, // Whether or not the track has explicit lyrics ( true = yes it does; false = no it does not OR unknown)
-    pub external_ids: ExternalTrackIds, // Known external IDs for the track
-    pub spotify_url: String, // The Spotify URL for the track
-    pub href: String,   // A link to the Web API endpoint providing full details of the track
-    pub id: String,     // The Spotify ID for the track
+    pub external_ids: ExternalTrackIds, // Known external IDs for the track. All None for simplified tracks (i.e. album tracks)
+    pub external_urls: HashMap<String, String>, // Known external URLs for the track, keyed by type (i.e. "spotify")
+    pub href: String, // A link to the Web API endpoint providing full details of the track
+    pub id: String,   // The Spotify ID for the track. Empty for local files, see `spotify_id`
     pub restriction_reason: RestrictionReason, // The reason for the track being restricted. If a track is restricted, the reason is usually market or explicit.
     pub name: String,                          // The name of the track
-    pub popularity: i32, // The popularity of the track. The value will be between 0 and 100, with 100 being the most popular.
+    pub popularity: Option<i32>, // The popularity of the track. The value will be between 0 and 100, with 100 being the most popular. None for simplified tracks (i.e. album tracks)
     pub preview_url: Option<String>, // A URL to a 30 second preview (MP3 format) of the track.
     pub track_number: i32, // The number of the track. If an album has several discs, the track number is the number on the specified disc.
-    pub uri: String,       // The Spotify URI for the track
-    pub is_local: bool,
+    pub uri: String, // The Spotify URI for the track. For local files a `spotify:local:` URI that the other endpoints don't accept, see `spotify_uri`
+    pub is_local: bool, // Whether the track is a local file (added to a playlist from the user's device) rather than a track in the Spotify catalog
+    pub is_playable: Option<bool>, // Whether the track is playable in the given market. Only present when a market was supplied in the request (track relinking)
+    pub linked_from: Option<LinkedTrack>, // If the track was relinked for the given market, the originally requested track
 }
 
 /// Implements Debug trait for Track struct
@@ -275,6 +535,367 @@ impl fmt::Debug for Track {
     }
 }
 
+/// Two tracks are equal if they have the same Spotify ID, regardless of their other fields. Local files have no Spotify ID,
+/// so they are equal if they have the same `spotify:local:` URI, and never equal to a track that has an ID
+impl PartialEq for Track {
+    fn eq(&self, other: &Self) -> bool {
+        match (self.spotify_id(), other.spotify_id()) {
+            (Some(id), Some(other_id)) => id == other_id,
+            (None, None) => self.uri == other.uri, // both local files
+            _ => false,
+        }
+    }
+}
+
+impl Eq for Track {}
+
+/// Hashes only the Spotify ID (or the URI of a local file), consistent with equality
+impl Hash for Track {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        match self.spotify_id() {
+            Some(id) => id.hash(state),
+            None => self.uri.hash(state),
+        }
+    }
+}
+
+/// Struct to represent the original track a relinked track was linked from
+pub struct LinkedTrack {
+    pub external_urls: HashMap<String, String>, // Known external URLs for the original track, keyed by type (i.e. "spotify")
+    pub href: String, // A link to the Web API endpoint providing full details of the original track
+    pub id: String,   // The Spotify ID for the original track
+    pub object_type: String, // The object type: "track"
+    pub uri: String,  // The Spotify URI for the original track
+}
+
+/// Implements Debug trait for LinkedTrack struct
+impl fmt::Debug for LinkedTrack {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LinkedTrack")
+            .field("id", &self.id)
+            .field("uri", &self.uri)
+            .finish()
+    }
+}
+
+impl LinkedTrack {
+    /// The Spotify URL for the original track, or an empty string if Spotify didn't return one
+    pub fn spotify_url(&self) -> &str {
+        match self.external_urls.get("spotify") {
+            Some(spotify_url) => spotify_url,
+            None => "", // default to empty string
+        }
+    }
+}
+
+impl Track {
+    /// The Spotify ID of the track, or None for local files, which aren't in the Spotify catalog. Use this rather than `id`
+    /// when passing tracks on to other endpoints, as local files (i.e. in playlists) can't be requested, saved or queued by id.
+    pub fn spotify_id(&self) -> Option<&str> {
+        if self.is_local || self.id.is_empty() {
+            return None; // local file, no Spotify ID
+        }
+
+        Some(&self.id)
+    }
+
+    /// The Spotify URI of the track, or None for local files, whose `spotify:local:` URIs aren't accepted by other endpoints
+    pub fn spotify_uri(&self) -> Option<&str> {
+        if self.is_local || self.uri.is_empty() {
+            return None; // local file, no usable URI
+        }
+
+        Some(&self.uri)
+    }
+
+    /// The Spotify URL for the track, or an empty string if Spotify didn't return one
+    pub fn spotify_url(&self) -> &str {
+        match self.external_urls.get("spotify") {
+            Some(spotify_url) => spotify_url,
+            None => "", // default to empty string
+        }
+    }
+
+    /// The URL to open the track in Spotify: `spotify_url` if Spotify returned one, otherwise built from the id
+    pub fn web_url(&self) -> String {
+        web_url(self.spotify_url(), "track", &self.id)
+    }
+
+    /// Whether the track can be played. A track is considered playable if it has no restriction and Spotify hasn't
+    /// marked it as unplayable. Without a market in the request Spotify doesn't send `is_playable`, so only the restriction is checked.
+    pub fn is_playable(&self) -> bool {
+        matches!(self.restriction_reason, RestrictionReason::None)
+            && self.is_playable.unwrap_or(true)
+    }
+
+    /// The markets in which the track can be played as `Market`s. Codes that aren't markets where Spotify is available are skipped.
+    pub fn available_markets_typed(&self) -> Vec<Market> {
+        typed_markets(&self.available_markets)
+    }
+
+    /// Whether both tracks are the same recording, i.e. market relinked versions of each other. Compares ISRCs when both
+    /// tracks have one, otherwise compares the ids, taking the originally requested track of relinked tracks into account.
+    ///
+    /// # Arguments
+    /// * `other` - The track to compare with
+    ///
+    pub fn same_recording(&self, other: &Track) -> bool {
+        if let (Some(isrc), Some(other_isrc)) = (&self.external_ids.isrc, &other.external_ids.isrc)
+        {
+            return isrc == other_isrc; // an ISRC identifies the recording
+        }
+
+        let ids = self.recording_ids();
+        other.recording_ids().iter().any(|id| ids.contains(id))
+    }
+
+    /// The id of the track and, if it was relinked, the id of the originally requested track. Empty for local files
+    fn recording_ids(&self) -> Vec<&str> {
+        let mut ids: Vec<&str> = self.spotify_id().into_iter().collect();
+        if let Some(linked_from) = &self.linked_from {
+            ids.push(linked_from.id.as_str());
+        }
+        ids
+    }
+}
+
+/// Struct to represent podcast Episode
+pub struct Episode {
+    pub audio_preview_url: Option<String>, // A URL to a 30 second preview (MP3 format) of the episode.
+    pub description: String, // A description of the episode. HTML tags are stripped away from this field
+    pub duration: i64,       // The episode length in milliseconds
+    pub explicit: bool, // Whether or not the episode has explicit content (true = yes it does; false = no it does not OR unknown)
+    pub external_urls: HashMap<String, String>, // Known external URLs for the episode, keyed by type (i.e. "spotify")
+    pub href: String, // A link to the Web API endpoint providing full details of the episode
+    pub id: String,   // The Spotify ID for the episode
+    pub images: Vec<SpotifyImage>, // The cover art for the episode in various sizes, widest first
+    pub is_externally_hosted: bool, // True if the episode is hosted outside of Spotify's CDN
+    pub is_playable: Option<bool>, // True if the episode is playable in the given market. None if Spotify didn't say
+    pub languages: Vec<String>, // A list of the languages used in the episode, identified by their ISO 639-1 code
+    pub name: String,           // The name of the episode
+    pub release_date: Option<NaiveDate>, // The date the episode was first released
+    pub release_date_precision: ReleaseDatePrecision, // The precision with which release_date value is known: year, month, or day
+    pub restriction_reason: RestrictionReason, // The reason for the episode being restricted, usually market or product.
+    pub uri: String,                           // The Spotify URI for the episode
+}
+
+/// Implements Debug trait for Episode struct
+impl fmt::Debug for Episode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Episode")
+            .field("id", &self.id)
+            .field("name", &self.name)
+            .field("release_date", &self.release_date)
+            .finish()
+    }
+}
+
+impl Episode {
+    /// The Spotify URL for the episode, or an empty string if Spotify didn't return one
+    pub fn spotify_url(&self) -> &str {
+        match self.external_urls.get("spotify") {
+            Some(spotify_url) => spotify_url,
+            None => "", // default to empty string
+        }
+    }
+
+    /// Whether the episode can be played. An episode is considered playable if it has no restriction and Spotify hasn't marked it as unplayable.
+    pub fn is_playable(&self) -> bool {
+        matches!(self.restriction_reason, RestrictionReason::None)
+            && self.is_playable.unwrap_or(true)
+    }
+}
+
+/// Struct to represent a podcast Show
+pub struct Show {
+    pub available_markets: Vec<String>, // A list of the countries in which the show can be played, identified by their ISO 3166-1 alpha-2 code.
+    pub description: String, // A description of the show. HTML tags are stripped away from this field
+    pub explicit: bool, // Whether or not the show has explicit content (true = yes it does; false = no it does not OR unknown)
+    pub external_urls: HashMap<String, String>, // Known external URLs for the show, keyed by type (i.e. "spotify")
+    pub href: String, // A link to the Web API endpoint providing full details of the show
+    pub id: String,   // The Spotify ID for the show
+    pub images: Vec<SpotifyImage>, // The cover art for the show in various sizes, widest first
+    pub is_externally_hosted: bool, // True if all of the show's episodes are hosted outside of Spotify's CDN
+    pub languages: Vec<String>, // A list of the languages used in the show, identified by their ISO 639 code
+    pub media_type: String,     // The media type of the show
+    pub name: String,           // The name of the show
+    pub publisher: String,      // The publisher of the show
+    pub total_episodes: i32,    // The total number of episodes in the show
+    pub uri: String,            // The Spotify URI for the show
+}
+
+/// Implements Debug trait for Show struct
+impl fmt::Debug for Show {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Show")
+            .field("id", &self.id)
+            .field("name", &self.name)
+            .field("publisher", &self.publisher)
+            .finish()
+    }
+}
+
+impl Show {
+    /// The Spotify URL for the show, or an empty string if Spotify didn't return one
+    pub fn spotify_url(&self) -> &str {
+        match self.external_urls.get("spotify") {
+            Some(spotify_url) => spotify_url,
+            None => "", // default to empty string
+        }
+    }
+}
+
+/// Struct to represent an Audiobook
+pub struct Audiobook {
+    pub authors: Vec<String>,                   // The author(s) of the audiobook
+    pub available_markets: Vec<String>, // A list of the countries in which the audiobook can be played, identified by their ISO 3166-1 alpha-2 code.
+    pub description: String, // A description of the audiobook. HTML tags are stripped away from this field
+    pub edition: Option<String>, // The edition of the audiobook
+    pub explicit: bool, // Whether or not the audiobook has explicit content (true = yes it does; false = no it does not OR unknown)
+    pub external_urls: HashMap<String, String>, // Known external URLs for the audiobook, keyed by type (i.e. "spotify")
+    pub href: String, // A link to the Web API endpoint providing full details of the audiobook
+    pub id: String,   // The Spotify ID for the audiobook
+    pub images: Vec<SpotifyImage>, // The cover art for the audiobook in various sizes, widest first
+    pub languages: Vec<String>, // A list of the languages used in the audiobook, identified by their ISO 639 code
+    pub media_type: String,     // The media type of the audiobook
+    pub name: String,           // The name of the audiobook
+    pub narrators: Vec<String>, // The narrator(s) for the audiobook
+    pub publisher: String,      // The publisher of the audiobook
+    pub total_chapters: i32,    // The number of chapters in the audiobook
+    pub chapters: Option<SpotifyCollection<Chapter>>, // The chapters of the audiobook. Only included when getting a single audiobook
+    pub uri: String,                                  // The Spotify URI for the audiobook
+}
+
+/// Implements Debug trait for Audiobook struct
+impl fmt::Debug for Audiobook {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Audiobook")
+            .field("id", &self.id)
+            .field("name", &self.name)
+            .field("authors", &self.authors)
+            .finish()
+    }
+}
+
+impl Audiobook {
+    /// The Spotify URL for the audiobook, or an empty string if Spotify didn't return one
+    pub fn spotify_url(&self) -> &str {
+        match self.external_urls.get("spotify") {
+            Some(spotify_url) => spotify_url,
+            None => "", // default to empty string
+        }
+    }
+}
+
+/// Struct to represent a Chapter of an audiobook
+pub struct Chapter {
+    pub audio_preview_url: Option<String>, // A URL to a 30 second preview (MP3 format) of the chapter.
+    pub available_markets: Vec<String>, // A list of the countries in which the chapter can be played, identified by their ISO 3166-1 alpha-2 code.
+    pub chapter_number: i32,            // The number of the chapter
+    pub description: String, // A description of the chapter. HTML tags are stripped away from this field
+    pub duration: i64,       // The chapter length in milliseconds
+    pub explicit: bool, // Whether or not the chapter has explicit content (true = yes it does; false = no it does not OR unknown)
+    pub external_urls: HashMap<String, String>, // Known external URLs for the chapter, keyed by type (i.e. "spotify")
+    pub href: String, // A link to the Web API endpoint providing full details of the chapter
+    pub id: String,   // The Spotify ID for the chapter
+    pub images: Vec<SpotifyImage>, // The cover art for the chapter in various sizes, widest first
+    pub languages: Vec<String>, // A list of the languages used in the chapter, identified by their ISO 639-1 code
+    pub name: String,           // The name of the chapter
+    pub release_date: Option<NaiveDate>, // The date the chapter was first released
+    pub release_date_precision: ReleaseDatePrecision, // The precision with which release_date value is known: year, month, or day
+    pub restriction_reason: RestrictionReason, // The reason for the chapter being restricted, usually market.
+    pub uri: String,                           // The Spotify URI for the chapter
+    pub audiobook: Option<Audiobook>, // The audiobook the chapter belongs to. Not included when getting an audiobook's chapters
+}
+
+/// Implements Debug trait for Chapter struct
+impl fmt::Debug for Chapter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Chapter")
+            .field("id", &self.id)
+            .field("name", &self.name)
+            .field("chapter_number", &self.chapter_number)
+            .finish()
+    }
+}
+
+impl Chapter {
+    /// The Spotify URL for the chapter, or an empty string if Spotify didn't return one
+    pub fn spotify_url(&self) -> &str {
+        match self.external_urls.get("spotify") {
+            Some(spotify_url) => spotify_url,
+            None => "", // default to empty string
+        }
+    }
+}
+
+/// Enum to represent items that can be played by a user (ie in the queue). Both variants are boxed, so a long queue
+/// doesn't take up the size of the larger struct for every item
+pub enum PlayableItem {
+    Track(Box<Track>),
+    Episode(Box<Episode>),
+}
+
+/// Implements Debug trait for PlayableItem enum
+impl fmt::Debug for PlayableItem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PlayableItem::Track(track) => track.fmt(f),
+            PlayableItem::Episode(episode) => episode.fmt(f),
+        }
+    }
+}
+
+impl PlayableItem {
+    /// The Spotify URI of the track or episode. For local files this is their `spotify:local:` URI
+    pub fn uri(&self) -> &str {
+        match self {
+            PlayableItem::Track(track) => &track.uri,
+            PlayableItem::Episode(episode) => &episode.uri,
+        }
+    }
+
+    /// The Spotify URI of the track or episode, or None for local files, whose `spotify:local:` URIs aren't accepted by other endpoints
+    pub fn spotify_uri(&self) -> Option<&str> {
+        match self {
+            PlayableItem::Track(track) => track.spotify_uri(),
+            PlayableItem::Episode(episode) => Some(&episode.uri),
+        }
+    }
+
+    /// The length of the track or episode in milliseconds
+    pub fn duration(&self) -> i64 {
+        match self {
+            PlayableItem::Track(track) => track.duration,
+            PlayableItem::Episode(episode) => episode.duration,
+        }
+    }
+}
+
+/// Enum to represent the object a share link points to (see `Spotify::resolve_link`)
+pub enum ResolvedItem {
+    Track(Track),
+    Album(Album),
+    Artist(Artist),
+    Playlist(Playlist),
+    Episode(Episode),
+    Show(Show),
+}
+
+/// Implements Debug trait for ResolvedItem enum
+impl fmt::Debug for ResolvedItem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ResolvedItem::Track(track) => track.fmt(f),
+            ResolvedItem::Album(album) => album.fmt(f),
+            ResolvedItem::Artist(artist) => artist.fmt(f),
+            ResolvedItem::Playlist(playlist) => playlist.fmt(f),
+            ResolvedItem::Episode(episode) => episode.fmt(f),
+            ResolvedItem::Show(show) => show.fmt(f),
+        }
+    }
+}
+
 /// Struct to represent dated track
 pub struct DatedTrack {
     pub track: Track,                      // The track
@@ -296,7 +917,7 @@ pub struct FeatureTrack {
     pub acousticness: f64, // A confidence measure from 0.0 to 1.0 of whether the track is acoustic. 1.0 represents high confidence the track is acoustic.
     pub analysis_url: String, // An HTTP URL to access the full audio analysis of this track.
     pub danceability: f64, // Danceability describes how suitable a track is for dancing based on a combination of musical elements including tempo, rhythm stability, beat strength, and overall regularity. A value of 0.0 is least danceable and 1.0 is most danceable.
-    pub duration: i32,     // The duration of the track in milliseconds.
+    pub duration: i64,     // The duration of the track in milliseconds.
     pub energy: f64, // Energy is a measure from 0.0 to 1.0 and represents a perceptual measure of intensity and activity. Typically, energetic tracks feel fast, loud, and noisy. For example, death metal has high energy, while a Bach prelude scores low on the scale. Perceptual features contributing to this attribute include dynamic range, perceived loudness, timbre, onset rate, and general entropy.
     pub id: String,  // The Spotify ID for the track.
     pub instrumentalness: f64, // Predicts whether a track contains no vocals. “Ooh” and “aah” sounds are treated as instrumental in this context. Rap or spoken word tracks are clearly “vocal”. The closer the instrumentalness value is to 1.0, the greater likelihood the track contains no vocal content. Values above 0.5 are intended to represent instrumental tracks, but confidence is higher as the value approaches 1.0.
@@ -334,6 +955,91 @@ impl fmt::Debug for FeatureTrack {
     }
 }
 
+impl FeatureTrack {
+    /// Whether all features are zero, which is what a track without audio features (returned as null) is parsed into
+    fn is_missing(&self) -> bool {
+        [
+            self.acousticness,
+            self.danceability,
+            self.energy,
+            self.instrumentalness,
+            self.liveness,
+            self.loudness,
+            self.speechiness,
+            self.tempo,
+            self.valence,
+        ]
+        .iter()
+        .all(|feature| *feature == 0.0)
+    }
+
+    /// Averages the audio features of a set of tracks. Tracks without audio features (all features zero) are skipped.
+    /// If no track has features, every average is 0.0.
+    ///
+    /// # Arguments
+    /// * `tracks` - The audio features of the tracks to average
+    ///
+    pub fn average(tracks: &[FeatureTrack]) -> FeatureSummary {
+        let tracks: Vec<&FeatureTrack> =
+            tracks.iter().filter(|track| !track.is_missing()).collect(); // skip missing features
+
+        let mean = |feature: fn(&FeatureTrack) -> f64| -> f64 {
+            if tracks.is_empty() {
+                return 0.0; // nothing to average
+            }
+            tracks.iter().copied().map(feature).sum::<f64>() / tracks.len() as f64
+        };
+
+        FeatureSummary {
+            track_count: tracks.len(),
+            acousticness: mean(|track| track.acousticness),
+            danceability: mean(|track| track.danceability),
+            duration: mean(|track| track.duration as f64),
+            energy: mean(|track| track.energy),
+            instrumentalness: mean(|track| track.instrumentalness),
+            liveness: mean(|track| track.liveness),
+            loudness: mean(|track| track.loudness),
+            speechiness: mean(|track| track.speechiness),
+            tempo: mean(|track| track.tempo),
+            valence: mean(|track| track.valence),
+        }
+    }
+}
+
+/// Struct to represent the average audio features of a set of tracks. See [FeatureTrack](struct.FeatureTrack.html) for what each feature means
+pub struct FeatureSummary {
+    pub track_count: usize, // The number of tracks averaged over (tracks without features are skipped)
+    pub acousticness: f64,  // Mean acousticness
+    pub danceability: f64,  // Mean danceability
+    pub duration: f64,      // Mean duration in milliseconds
+    pub energy: f64,        // Mean energy
+    pub instrumentalness: f64, // Mean instrumentalness
+    pub liveness: f64,      // Mean liveness
+    pub loudness: f64,      // Mean loudness in decibels
+    pub speechiness: f64,   // Mean speechiness
+    pub tempo: f64,         // Mean tempo in BPM
+    pub valence: f64,       // Mean valence
+}
+
+/// Implements Debug trait for FeatureSummary struct
+impl fmt::Debug for FeatureSummary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FeatureSummary")
+            .field("track_count", &self.track_count)
+            .field("acousticness", &self.acousticness)
+            .field("danceability", &self.danceability)
+            .field("duration", &self.duration)
+            .field("energy", &self.energy)
+            .field("instrumentalness", &self.instrumentalness)
+            .field("liveness", &self.liveness)
+            .field("loudness", &self.loudness)
+            .field("speechiness", &self.speechiness)
+            .field("tempo", &self.tempo)
+            .field("valence", &self.valence)
+            .finish()
+    }
+}
+
 /// Struct representing bars in a track for audio analysis
 /// [Bar](https://developer.spotify.com/documentation/web-api/reference/#/operations/get-audio-analysis): A bar (or measure) is a segment of time defined as a given number of beats.
 pub struct Bar {
@@ -516,11 +1222,12 @@ impl fmt::Debug for AnalysisTrack {
 pub struct User {
     pub country: Option<String>, // The country of the user, ISO 3166-1 alpha-2 country code.
     pub display_name: Option<String>, // The name displayed on the user's profile.
-    pub spotify_url: String,     // Spotify url for the user
-    pub total_followers: i32,    // Total number of followers
-    pub href: String,            // A link to the Web API endpoint for this user
-    pub id: String,              // The Spotify user ID for the user
-    pub images: Vec<SpotifyImage>, // The user's profile image
+    pub email: Option<String>, // The user's email address. Only returned with the user-read-email scope.
+    pub external_urls: HashMap<String, String>, // Known external URLs for the user, keyed by type (i.e. "spotify")
+    pub followers: Followers,                   // The followers of the user
+    pub href: String,                           // A link to the Web API endpoint for this user
+    pub id: String,                             // The Spotify user ID for the user
+    pub images: Vec<SpotifyImage>,              // The user's profile image
     pub product: Option<String>, // The user's Spotify subscription level: "premium", "free", etc. (The subscription level "open" can be considered the same as "free".)
     pub uri: String,             // The Spotify URI for the user
 }
@@ -531,25 +1238,77 @@ impl fmt::Debug for User {
         f.debug_struct("User")
             .field("display_name", &self.display_name)
             .field("id", &self.id)
-            .field("total_followers", &self.total_followers)
+            .field("total_followers", &self.followers.total)
             .finish()
     }
 }
 
+impl User {
+    /// The total number of followers of the user
+    pub fn total_followers(&self) -> i32 {
+        self.followers.total
+    }
+
+    /// The Spotify URL for the user, or an empty string if Spotify didn't return one
+    pub fn spotify_url(&self) -> &str {
+        match self.external_urls.get("spotify") {
+            Some(spotify_url) => spotify_url,
+            None => "", // default to empty string
+        }
+    }
+
+    /// The image of the user whose size is closest to `px` pixels, or None if there are no images
+    ///
+    /// # Arguments
+    /// * `px` - The desired width (or height, for images without a width) in pixels
+    ///
+    pub fn image_closest_to(&self, px: i32) -> Option<&SpotifyImage> {
+        image_closest_to(&self.images, px)
+    }
+
+    /// The largest image of the user, or None if there are no images
+    pub fn largest_image(&self) -> Option<&SpotifyImage> {
+        largest_image(&self.images)
+    }
+
+    /// The smallest image of the user, or None if there are no images
+    pub fn smallest_image(&self) -> Option<&SpotifyImage> {
+        smallest_image(&self.images)
+    }
+}
+
+/// Two users are equal if they have the same Spotify ID, regardless of their other fields
+impl PartialEq for User {
+    fn eq(&self, other: &Self) -> bool {
+        self.id == other.id
+    }
+}
+
+impl Eq for User {}
+
+/// Hashes only the Spotify ID, consistent with equality
+impl Hash for User {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.id.hash(state);
+    }
+}
+
 /// Struct to represent a Spotify Playlist
 pub struct Playlist {
     pub collaborative: bool, // true if the owner allows other users to modify the playlist
     pub description: Option<String>, // The playlist description. Only returned for modified, verified playlists, otherwise null
-    pub spotify_url: String,         // Spotify url for the playlist
-    pub total_followers: i32,        // The total number of followers
+    pub external_urls: HashMap<String, String>, // Known external URLs for the playlist, keyed by type (i.e. "spotify")
+    pub followers: Followers,                   // The followers of the playlist
     pub href: String, // A link to the Web API endpoint providing full details of the playlist
     pub id: String,   // The Spotify ID for the playlist
     pub images: Vec<SpotifyImage>, // The playlist cover image in different sizes
     pub name: String, // The name of the playlist
     pub owner: User,  // The user who owns the playlist
+    pub primary_color: Option<String>, // The color Spotify clients theme the playlist with, i.e. "#FFFFFF". Usually null
     pub public: Option<bool>, // true if the playlist is public
     pub snapshot_id: String, // The version identifier for the current playlist. Can be supplied in other requests to target a specific playlist version
     pub tracks: Option<SpotifyCollection<PlaylistTrack>>, // The tracks of the playlist
+    pub tracks_href: Option<String>, // A link to the Web API endpoint where the full list of tracks can be retrieved
     pub uri: String,         // The Spotify URI for the playlist
 }
 
@@ -560,19 +1319,101 @@ impl fmt::Debug for Playlist {
             .field("name", &self.name)
             .field("description", &self.description)
             .field("id", &self.id)
-            .field("total_followers", &self.total_followers)
+            .field("total_followers", &self.followers.total)
             .field("owner", &self.owner)
             .field("tracks", &self.tracks)
             .finish()
     }
 }
 
+impl Playlist {
+    /// The total number of followers of the playlist
+    pub fn total_followers(&self) -> i32 {
+        self.followers.total
+    }
+
+    /// The Spotify URL for the playlist, or an empty string if Spotify didn't return one
+    pub fn spotify_url(&self) -> &str {
+        match self.external_urls.get("spotify") {
+            Some(spotify_url) => spotify_url,
+            None => "", // default to empty string
+        }
+    }
+
+    /// The URL to open the playlist in Spotify: `spotify_url` if Spotify returned one, otherwise built from the id
+    pub fn web_url(&self) -> String {
+        web_url(self.spotify_url(), "playlist", &self.id)
+    }
+
+    /// Whether the user can edit the playlist (i.e. add or remove tracks): they own it, or it is collaborative
+    ///
+    /// # Arguments
+    /// * `user_id` - The Spotify user ID of the user, i.e. from `current_user_id`
+    ///
+    pub fn is_editable_by(&self, user_id: &str) -> bool {
+        self.owner.id == user_id || self.collaborative
+    }
+
+    /// The image of the playlist whose size is closest to `px` pixels, or None if there are no images
+    ///
+    /// # Arguments
+    /// * `px` - The desired width (or height, for images without a width) in pixels
+    ///
+    pub fn image_closest_to(&self, px: i32) -> Option<&SpotifyImage> {
+        image_closest_to(&self.images, px)
+    }
+
+    /// The largest image of the playlist, or None if there are no images
+    pub fn largest_image(&self) -> Option<&SpotifyImage> {
+        largest_image(&self.images)
+    }
+
+    /// The smallest image of the playlist, or None if there are no images
+    pub fn smallest_image(&self) -> Option<&SpotifyImage> {
+        smallest_image(&self.images)
+    }
+}
+
+/// Two playlists are equal if they have the same Spotify ID, regardless of their other fields
+impl PartialEq for Playlist {
+    fn eq(&self, other: &Self) -> bool {
+        self.id == other.id
+    }
+}
+
+impl Eq for Playlist {}
+
+/// Hashes only the Spotify ID, consistent with equality
+impl Hash for Playlist {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.id.hash(state);
+    }
+}
+
+/// Lightweight summary of a playlist (i.e. for a menu of the user's playlists)
+pub struct PlaylistSummary {
+    pub id: String,       // The Spotify ID for the playlist
+    pub name: String,     // The name of the playlist
+    pub track_count: i32, // The total number of items in the playlist
+}
+
+/// Implements Debug trait for PlaylistSummary struct
+impl fmt::Debug for PlaylistSummary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PlaylistSummary")
+            .field("id", &self.id)
+            .field("name", &self.name)
+            .field("track_count", &self.track_count)
+            .finish()
+    }
+}
+
 /// Struct to represent track in playlist
 pub struct PlaylistTrack {
     pub added_at: Option<NaiveDateTime>, // The date and time the track was added.
     pub added_by: User,                  // The Spotify user who added the track.
     pub is_local: bool,                  // Whether this track is a local file or not.
-    pub track: Track,                    // The track.
+    pub track: PlayableItem,             // The track or episode.
 }
 
 /// Implements Debug trait for PlaylistTrack struct
@@ -586,6 +1427,72 @@ impl fmt::Debug for PlaylistTrack {
     }
 }
 
+/// Struct to represent a page of a playlist's tracks along with the playlist's size
+pub struct PlaylistTracks {
+    pub tracks: SpotifyCollection<PlaylistTrack>, // The requested page of tracks
+    pub total: i32, // The number of items in the whole playlist, regardless of paging or client side filtering
+}
+
+/// Implements Debug trait for PlaylistTracks struct
+impl fmt::Debug for PlaylistTracks {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PlaylistTracks")
+            .field("tracks", &self.tracks)
+            .field("total", &self.total)
+            .finish()
+    }
+}
+
+/// Struct to represent the differences between the tracks of two playlists, as Spotify URIs
+pub struct PlaylistDiff {
+    pub only_in_a: Vec<String>, // Tracks of the first playlist that aren't in the second one
+    pub only_in_b: Vec<String>, // Tracks of the second playlist that aren't in the first one
+    pub common: Vec<String>,    // Tracks in both playlists, with their URI in the first playlist
+}
+
+/// Implements Debug trait for PlaylistDiff struct
+impl fmt::Debug for PlaylistDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PlaylistDiff")
+            .field("only_in_a", &self.only_in_a)
+            .field("only_in_b", &self.only_in_b)
+            .field("common", &self.common)
+            .finish()
+    }
+}
+
+/// Struct to represent a page of the user's recently played tracks along with the cursors to page through the history
+pub struct RecentlyPlayed {
+    pub tracks: SpotifyCollection<PlayedTrack>, // The requested page of played tracks
+    pub cursors: Cursors, // The cursors to request the page before or after this one
+}
+
+/// Implements Debug trait for RecentlyPlayed struct
+impl fmt::Debug for RecentlyPlayed {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RecentlyPlayed")
+            .field("tracks", &self.tracks)
+            .field("cursors", &self.cursors)
+            .finish()
+    }
+}
+
+/// Struct to represent the cursors of a page of recently played tracks. They can be passed as `after`/`before` to `get_recently_played_tracks` to continue paging
+pub struct Cursors {
+    pub after: Option<NaiveDateTime>, // Play time to request the newer tracks with. None if there are no newer tracks
+    pub before: Option<NaiveDateTime>, // Play time to request the older tracks with. None if there are no older tracks
+}
+
+/// Implements Debug trait for Cursors struct
+impl fmt::Debug for Cursors {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Cursors")
+            .field("after", &self.after)
+            .field("before", &self.before)
+            .finish()
+    }
+}
+
 /// Struct to represent track played by user (ie in recently played)
 pub struct PlayedTrack {
     pub track: Track,                     // The track the user listened to.
@@ -604,49 +1511,434 @@ impl fmt::Debug for PlayedTrack {
     }
 }
 
-/// Enum to represent possibilities for time range of user top tracks and artists
-pub enum TimeRange {
-    ShortTerm,
-    MediumTerm,
-    LongTerm,
+/// Enum to represent possibilities for time range of user top tracks and artists
+pub enum TimeRange {
+    ShortTerm,
+    MediumTerm,
+    LongTerm,
+}
+
+/// Implements Debug trait for TimeRange enum
+impl fmt::Debug for TimeRange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TimeRange::ShortTerm => write!(f, "ShortTerm"),
+            TimeRange::MediumTerm => write!(f, "MediumTerm"),
+            TimeRange::LongTerm => write!(f, "LongTerm"),
+        }
+    }
+}
+
+impl TimeRange {
+    /// Converts TimeRange to the string the API expects for the time_range parameter
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TimeRange::ShortTerm => "short_term",
+            TimeRange::MediumTerm => "medium_term",
+            TimeRange::LongTerm => "long_term",
+        }
+    }
+}
+
+/// Enum to represent the kinds of items that can be retrieved from a user's top items
+pub enum TopItemKind {
+    Artists,
+    Tracks,
+}
+
+impl TopItemKind {
+    /// Returns the path segment of the top items endpoint for this kind
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TopItemKind::Artists => "artists",
+            TopItemKind::Tracks => "tracks",
+        }
+    }
+}
+
+/// Implements Debug trait for TopItemKind enum
+impl fmt::Debug for TopItemKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TopItemKind::Artists => write!(f, "Artists"),
+            TopItemKind::Tracks => write!(f, "Tracks"),
+        }
+    }
+}
+
+/// A genre that can be used as a recommendation seed. Can only be created from one of the available genre seeds (see `Spotify::genre`,
+/// or `Genre::try_from` to check against the built in list without a request)
+pub struct Genre(pub(crate) String);
+
+impl Genre {
+    /// Returns the genre as it is sent to the API
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+/// Implements Debug trait for Genre struct
+impl fmt::Debug for Genre {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Genre({})", self.0)
+    }
+}
+
+/// The snapshot id of a playlist, identifying a version of the playlist. Returned by the methods modifying a playlist so it
+/// can't be mixed up with a playlist id. Pass `as_str()` as the `snapshot_id` of a later modification to target this version
+#[derive(Clone, PartialEq, Eq)]
+pub struct SnapshotId(pub String);
+
+impl SnapshotId {
+    /// Returns the snapshot id as it is sent to the API
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+/// Implements Debug trait for SnapshotId struct
+impl fmt::Debug for SnapshotId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "SnapshotId({})", self.0)
+    }
+}
+
+/// Struct to collect seeds and tunable parameters for track recommendations. Built up with chained calls and passed to [get_recommendations_with_params](struct.Spotify.html#method.get_recommendations_with_params)
+pub struct RecommendationParams {
+    pub seed_artists: Vec<String>, // Spotify IDs of seed artists
+    pub seed_genres: Vec<String>,  // Seed genres (see available genre seeds)
+    pub seed_tracks: Vec<String>,  // Spotify IDs of seed tracks
+    pub optional_parameters: Vec<(String, String)>, // Tunable parameters (ie limit, market, target_danceability). See get_recommendations for valid keys
+    pub exclude_explicit: bool, // Whether explicit tracks should be removed from the results (done client side)
+}
+
+impl Default for RecommendationParams {
+    /// default
+    fn default() -> Self {
+        RecommendationParams::new()
+    }
+}
+
+impl RecommendationParams {
+    /// Creates a blank set of recommendation parameters
+    pub fn new() -> RecommendationParams {
+        RecommendationParams {
+            seed_artists: Vec::new(),
+            seed_genres: Vec::new(),
+            seed_tracks: Vec::new(),
+            optional_parameters: Vec::new(),
+            exclude_explicit: false,
+        }
+    }
+
+    /// Adds a seed artist
+    pub fn seed_artist(mut self, artist_id: &str) -> RecommendationParams {
+        self.seed_artists.push(artist_id.to_string());
+        self
+    }
+
+    /// Adds a seed genre
+    pub fn seed_genre(mut self, genre: Genre) -> RecommendationParams {
+        self.seed_genres.push(genre.0);
+        self
+    }
+
+    /// Adds a seed track
+    pub fn seed_track(mut self, track_id: &str) -> RecommendationParams {
+        self.seed_tracks.push(track_id.to_string());
+        self
+    }
+
+    /// Adds a tunable parameter (ie `("target_energy", "0.8")`)
+    pub fn parameter(mut self, key: &str, value: &str) -> RecommendationParams {
+        self.optional_parameters
+            .push((key.to_string(), value.to_string()));
+        self
+    }
+
+    /// Sets whether explicit tracks should be removed from the recommendations. The recommendations endpoint has no
+    /// explicit content parameter, so this is done client side after the request and can leave fewer tracks than the requested `limit`.
+    pub fn exclude_explicit(mut self, exclude_explicit: bool) -> RecommendationParams {
+        self.exclude_explicit = exclude_explicit;
+        self
+    }
+}
+
+/// Struct to represent a seed of a set of recommendations and how it was used
+pub struct RecommendationSeed {
+    pub after_filtering_size: i32, // The number of tracks available after min_* and max_* filters have been applied
+    pub after_relinking_size: i32, // The number of tracks available after relinking for regional availability
+    pub href: Option<String>, // A link to the full track or artist data for this seed. None for genre seeds
+    pub id: String, // The id used to select this seed: the Spotify ID of an artist or track, or the genre
+    pub initial_pool_size: i32, // The number of recommended tracks available for this seed
+    pub seed_type: String, // The entity type of this seed: "artist", "track" or "genre"
+}
+
+/// Implements Debug trait for RecommendationSeed struct
+impl fmt::Debug for RecommendationSeed {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RecommendationSeed")
+            .field("id", &self.id)
+            .field("seed_type", &self.seed_type)
+            .field("initial_pool_size", &self.initial_pool_size)
+            .field("after_filtering_size", &self.after_filtering_size)
+            .field("after_relinking_size", &self.after_relinking_size)
+            .finish()
+    }
+}
+
+/// Struct to represent a set of recommended tracks along with the seeds they were based on
+pub struct Recommendations {
+    pub seeds: Vec<RecommendationSeed>, // The seeds of the recommendations and how many tracks each contributed
+    pub tracks: Vec<Track>,             // The recommended tracks
+}
+
+/// Implements Debug trait for Recommendations struct
+impl fmt::Debug for Recommendations {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Recommendations")
+            .field("seeds", &self.seeds)
+            .field("tracks", &self.tracks)
+            .finish()
+    }
+}
+
+/// Enum to represent a market (country) content is requested for. Create from a country code with `Market::try_from`
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub enum Market {
+    Country(String), // An ISO 3166-1 alpha-2 country code of a market where Spotify is available, i.e. "US"
+    FromToken,       // The market of the current user (taken from the access token)
+}
+
+impl Market {
+    /// Returns the value the API expects for the market parameter
+    pub fn code(&self) -> &str {
+        match self {
+            Market::Country(code) => code,
+            Market::FromToken => "from_token",
+        }
+    }
+}
+
+/// Implements Debug trait for Market enum
+impl fmt::Debug for Market {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Market::Country(code) => write!(f, "Country({})", code),
+            Market::FromToken => write!(f, "FromToken"),
+        }
+    }
+}
+
+/// Enum representing repeat state of user playback
+pub enum RepeatState {
+    Track,   // track is repeating
+    Context, // context is repeating
+    Off,     // no repeat
+}
+
+/// Implements debug trait for RepeatState
+impl fmt::Debug for RepeatState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RepeatState::Track => write!(f, "Track"),
+            RepeatState::Context => write!(f, "Context"),
+            RepeatState::Off => write!(f, "Off"),
+        }
+    }
+}
+
+impl RepeatState {
+    /// Converts RepeatState to string
+    pub fn to_string(&self) -> String {
+        match self {
+            RepeatState::Track => String::from("track"),
+            RepeatState::Context => String::from("context"),
+            RepeatState::Off => String::from("off"),
+        }
+    }
+}
+
+/// Enum to represent the item types Spotify can include in playlist and playback responses (the `additional_types` parameter)
+pub enum ItemType {
+    Track,
+    Episode,
+}
+
+/// Implements Debug trait for ItemType enum
+impl fmt::Debug for ItemType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ItemType::Track => write!(f, "Track"),
+            ItemType::Episode => write!(f, "Episode"),
+        }
+    }
+}
+
+impl ItemType {
+    /// Converts ItemType to the string the API expects for the additional_types parameter
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ItemType::Track => "track",
+            ItemType::Episode => "episode",
+        }
+    }
+
+    /// Joins item types into the comma separated value used for the `additional_types` query parameter
+    pub fn join(item_types: &[ItemType]) -> String {
+        item_types
+            .iter()
+            .map(|item_type| item_type.as_str())
+            .collect::<Vec<&str>>()
+            .join(",")
+    }
+}
+
+/// Enum to represent an authorization scope: <https://developer.spotify.com/documentation/web-api/concepts/scopes>. Create from its name with `Scope::try_from`
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Scope {
+    UgcImageUpload,
+    UserReadPlaybackState,
+    UserModifyPlaybackState,
+    UserReadCurrentlyPlaying,
+    AppRemoteControl,
+    Streaming,
+    PlaylistReadPrivate,
+    PlaylistReadCollaborative,
+    PlaylistModifyPrivate,
+    PlaylistModifyPublic,
+    UserFollowModify,
+    UserFollowRead,
+    UserReadPlaybackPosition,
+    UserTopRead,
+    UserReadRecentlyPlayed,
+    UserLibraryModify,
+    UserLibraryRead,
+    UserReadEmail,
+    UserReadPrivate,
 }
 
-/// Implements Debug trait for TimeRange enum
-impl fmt::Debug for TimeRange {
+/// Implements Debug trait for Scope enum
+impl fmt::Debug for Scope {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+impl Scope {
+    /// Every scope Spotify defines
+    pub const ALL: [Scope; 19] = [
+        Scope::UgcImageUpload,
+        Scope::UserReadPlaybackState,
+        Scope::UserModifyPlaybackState,
+        Scope::UserReadCurrentlyPlaying,
+        Scope::AppRemoteControl,
+        Scope::Streaming,
+        Scope::PlaylistReadPrivate,
+        Scope::PlaylistReadCollaborative,
+        Scope::PlaylistModifyPrivate,
+        Scope::PlaylistModifyPublic,
+        Scope::UserFollowModify,
+        Scope::UserFollowRead,
+        Scope::UserReadPlaybackPosition,
+        Scope::UserTopRead,
+        Scope::UserReadRecentlyPlayed,
+        Scope::UserLibraryModify,
+        Scope::UserLibraryRead,
+        Scope::UserReadEmail,
+        Scope::UserReadPrivate,
+    ];
+
+    /// Returns the name Spotify uses for the scope, i.e. `"user-read-private"`
+    pub fn as_str(&self) -> &'static str {
         match self {
-            TimeRange::ShortTerm => write!(f, "ShortTerm"),
-            TimeRange::MediumTerm => write!(f, "MediumTerm"),
-            TimeRange::LongTerm => write!(f, "LongTerm"),
+            Scope::UgcImageUpload => "ugc-image-upload",
+            Scope::UserReadPlaybackState => "user-read-playback-state",
+            Scope::UserModifyPlaybackState => "user-modify-playback-state",
+            Scope::UserReadCurrentlyPlaying => "user-read-currently-playing",
+            Scope::AppRemoteControl => "app-remote-control",
+            Scope::Streaming => "streaming",
+            Scope::PlaylistReadPrivate => "playlist-read-private",
+            Scope::PlaylistReadCollaborative => "playlist-read-collaborative",
+            Scope::PlaylistModifyPrivate => "playlist-modify-private",
+            Scope::PlaylistModifyPublic => "playlist-modify-public",
+            Scope::UserFollowModify => "user-follow-modify",
+            Scope::UserFollowRead => "user-follow-read",
+            Scope::UserReadPlaybackPosition => "user-read-playback-position",
+            Scope::UserTopRead => "user-top-read",
+            Scope::UserReadRecentlyPlayed => "user-read-recently-played",
+            Scope::UserLibraryModify => "user-library-modify",
+            Scope::UserLibraryRead => "user-library-read",
+            Scope::UserReadEmail => "user-read-email",
+            Scope::UserReadPrivate => "user-read-private",
         }
     }
+
+    /// Joins scopes into the space separated string used when authenticating and stored by the client
+    pub fn join(scopes: &[Scope]) -> String {
+        scopes
+            .iter()
+            .map(|scope| scope.as_str())
+            .collect::<Vec<&str>>()
+            .join(" ")
+    }
 }
 
-/// Enum representing repeat state of user playback
-pub enum RepeatState {
-    Track,   // track is repeating
-    Context, // context is repeating
-    Off,     // no repeat
+/// Enum to represent the types of items that can be searched for
+pub enum SearchType {
+    Album,
+    Artist,
+    Playlist,
+    Track,
+    Show,
+    Episode,
+    Audiobook,
 }
 
-/// Implements debug trait for RepeatState
-impl fmt::Debug for RepeatState {
+/// Implements Debug trait for SearchType enum
+impl fmt::Debug for SearchType {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+impl SearchType {
+    /// Returns the value used for the `type` query parameter of a search
+    pub fn as_str(&self) -> &str {
         match self {
-            RepeatState::Track => write!(f, "Track"),
-            RepeatState::Context => write!(f, "Context"),
-            RepeatState::Off => write!(f, "Off"),
+            SearchType::Album => "album",
+            SearchType::Artist => "artist",
+            SearchType::Playlist => "playlist",
+            SearchType::Track => "track",
+            SearchType::Show => "show",
+            SearchType::Episode => "episode",
+            SearchType::Audiobook => "audiobook",
         }
     }
 }
 
-impl RepeatState {
-    /// Converts RepeatState to string
-    pub fn to_string(&self) -> String {
+/// Enum to represent a single search result
+pub enum SearchItem {
+    Album(Album),
+    Artist(Artist),
+    Playlist(Playlist),
+    Track(Track),
+    Show(Show),
+    Episode(Episode),
+    Audiobook(Audiobook),
+}
+
+/// Implements Debug trait for SearchItem enum
+impl fmt::Debug for SearchItem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
-            RepeatState::Track => String::from("track"),
-            RepeatState::Context => String::from("context"),
-            RepeatState::Off => String::from("off"),
+            SearchItem::Album(album) => album.fmt(f),
+            SearchItem::Artist(artist) => artist.fmt(f),
+            SearchItem::Playlist(playlist) => playlist.fmt(f),
+            SearchItem::Track(track) => track.fmt(f),
+            SearchItem::Show(show) => show.fmt(f),
+            SearchItem::Episode(episode) => episode.fmt(f),
+            SearchItem::Audiobook(audiobook) => audiobook.fmt(f),
         }
     }
 }
@@ -675,6 +1967,14 @@ impl fmt::Debug for Device {
     }
 }
 
+impl Device {
+    /// Whether the device's volume can be changed. Devices that don't report a volume (such as some cast targets) or are
+    /// restricted reject volume changes, so UIs can use this to disable their volume control.
+    pub fn supports_volume(&self) -> bool {
+        self.volume_percent.is_some() && !self.is_restricted
+    }
+}
+
 /// Struct representing allowed actions for a given playback state
 pub struct PlaybackActions {
     pub interrupting_playback: bool, // If true, the user can go to the next track.
@@ -713,9 +2013,10 @@ pub struct Playback {
     pub repeat_state: RepeatState, // The repeat state of the user's playback.
     pub shuffle_state: bool,    // The shuffle state of the user's playback.
     pub timestamp: Option<NaiveDateTime>, // The timestamp when data was fetched
-    pub progress: Option<i32>,  // The progress into the currently playing track.
+    pub progress: Option<i64>,  // The progress into the currently playing track.
     pub is_playing: bool,       // If something is currently playing.
-    pub track: Option<Track>,   // The track that is currently playing
+    pub track: Option<PlayableItem>, // The track or episode that is currently playing
+    pub context: Option<SpotifyContext>, // The album, artist or playlist being played from. None if playing from elsewhere (i.e. liked songs)
     pub actions: Option<PlaybackActions>, // The allowed actions for the current playback state
 }
 
@@ -729,12 +2030,15 @@ impl fmt::Debug for Playback {
             .field("timestamp", &self.timestamp)
             .field("progress", &self.progress)
             .field("is_playing", &self.is_playing)
+            .field("context", &self.context)
             .field("track", &self.track)
             .finish()
     }
 }
 
-/// Error object for Spotify struct
+/// Error object for Spotify struct. This is the crate's only (and so canonical) error type: every public method returns it.
+/// It implements `std::error::Error`, so it converts into `Box<dyn std::error::Error>` and works with `?` in code using boxed errors,
+/// and boxed errors from the authorization helpers convert back into `SpotifyError::GeneralError`.
 pub enum SpotifyError {
     RequestError(String),
     InsufficientScope(String),
@@ -744,11 +2048,18 @@ pub enum SpotifyError {
     BadRequest(String),
     InvalidRequest(String),
     AuthenticationError(String),
-    NotAuthenticated,
+    NotAuthenticated, // no access token set, i.e. authenticate() hasn't been called yet
     FileError(String),
     NoFile,
     GeneralError(String),
     Unauthorized(String),
+    NotFound(String),
+    SnapshotMismatch(String), // the playlist changed since the given snapshot id, re-fetch the playlist and try again
+    NetworkError(String), // Spotify couldn't be reached or the connection failed before a response was received
+    PremiumRequired(String), // the command (i.e. a player command) needs the user to have Spotify Premium
+    Timeout(String),         // Spotify didn't respond in time
+    NoActiveDevice(String), // a player command was sent without a device id while no device is active
+    MalformedRequest(String), // Spotify rejected the request as malformed (400), i.e. an unsupported parameter value
     // Unknown,
 }
 
@@ -766,16 +2077,61 @@ impl fmt::Debug for SpotifyError {
             SpotifyError::BadRequest(e) => write!(f, "Bad request: {}", e),
             SpotifyError::InvalidRequest(e) => write!(f, "Invalid request: {}", e),
             SpotifyError::AuthenticationError(e) => write!(f, "Authentication error: {}", e),
-            SpotifyError::NotAuthenticated => write!(f, "Not authenticated"),
+            SpotifyError::NotAuthenticated => write!(
+                f,
+                "Not authenticated; call authenticate() first (or create the client with from_tokens)"
+            ),
             SpotifyError::FileError(e) => write!(f, "File error: {}", e),
             SpotifyError::NoFile => write!(f, "No file present"),
             SpotifyError::GeneralError(e) => write!(f, "General error: {}", e),
             SpotifyError::Unauthorized(e) => write!(f, "Unauthorized: {}", e),
+            SpotifyError::NotFound(e) => write!(f, "Not found: {}", e),
+            SpotifyError::SnapshotMismatch(e) => write!(f, "Snapshot mismatch: {}", e),
+            SpotifyError::NetworkError(e) => write!(f, "Network error: {}", e),
+            SpotifyError::PremiumRequired(e) => write!(f, "Premium required: {}", e),
+            SpotifyError::Timeout(e) => write!(f, "Timed out: {}", e),
+            SpotifyError::NoActiveDevice(e) => write!(f, "No active device: {}", e),
+            SpotifyError::MalformedRequest(e) => write!(f, "Malformed request: {}", e),
             // SpotifyError::Unknown => write!(f, "Unknown error"),
         }
     }
 }
 
+/// Implements Display trait for SpotifyError so it can be shown to users (same message as Debug)
+impl fmt::Display for SpotifyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self, f)
+    }
+}
+
+impl std::error::Error for SpotifyError {}
+
+/// Converts boxed errors (as returned by the authorization helpers) into SpotifyError. A boxed SpotifyError is unwrapped as is
+impl From<Box<dyn std::error::Error>> for SpotifyError {
+    fn from(error: Box<dyn std::error::Error>) -> SpotifyError {
+        match error.downcast::<SpotifyError>() {
+            Ok(spotify_error) => *spotify_error,
+            Err(error) => SpotifyError::GeneralError(error.to_string()),
+        }
+    }
+}
+
+/// Converts transport errors of the http client into SpotifyError, keeping what kind of failure it was:
+/// timeouts become `Timeout`, failures to connect or to send the request or read the response become `NetworkError`
+impl From<reqwest::Error> for SpotifyError {
+    fn from(error: reqwest::Error) -> SpotifyError {
+        if error.is_timeout() {
+            SpotifyError::Timeout(error.to_string())
+        } else if error.is_connect() || error.is_request() || error.is_body() {
+            SpotifyError::NetworkError(error.to_string()) // i.e. connection refused, DNS failure, connection reset
+        } else if error.is_builder() {
+            SpotifyError::InvalidRequest(error.to_string()) // i.e. an invalid url
+        } else {
+            SpotifyError::RequestError(error.to_string()) // i.e. a response that couldn't be decoded
+        }
+    }
+}
+
 /// An authenticated instance of the Spotify API client. Can be used to make requests in the given scope.
 pub struct Spotify {
     client_id: RwLock<Option<String>>,
@@ -783,8 +2139,44 @@ pub struct Spotify {
     access_token: RwLock<Option<String>>,
     refresh_token: RwLock<Option<String>>,
     expires_at: RwLock<Option<DateTime<Utc>>>,
+    api_url: RwLock<String>,              // base url requests are sent to
+    accounts_url: RwLock<String>,         // base url tokens are refreshed at
+    max_retries: RwLock<u32>, // maximum number of retries for a request that failed with a server error
+    retry_delay: RwLock<time::Duration>, // delay before the first retry of a request, doubles with every retry
+    user_agent: RwLock<HeaderValue>,     // User-Agent header sent with every request
+    auth_timeout: RwLock<time::Duration>, // how long authenticate waits for the user to authorize in the browser
+    auth_success_page: RwLock<Option<String>>, // HTML authenticate shows in the browser once authorized, None for the built-in page
+    auto_select_device: RwLock<bool>, // whether player commands retry on the first available device when no device is active
+    genre_seeds: RwLock<Option<Vec<String>>>, // cached available genre seeds
+    user_id: RwLock<Option<String>>,  // cached Spotify ID of the current user
+    user_market: RwLock<Option<Option<Market>>>, // cached market of the current user (outer None: not fetched yet)
+    client: reqwest::blocking::Client, // http client shared by all requests so connections (and TLS sessions) are reused
 }
 
+/// Base url of the Spotify Web API
+const SPOTIFY_API_URL: &str = "https://api.spotify.com/v1";
+
+/// How long before the access token expires it is refreshed (by the background token refresher and `ensure_fresh_token`)
+const REFRESH_AHEAD: Duration = Duration::seconds(60);
+
+/// How often the background token refresher checks whether the access token needs refreshing
+const REFRESHER_POLL_INTERVAL: time::Duration = time::Duration::from_millis(500);
+
+/// Longest the background token refresher waits before retrying after failed refreshes
+const REFRESHER_MAX_BACKOFF: time::Duration = time::Duration::from_secs(300);
+
+/// Cap on the exponent of the token refresher's backoff, well past the point where the delay reaches the maximum
+const REFRESHER_MAX_BACKOFF_EXPONENT: u32 = 16;
+
+/// Default maximum number of retries for a request that failed with a transient server error
+const DEFAULT_MAX_RETRIES: u32 = 3;
+
+/// Default delay before the first retry of a request that failed with a server error
+const DEFAULT_RETRY_DELAY: time::Duration = time::Duration::from_millis(500);
+
+/// Default User-Agent header sent with every request
+const DEFAULT_USER_AGENT: &str = concat!("spotify.rs/", env!("CARGO_PKG_VERSION"));
+
 impl Default for Spotify {
     /// default
     fn default() -> Self {
@@ -802,10 +2194,23 @@ impl Spotify {
             access_token: RwLock::new(None),
             refresh_token: RwLock::new(None),
             expires_at: RwLock::new(None),
+            api_url: RwLock::new(String::from(SPOTIFY_API_URL)),
+            accounts_url: RwLock::new(String::from(SPOTIFY_ACCOUNTS_URL)),
+            max_retries: RwLock::new(DEFAULT_MAX_RETRIES),
+            retry_delay: RwLock::new(DEFAULT_RETRY_DELAY),
+            user_agent: RwLock::new(HeaderValue::from_static(DEFAULT_USER_AGENT)),
+            auth_timeout: RwLock::new(DEFAULT_AUTH_TIMEOUT),
+            auth_success_page: RwLock::new(None),
+            auto_select_device: RwLock::new(false),
+            genre_seeds: RwLock::new(None),
+            user_id: RwLock::new(None),
+            user_market: RwLock::new(None),
+            client: reqwest::blocking::Client::new(),
         }
     }
 
-    /// Creates spotify object from usual information and auth code. Essentially from less information
+    /// Creates spotify object from usual information and auth code. Essentially from less information. Fails with
+    /// `SpotifyError::AuthenticationError` if Spotify rejects the auth code (i.e. it was already used) or the client id
     ///
     pub fn new_from_auth_code(
         authorization_code: &str,
@@ -813,23 +2218,52 @@ impl Spotify {
         scope: String,
         code_verifier: &str,
         redirect_uri: &str,
-    ) -> Spotify {
+    ) -> Result<Spotify, SpotifyError> {
         let (access_token, refresh_token, expires_in) =
-            get_access_token(authorization_code, client_id, code_verifier, redirect_uri).unwrap();
+            get_access_token(authorization_code, client_id, code_verifier, redirect_uri)?;
 
         let expires_at = Utc::now() + Duration::seconds(expires_in); // get time when access token expires
 
-        Spotify {
+        Ok(Spotify {
             client_id: RwLock::new(Some(String::from(client_id))),
             scope: RwLock::new(Some(scope)),
             access_token: RwLock::new(Some(access_token)),
             refresh_token: RwLock::new(Some(refresh_token)),
             expires_at: RwLock::new(Some(expires_at)),
+            ..Spotify::new()
+        })
+    }
+
+    /// Creates an authenticated Spotify object from tokens that were obtained and stored elsewhere (i.e. in a database).
+    /// No request is made; if the access token has expired it will be refreshed on the next request.
+    ///
+    /// # Arguments
+    /// * `client_id` - The client id of the application the tokens were issued to
+    /// * `scope` - The scope the tokens were granted for, separated by spaces
+    /// * `access_token` - The access token
+    /// * `refresh_token` - The refresh token used to get a new access token once it expires
+    /// * `expires_at` - The time at which the access token expires
+    ///
+    pub fn from_tokens(
+        client_id: String,
+        scope: String,
+        access_token: String,
+        refresh_token: String,
+        expires_at: DateTime<Utc>,
+    ) -> Spotify {
+        Spotify {
+            client_id: RwLock::new(Some(client_id)),
+            scope: RwLock::new(Some(scope)),
+            access_token: RwLock::new(Some(access_token)),
+            refresh_token: RwLock::new(Some(refresh_token)),
+            expires_at: RwLock::new(Some(expires_at)),
+            ..Spotify::new()
         }
     }
 
     /// Creates a new Spotify object by authenticating with the Spotify API using the PKCE codeflow.
-    /// Grabs `client_id` from `.env` file.
+    /// Grabs `client_id` from `.env` file. Fails with `SpotifyError::AuthenticationError` if the user doesn't complete
+    /// authorization in the browser within the timeout set with `set_auth_timeout` (default: 2 minutes).
     ///
     /// # Arguments
     /// * `localhost_port` - The localhost port fort the redirect uri. Note: currently there is only support for localhost redirect uris.
@@ -848,12 +2282,14 @@ impl Spotify {
             &redirect_uri,
             &scope,
             &code_challenge,
+            self.auth_timeout(),
+            self.auth_success_page().as_deref(),
         );
 
         let (access_token, refresh_token, expires_in) = match auth_code_result {
             Ok(auth_code) => {
-                get_access_token(&auth_code, &client_id, &code_verifier, &redirect_uri).unwrap()
-                // get access token (be lazy with error handling and just panic if request is bad)
+                get_access_token(&auth_code, &client_id, &code_verifier, &redirect_uri)?
+                // get access token
             }
             Err(e) => return Err(SpotifyError::AuthenticationError(e.to_string())),
         };
@@ -908,17 +2344,12 @@ impl Spotify {
             return Err(SpotifyError::NotAuthenticated);
         };
 
-        match *self.expires_at.read().unwrap() {
+        let expires_at = *self.expires_at.read().unwrap(); // copy out so the lock is released before refreshing writes to it
+        match expires_at {
             Some(expires_at) => {
                 // if access token is expired, refresh it
                 if Utc::now() > expires_at {
-                    let (access_token, expires_at, refresh_token) = self.refresh()?;
-                    let mut self_access_token = self.access_token.write().unwrap();
-                    *self_access_token = Some(access_token);
-                    let mut self_expires_at = self.expires_at.write().unwrap();
-                    *self_expires_at = Some(expires_at);
-                    let mut self_refresh_token = self.refresh_token.write().unwrap();
-                    *self_refresh_token = Some(refresh_token);
+                    self.refresh_tokens()?;
                 }
                 return Ok((*self.access_token.read().unwrap())
                     .as_ref()
@@ -929,28 +2360,126 @@ impl Spotify {
         };
     }
 
-    /// Refreshes the access token and returns the new access token and the time it expires
-    fn refresh(&self) -> Result<(String, DateTime<Utc>, String), SpotifyError> {
+    /// Refreshes the access token and stores the new tokens, along with the scope Spotify granted if it sent one
+    fn refresh_tokens(&self) -> Result<(), SpotifyError> {
+        let (access_token, expires_at, refresh_token, scope) = self.refresh()?;
+        *self.access_token.write().unwrap() = Some(access_token);
+        *self.expires_at.write().unwrap() = Some(expires_at);
+        *self.refresh_token.write().unwrap() = Some(refresh_token);
+        if let Some(scope) = scope {
+            *self.scope.write().unwrap() = Some(scope); // i.e. narrower after the user revoked some scopes
+        }
+
+        Ok(())
+    }
+
+    /// Returns how long the current access token stays valid, or None if not authenticated. Negative if the token already expired.
+    pub fn expires_in(&self) -> Option<Duration> {
+        match *self.expires_at.read().unwrap() {
+            Some(expires_at) => Some(expires_at - Utc::now()),
+            None => None,
+        }
+    }
+
+    /// Spawns a background thread that refreshes the access token shortly (60 seconds) before it expires, so requests
+    /// made from other threads never have to wait on a token exchange. Useful for long running programs.
+    ///
+    /// All state of `Spotify` lives behind `RwLock`s, so the refresher can swap in new tokens while requests are made from
+    /// other threads: a request that is already underway keeps using the token it read. The thread only holds a weak reference
+    /// and stops on its own once the last `Arc` to the `Spotify` object is dropped. A failed refresh is logged and retried with
+    /// an exponentially growing delay (up to 5 minutes). If Spotify rejects the refresh token (`SpotifyError::AuthenticationError`,
+    /// i.e. it was revoked) the thread stops, since retrying can't succeed; the user has to authenticate again.
+    ///
+    /// Returns the handle of the spawned thread.
+    ///
+    pub fn spawn_token_refresher(self: &Arc<Self>) -> JoinHandle<()> {
+        let spotify = Arc::downgrade(self);
+
+        thread::spawn(move || {
+            let mut failures = 0; // number of refreshes in a row that failed
+            let mut next_attempt = time::Instant::now(); // refreshes aren't attempted before this, pushed back after a failure
+
+            loop {
+                match spotify.upgrade() {
+                    Some(spotify) => {
+                        let needs_refresh = match spotify.expires_in() {
+                            Some(expires_in) => expires_in < REFRESH_AHEAD,
+                            None => false, // not authenticated (yet), nothing to refresh
+                        };
+
+                        if needs_refresh
+                            && spotify.is_user_authenticated()
+                            && time::Instant::now() >= next_attempt
+                        {
+                            match spotify.refresh_tokens() {
+                                Ok(()) => failures = 0,
+                                Err(SpotifyError::AuthenticationError(message)) => {
+                                    log::warn!(
+                                        "stopping token refresher, refresh token rejected: {}",
+                                        message
+                                    );
+                                    return; // retrying won't help, the user has to authenticate again
+                                }
+                                Err(e) => {
+                                    let backoff = (REFRESHER_POLL_INTERVAL * 2u32.pow(failures))
+                                        .min(REFRESHER_MAX_BACKOFF);
+                                    failures = (failures + 1).min(REFRESHER_MAX_BACKOFF_EXPONENT);
+                                    next_attempt = time::Instant::now() + backoff;
+                                    log::warn!(
+                                        "token refresh failed, retrying in {} ms: {}",
+                                        backoff.as_millis(),
+                                        e
+                                    );
+                                }
+                            }
+                        }
+                    } // strong reference dropped here so the refresher doesn't keep Spotify alive
+                    None => return, // Spotify was dropped
+                }
+
+                thread::sleep(REFRESHER_POLL_INTERVAL);
+            }
+        })
+    }
+
+    /// Refreshes the access token and returns the new access token, the time it expires, the refresh token and the granted scope
+    fn refresh(&self) -> Result<(String, DateTime<Utc>, String, Option<String>), SpotifyError> {
         if self.refresh_token.read().unwrap().is_none() || self.client_id.read().unwrap().is_none()
         {
             // if client id or refresh token is not set, return error
             return Err(SpotifyError::NotAuthenticated);
         }
-        let (access_token, expires_in, refresh_token) = match refresh_access_token(
+        let (access_token, expires_in, refresh_token, scope) = match refresh_access_token(
+            &self.accounts_url(),
             &self.refresh_token.read().unwrap().as_ref().unwrap(),
             &self.client_id.read().unwrap().as_ref().unwrap(),
         ) {
             // can unwrap because they are set
-            Ok((access_token, expires_in, refresh_token)) => {
-                (access_token, expires_in, refresh_token)
+            Ok((access_token, expires_in, refresh_token, scope)) => {
+                (access_token, expires_in, refresh_token, scope)
             }
-            Err(e) => panic!("{:?}", e), // on error panic
+            Err(e) => return Err(e), // i.e. an invalid_grant when the refresh token was revoked
         };
 
         let expires_at = Utc::now() + Duration::seconds(expires_in); // get time when access token expires
 
-        // return access token and time when access token expires
-        Ok((access_token, expires_at, refresh_token))
+        // return access token, time when access token expires, refresh token and granted scope
+        Ok((access_token, expires_at, refresh_token, scope))
+    }
+
+    /// Refreshes the access token if it expires within the next minute, otherwise does nothing. Call this before starting a
+    /// batch of requests (i.e. from several threads) so the token is refreshed once up front rather than in the middle of the batch.
+    pub fn ensure_fresh_token(&self) -> Result<(), SpotifyError> {
+        let expires_at = match *self.expires_at.read().unwrap() {
+            Some(expires_at) => expires_at,
+            None => return Err(SpotifyError::NotAuthenticated),
+        }; // copied out so the lock is released before the refresh writes to it
+
+        if expires_at - Utc::now() > REFRESH_AHEAD {
+            return Ok(()); // still fresh
+        }
+
+        self.refresh_tokens() // also stores the scope Spotify granted
     }
 
     /// Saves necessary authorization information to file for later use
@@ -1002,8 +2531,9 @@ impl Spotify {
         let scope = lines.next().unwrap().to_string(); // get scope
         let refresh_token = lines.next().unwrap().to_string(); // get refresh token
 
-        let (access_token, expires_in, new_refresh_token) =
-            refresh_access_token(&refresh_token, &client_id)?; // refresh access token. Panics if request is bad
+        let (access_token, expires_in, new_refresh_token, granted_scope) =
+            refresh_access_token(SPOTIFY_ACCOUNTS_URL, &refresh_token, &client_id)?; // refresh access token. Panics if request is bad
+        let scope = granted_scope.unwrap_or(scope); // prefer the scope Spotify actually granted
         let expires_at = Utc::now() + Duration::seconds(expires_in); // get time when access token expires
 
         // return Spotify object
@@ -1013,6 +2543,7 @@ impl Spotify {
             access_token: RwLock::new(Some(access_token)),
             refresh_token: RwLock::new(Some(new_refresh_token)),
             expires_at: RwLock::new(Some(expires_at)),
+            ..Spotify::new()
         })
     }
 
@@ -1036,8 +2567,9 @@ impl Spotify {
         let scope = lines.next().unwrap().to_string(); // get scope
         let refresh_token = lines.next().unwrap().to_string(); // get refresh token
 
-        let (access_token, expires_in, new_refresh_token) =
-            refresh_access_token(&refresh_token, &client_id)?; // refresh access token. Panics if request is bad
+        let (access_token, expires_in, new_refresh_token, granted_scope) =
+            refresh_access_token(&self.accounts_url(), &refresh_token, &client_id)?; // refresh access token. Panics if request is bad
+        let scope = granted_scope.unwrap_or(scope); // prefer the scope Spotify actually granted
         let expires_at = Utc::now() + Duration::seconds(expires_in); // get time when access token expires
 
         // set client id, scope, access token, refresh token, and expires at
@@ -1063,4 +2595,422 @@ impl Spotify {
             Err(_) => false,
         }
     }
+
+    /// Logs the Spotify object out: clears the client id, scope, tokens and their expiry, as well as the cached user id and market.
+    /// Afterwards `is_authenticated()` returns false and requests fail with `SpotifyError::NotAuthenticated` until `authenticate()` is called again, i.e. for another account.
+    ///
+    pub fn clear_auth(&self) {
+        *self.client_id.write().unwrap() = None;
+        *self.scope.write().unwrap() = None;
+        *self.access_token.write().unwrap() = None;
+        *self.refresh_token.write().unwrap() = None;
+        *self.expires_at.write().unwrap() = None;
+        *self.user_id.write().unwrap() = None; // cached values belong to the old user
+        *self.user_market.write().unwrap() = None;
+    }
+
+    /// Sets the base url requests are sent to. Default: `https://api.spotify.com/v1`. Useful for routing requests through a proxy or to a mock server in tests.
+    ///
+    /// # Arguments
+    /// * `api_url` - The new base url, i.e. `http://localhost:8080/v1`
+    ///
+    pub fn set_api_url(&self, api_url: &str) {
+        *self.api_url.write().unwrap() = api_url.trim_end_matches('/').to_string();
+        // request urls add their own slash
+    }
+
+    /// Returns the base url requests are sent to
+    pub(crate) fn api_url(&self) -> String {
+        self.api_url.read().unwrap().clone()
+    }
+
+    /// Sets the base url of the accounts service tokens are refreshed at. Default: `https://accounts.spotify.com`. Useful for pointing token refreshes at a mock server in tests.
+    ///
+    /// # Arguments
+    /// * `accounts_url` - The new base url, i.e. `http://localhost:8080`
+    ///
+    pub fn set_accounts_url(&self, accounts_url: &str) {
+        *self.accounts_url.write().unwrap() = accounts_url.trim_end_matches('/').to_string();
+        // token url adds its own slash
+    }
+
+    /// Returns the base url of the accounts service
+    pub(crate) fn accounts_url(&self) -> String {
+        self.accounts_url.read().unwrap().clone()
+    }
+
+    /// Sets the maximum number of times a request is retried after Spotify responds with a transient server error (5xx). Default: 3.
+    /// Only GET requests are retried, since a write (i.e. adding tracks to a playlist) may already have been applied when the error comes back.
+    ///
+    /// # Arguments
+    /// * `max_retries` - The maximum number of retries. 0 disables retrying.
+    ///
+    pub fn set_max_retries(&self, max_retries: u32) {
+        *self.max_retries.write().unwrap() = max_retries;
+    }
+
+    /// Returns the maximum number of retries for a request
+    pub(crate) fn max_retries(&self) -> u32 {
+        *self.max_retries.read().unwrap()
+    }
+
+    /// Sets how long to wait before the first retry of a request that failed with a server error. The delay doubles with every
+    /// further retry. Default: 500 milliseconds.
+    ///
+    /// # Arguments
+    /// * `retry_delay` - The delay before the first retry.
+    ///
+    pub fn set_retry_delay(&self, retry_delay: time::Duration) {
+        *self.retry_delay.write().unwrap() = retry_delay;
+    }
+
+    /// Returns the delay before the first retry of a request
+    pub(crate) fn retry_delay(&self) -> time::Duration {
+        *self.retry_delay.read().unwrap()
+    }
+
+    /// Sets the User-Agent header sent with every request, so Spotify can tell which application made it. Default: `spotify.rs/<version>`.
+    /// Fails with `SpotifyError::InvalidRequest` if the User-Agent can't be sent as a header (i.e. it contains a line break),
+    /// in which case the previous User-Agent is kept.
+    ///
+    /// # Arguments
+    /// * `user_agent` - The new User-Agent, i.e. `my-app/1.0`
+    ///
+    pub fn set_user_agent(&self, user_agent: &str) -> Result<(), SpotifyError> {
+        let header_value = match HeaderValue::from_str(user_agent) {
+            Ok(header_value) => header_value,
+            Err(_) => {
+                return Err(SpotifyError::InvalidRequest(format!(
+                    "{} is not a valid User-Agent",
+                    user_agent
+                )))
+            }
+        };
+
+        *self.user_agent.write().unwrap() = header_value; // parsed once here rather than on every request
+        Ok(())
+    }
+
+    /// Returns the User-Agent header sent with every request
+    pub(crate) fn user_agent(&self) -> HeaderValue {
+        self.user_agent.read().unwrap().clone()
+    }
+
+    /// Sets how long `authenticate` waits for the user to complete authorization in the browser. Default: 2 minutes
+    ///
+    /// # Arguments
+    /// * `auth_timeout` - The new timeout
+    ///
+    pub fn set_auth_timeout(&self, auth_timeout: time::Duration) {
+        *self.auth_timeout.write().unwrap() = auth_timeout;
+    }
+
+    /// Returns how long `authenticate` waits for the user to complete authorization
+    pub(crate) fn auth_timeout(&self) -> time::Duration {
+        *self.auth_timeout.read().unwrap()
+    }
+
+    /// Sets the page `authenticate` shows in the browser once the user authorized the application. Default: a built-in success page.
+    /// To send the user elsewhere, use a page that redirects, i.e. with `<meta http-equiv="refresh" content="0; url=https://example.com">`.
+    ///
+    /// # Arguments
+    /// * `success_page` - The HTML of the page, or None for the built-in page
+    ///
+    pub fn set_auth_success_page(&self, success_page: Option<String>) {
+        *self.auth_success_page.write().unwrap() = success_page;
+    }
+
+    /// Returns the page `authenticate` shows once the user authorized the application, None for the built-in page
+    pub(crate) fn auth_success_page(&self) -> Option<String> {
+        self.auth_success_page.read().unwrap().clone()
+    }
+
+    /// Sets whether player commands (i.e. pause, skip, seek) given no device id are retried on the first available device when
+    /// Spotify reports that no device is active. Needs the user-read-playback-state scope to list the devices. Default: false,
+    /// the command fails with `SpotifyError::NoActiveDevice`
+    ///
+    /// # Arguments
+    /// * `auto_select_device` - Whether to pick a device automatically
+    ///
+    pub fn set_auto_select_device(&self, auto_select_device: bool) {
+        *self.auto_select_device.write().unwrap() = auto_select_device;
+    }
+
+    /// Returns whether player commands are retried on the first available device when no device is active
+    pub(crate) fn auto_select_device(&self) -> bool {
+        *self.auto_select_device.read().unwrap()
+    }
+
+    /// Returns true if the access token belongs to a user (and not just the application). Only user tokens come with a refresh token
+    pub(crate) fn is_user_authenticated(&self) -> bool {
+        self.is_authenticated() && self.refresh_token.read().unwrap().is_some()
+    }
+
+    /// Returns the http client requests are sent with. Clones share the same connection pool
+    pub(crate) fn client(&self) -> &reqwest::blocking::Client {
+        &self.client
+    }
+
+    /// Returns the cached genre seeds, if they have been fetched
+    pub(crate) fn cached_genre_seeds(&self) -> Option<Vec<String>> {
+        self.genre_seeds.read().unwrap().clone()
+    }
+
+    /// Caches the genre seeds
+    pub(crate) fn cache_genre_seeds(&self, genre_seeds: Vec<String>) {
+        *self.genre_seeds.write().unwrap() = Some(genre_seeds);
+    }
+
+    /// Returns the space separated scopes the client was authorized with, empty if there are none
+    pub(crate) fn scope_string(&self) -> String {
+        match &*self.scope.read().unwrap() {
+            Some(scope) => scope.clone(),
+            None => String::new(), // not authenticated
+        }
+    }
+
+    /// Returns the cached id of the current user, if it has been fetched
+    pub(crate) fn cached_user_id(&self) -> Option<String> {
+        self.user_id.read().unwrap().clone()
+    }
+
+    /// Caches the id of the current user
+    pub(crate) fn cache_user_id(&self, user_id: String) {
+        *self.user_id.write().unwrap() = Some(user_id);
+    }
+
+    /// Returns the cached market of the current user, if it has been fetched
+    pub(crate) fn cached_user_market(&self) -> Option<Option<Market>> {
+        self.user_market.read().unwrap().clone()
+    }
+
+    /// Caches the market of the current user
+    pub(crate) fn cache_user_market(&self, user_market: Option<Market>) {
+        *self.user_market.write().unwrap() = Some(user_market);
+    }
+}
+
+/// Where a Spotify object built by SpotifyBuilder gets its authorization from
+enum BuilderAuthorization {
+    None,         // not authenticated, call authenticate() later
+    File(String), // authorization information saved with save_to_file
+    Tokens(String, String, String, String, DateTime<Utc>), // client id, scope, access token, refresh token, expires at
+}
+
+/// Struct to collect the configuration of a Spotify object. Built up with chained calls and turned into a configured Spotify object with `build`,
+/// i.e. `SpotifyBuilder::new().timeout(Duration::from_secs(10)).max_retries(5).from_file("auth.txt").build()`.
+/// Options that aren't set keep the defaults of `Spotify::new()`
+pub struct SpotifyBuilder {
+    api_url: Option<String>,                   // base url requests are sent to
+    accounts_url: Option<String>,              // base url tokens are refreshed at
+    max_retries: Option<u32>, // maximum number of retries for a request that failed with a server error
+    retry_delay: Option<time::Duration>, // delay before the first retry of a request
+    user_agent: Option<String>, // User-Agent header sent with every request
+    timeout: Option<time::Duration>, // how long a request may take before it fails with SpotifyError::Timeout
+    auth_timeout: Option<time::Duration>, // how long authenticate waits for the user to authorize in the browser
+    auth_success_page: Option<String>,    // HTML authenticate shows in the browser once authorized
+    auto_select_device: bool, // whether player commands retry on the first available device when no device is active
+    client: Option<reqwest::blocking::Client>, // http client to send requests with
+    authorization: BuilderAuthorization, // where to get the authorization from
+}
+
+impl Default for SpotifyBuilder {
+    /// default
+    fn default() -> Self {
+        SpotifyBuilder::new()
+    }
+}
+
+impl SpotifyBuilder {
+    /// Creates a builder with the default configuration and no authorization
+    pub fn new() -> SpotifyBuilder {
+        SpotifyBuilder {
+            api_url: None,
+            accounts_url: None,
+            max_retries: None,
+            retry_delay: None,
+            user_agent: None,
+            timeout: None,
+            auth_timeout: None,
+            auth_success_page: None,
+            auto_select_device: false,
+            client: None,
+            authorization: BuilderAuthorization::None,
+        }
+    }
+
+    /// Sets the base url requests are sent to (see [set_api_url](struct.Spotify.html#method.set_api_url))
+    pub fn api_url(mut self, api_url: &str) -> SpotifyBuilder {
+        self.api_url = Some(api_url.to_string());
+        self
+    }
+
+    /// Sets the base url tokens are refreshed at (see [set_accounts_url](struct.Spotify.html#method.set_accounts_url))
+    pub fn accounts_url(mut self, accounts_url: &str) -> SpotifyBuilder {
+        self.accounts_url = Some(accounts_url.to_string());
+        self
+    }
+
+    /// Sets the maximum number of retries on server errors (see [set_max_retries](struct.Spotify.html#method.set_max_retries))
+    pub fn max_retries(mut self, max_retries: u32) -> SpotifyBuilder {
+        self.max_retries = Some(max_retries);
+        self
+    }
+
+    /// Sets the delay before the first retry on server errors (see [set_retry_delay](struct.Spotify.html#method.set_retry_delay))
+    pub fn retry_delay(mut self, retry_delay: time::Duration) -> SpotifyBuilder {
+        self.retry_delay = Some(retry_delay);
+        self
+    }
+
+    /// Sets the User-Agent header sent with every request (see [set_user_agent](struct.Spotify.html#method.set_user_agent)). `build` fails if it isn't a valid header value
+    pub fn user_agent(mut self, user_agent: &str) -> SpotifyBuilder {
+        self.user_agent = Some(user_agent.to_string());
+        self
+    }
+
+    /// Sets how long a request may take before it fails with `SpotifyError::Timeout`. Default: 30 seconds. Ignored if a client is given with `client`
+    pub fn timeout(mut self, timeout: time::Duration) -> SpotifyBuilder {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Sets how long `authenticate` waits for the user to authorize (see [set_auth_timeout](struct.Spotify.html#method.set_auth_timeout))
+    pub fn auth_timeout(mut self, auth_timeout: time::Duration) -> SpotifyBuilder {
+        self.auth_timeout = Some(auth_timeout);
+        self
+    }
+
+    /// Sets the page `authenticate` shows once the user authorized (see [set_auth_success_page](struct.Spotify.html#method.set_auth_success_page))
+    pub fn auth_success_page(mut self, success_page: &str) -> SpotifyBuilder {
+        self.auth_success_page = Some(success_page.to_string());
+        self
+    }
+
+    /// Sets whether player commands pick a device when none is active (see [set_auto_select_device](struct.Spotify.html#method.set_auto_select_device))
+    pub fn auto_select_device(mut self, auto_select_device: bool) -> SpotifyBuilder {
+        self.auto_select_device = auto_select_device;
+        self
+    }
+
+    /// Sets the http client requests are sent with, i.e. one configured with a proxy. It is used as is, so `timeout` doesn't apply to it
+    pub fn client(mut self, client: reqwest::blocking::Client) -> SpotifyBuilder {
+        self.client = Some(client);
+        self
+    }
+
+    /// Authorizes the Spotify object from a file saved with `save_to_file` when building (see [new_from_file](struct.Spotify.html#method.new_from_file))
+    pub fn from_file(mut self, file_name: &str) -> SpotifyBuilder {
+        self.authorization = BuilderAuthorization::File(file_name.to_string());
+        self
+    }
+
+    /// Authorizes the Spotify object with existing tokens (see [from_tokens](struct.Spotify.html#method.from_tokens))
+    pub fn from_tokens(
+        mut self,
+        client_id: &str,
+        scope: &str,
+        access_token: &str,
+        refresh_token: &str,
+        expires_at: DateTime<Utc>,
+    ) -> SpotifyBuilder {
+        self.authorization = BuilderAuthorization::Tokens(
+            client_id.to_string(),
+            scope.to_string(),
+            access_token.to_string(),
+            refresh_token.to_string(),
+            expires_at,
+        );
+        self
+    }
+
+    /// Creates the configured Spotify object. Fails if the http client can't be created or authorizing from a file fails
+    pub fn build(self) -> Result<Spotify, SpotifyError> {
+        let client = match self.client {
+            Some(client) => client,
+            None => match self.timeout {
+                Some(timeout) => reqwest::blocking::Client::builder()
+                    .timeout(timeout)
+                    .build()?,
+                None => reqwest::blocking::Client::new(), // default client
+            },
+        };
+
+        let spotify = Spotify {
+            client,
+            ..Spotify::new()
+        };
+
+        if let Some(api_url) = &self.api_url {
+            spotify.set_api_url(api_url);
+        }
+        if let Some(accounts_url) = &self.accounts_url {
+            spotify.set_accounts_url(accounts_url); // set before authorizing, so tokens are refreshed there
+        }
+        if let Some(max_retries) = self.max_retries {
+            spotify.set_max_retries(max_retries);
+        }
+        if let Some(retry_delay) = self.retry_delay {
+            spotify.set_retry_delay(retry_delay);
+        }
+        if let Some(user_agent) = &self.user_agent {
+            spotify.set_user_agent(user_agent)?;
+        }
+        if let Some(auth_timeout) = self.auth_timeout {
+            spotify.set_auth_timeout(auth_timeout);
+        }
+        spotify.set_auth_success_page(self.auth_success_page);
+        spotify.set_auto_select_device(self.auto_select_device);
+
+        match self.authorization {
+            BuilderAuthorization::None => (),
+            BuilderAuthorization::File(file_name) => spotify.authenticate_from_file(&file_name)?,
+            BuilderAuthorization::Tokens(
+                client_id,
+                scope,
+                access_token,
+                refresh_token,
+                expires_at,
+            ) => {
+                *spotify.client_id.write().unwrap() = Some(client_id);
+                *spotify.scope.write().unwrap() = Some(scope);
+                *spotify.access_token.write().unwrap() = Some(access_token);
+                *spotify.refresh_token.write().unwrap() = Some(refresh_token);
+                *spotify.expires_at.write().unwrap() = Some(expires_at);
+            }
+        }
+
+        Ok(spotify)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Market, Spotify};
+    use chrono::{Duration, Utc};
+    use std::sync::RwLock;
+
+    #[test]
+    fn market_defaults_only_for_user_tokens() {
+        let user = Spotify::from_tokens(
+            String::from("client_id"),
+            String::from("user-read-private"),
+            String::from("access_token"),
+            String::from("refresh_token"),
+            Utc::now() + Duration::seconds(3600),
+        );
+        assert_eq!(user.market_or_user_default(None), Some(Market::FromToken));
+
+        // an application token isn't tied to a user, so it has no refresh token and no market to default to
+        let application = Spotify {
+            access_token: RwLock::new(Some(String::from("access_token"))),
+            expires_at: RwLock::new(Some(Utc::now() + Duration::seconds(3600))),
+            ..Spotify::new()
+        };
+        assert_eq!(application.market_or_user_default(None), None);
+        assert_eq!(
+            application.market_or_user_default(Some(Market::FromToken)),
+            Some(Market::FromToken)
+        );
+    }
 }
@@ -3,8 +3,15 @@ use json::{self, JsonValue, Null};
 use reqwest;
 use serde_json::Value;
 use std::collections::HashMap;
+use std::fmt::Display;
+use std::thread;
+use std::time::Instant;
+
+/// Number of characters of an unexpected (non-JSON) response body included in the error
+const BODY_SNIPPET_LENGTH: usize = 200;
 
 /// Enum to store types of requests relevant to Spotify API
+#[derive(Clone)]
 pub enum RequestMethod {
     Get,
     Post(HashMap<String, Value>),
@@ -12,11 +19,145 @@ pub enum RequestMethod {
     Delete(HashMap<String, Value>),
 }
 
+/// Query parameters whose values are credentials and are never logged
+const SENSITIVE_QUERY_PARAMETERS: [&str; 5] = [
+    "access_token",
+    "refresh_token",
+    "code",
+    "code_verifier",
+    "client_secret",
+];
+
+impl RequestMethod {
+    /// The http method of the request, i.e. "GET"
+    fn as_str(&self) -> &'static str {
+        match self {
+            RequestMethod::Get => "GET",
+            RequestMethod::Post(_) => "POST",
+            RequestMethod::Put(_) => "PUT",
+            RequestMethod::Delete(_) => "DELETE",
+        }
+    }
+}
+
+/// Returns the url with the values of credential query parameters (i.e. `refresh_token`) replaced, so it can be logged
+///
+/// # Arguments
+/// * `url` - The request url
+///
+pub(crate) fn redact_url(url: &str) -> String {
+    let (base, query) = match url.split_once('?') {
+        Some((base, query)) => (base, query),
+        None => return url.to_string(), // no query string, nothing to redact
+    };
+
+    let parameters: Vec<String> = query
+        .split('&')
+        .map(|parameter| match parameter.split_once('=') {
+            Some((key, _)) if SENSITIVE_QUERY_PARAMETERS.contains(&key) => {
+                format!("{}=REDACTED", key)
+            }
+            _ => parameter.to_string(),
+        })
+        .collect();
+
+    format!("{}?{}", base, parameters.join("&"))
+}
+
+/// Appends a query parameter to a request url. Starts the query string with `?` if the url doesn't have one yet, otherwise separates parameters with `&`
+///
+/// # Arguments
+/// * `url_extension` - url (or part of url) to add the parameter to
+/// * `key` - name of the query parameter
+/// * `value` - value of the query parameter
+///
+pub(crate) fn append_query_parameter<T: Display>(url_extension: &mut String, key: &str, value: T) {
+    if url_extension.contains('?') {
+        url_extension.push('&'); // query string already started, separate from previous parameter
+    } else {
+        url_extension.push('?'); // start query string
+    }
+
+    url_extension.push_str(&format!("{}={}", key, value));
+}
+
+/// Whether an error message is about an invalid (i.e. outdated) playlist snapshot id
+///
+/// # Arguments
+/// * `message` - The message of the error returned by Spotify
+///
+fn is_snapshot_error(message: &JsonValue) -> bool {
+    match message.as_str() {
+        Some(message) => message.to_lowercase().contains("snapshot"),
+        None => false,
+    }
+}
+
+/// Whether a 403 error is Spotify refusing a player command because the user doesn't have Premium
+///
+/// # Arguments
+/// * `error` - The error object returned by Spotify
+///
+fn is_premium_required_error(error: &JsonValue) -> bool {
+    match (error["reason"].as_str(), error["message"].as_str()) {
+        (Some(reason), _) => reason == "PREMIUM_REQUIRED",
+        (None, Some(message)) => message.to_lowercase().contains("premium required"),
+        (None, None) => false,
+    }
+}
+
+/// Whether an error is Spotify refusing a player command because no device is active
+///
+/// # Arguments
+/// * `error` - The error object returned by Spotify
+///
+fn is_no_active_device_error(error: &JsonValue) -> bool {
+    match (error["reason"].as_str(), error["message"].as_str()) {
+        (Some(reason), _) => reason == "NO_ACTIVE_DEVICE",
+        (None, Some(message)) => message.to_lowercase().contains("no active device"),
+        (None, None) => false,
+    }
+}
+
 impl Spotify {
+    /// Builds the full url a request would be sent to without making the request
+    ///
+    /// # Arguments
+    /// * `url_extension` - part of url past: `https://api.spotify.com/v1/` (or the base url set with `set_api_url`)
+    ///
+    pub fn build_url(&self, url_extension: &str) -> String {
+        return format!("{}/{}", self.api_url(), url_extension);
+    }
+
+    /// Makes a request to any endpoint of the Spotify API, including ones this crate doesn't wrap (yet). Handles authorization,
+    /// refreshing the access token, retries and error mapping like every other method; the response is returned as untyped json.
+    /// Prefer the typed methods where they exist.
+    ///
+    /// # Arguments
+    /// * `method` - type of request (GET, POST, PUT, DELETE), holding the json body for requests that have one
+    /// * `url_extension` - part of url past: `https://api.spotify.com/v1/`, including any query parameters. I.e. `me/player/devices`
+    ///
+    pub fn request(
+        &self,
+        method: RequestMethod,
+        url_extension: &str,
+    ) -> Result<Value, SpotifyError> {
+        let response = self.spotify_request(url_extension, method)?;
+
+        if response.is_null() {
+            return Ok(Value::Null); // no content
+        }
+
+        match serde_json::from_str(&response.dump()) {
+            Ok(value) => Ok(value),
+            Err(e) => Err(SpotifyError::RequestError(e.to_string())), // shouldn't happen, the response was already valid json
+        }
+    }
+
     /// General request to the spotify API. Returns JSON response
     ///
     /// # Arguments
-    /// * `url_extension` - part of url past: `https://api.spotify.com/v1/`. Specific to each type of request
+    /// * `url_extension` - part of url past: `https://api.spotify.com/v1/` (or the base url set with `set_api_url`). Specific to each type of request
     /// * `request_method` - type of request (GET, POST, PUT, DELETE)
     ///
     /// # Panics
@@ -29,61 +170,97 @@ impl Spotify {
     ) -> Result<JsonValue, SpotifyError> {
         let access_token = self.access_token()?; // get access token
 
-        let client = reqwest::blocking::Client::new(); // create client
+        let client = self.client(); // shared client, reuses open connections
 
         let mut headers = reqwest::header::HeaderMap::new(); // create header map
         headers.insert(
             "Authorization",
             format!("Bearer {}", access_token).parse().unwrap(),
         ); // insert authorization header
+        headers.insert("User-Agent", self.user_agent()); // identify the application
+
+        let request_url = self.build_url(url_extension); // create request url
 
-        let request_url = format!("https://api.spotify.com/v1/{}", url_extension); // create request url
+        // only GET requests are retried: a write may already have been applied when a gateway responds with a server error
+        let max_retries = match request_method {
+            RequestMethod::Get => self.max_retries(), // maximum number of retries on server errors
+            _ => 0,
+        };
+        let retry_delay = self.retry_delay(); // delay before the first retry
+        let mut retries = 0; // number of retries made so far
 
-        // Send appropriate request for request method
-        let response = match request_method {
-            RequestMethod::Get => match client.get(&request_url).headers(headers).send() {
-                Ok(response) => response,
-                Err(e) => return Err(SpotifyError::RequestError(e.to_string())),
-            },
-            RequestMethod::Post(body) => {
-                match client
+        // Send appropriate request for request method. Retry with exponential backoff on transient server errors
+        let response = loop {
+            let request = match &request_method {
+                RequestMethod::Get => client.get(&request_url).headers(headers.clone()),
+                RequestMethod::Post(body) => client
                     .post(&request_url)
-                    .headers(headers)
+                    .headers(headers.clone())
                     .header("Content-Type", "application/json")
-                    .json(&body)
-                    .send()
-                {
-                    Ok(response) => response,
-                    Err(e) => return Err(SpotifyError::RequestError(e.to_string())),
-                }
-            }
-            RequestMethod::Put(body) => {
-                match client
+                    .json(body),
+                RequestMethod::Put(body) => client
                     .put(&request_url)
-                    .headers(headers)
+                    .headers(headers.clone())
                     .header("Content-Type", "application/json")
-                    .json(&body)
-                    .send()
-                {
-                    Ok(response) => response,
-                    Err(e) => return Err(SpotifyError::RequestError(e.to_string())),
-                }
-            }
-            RequestMethod::Delete(body) => {
-                match client
+                    .json(body),
+                RequestMethod::Delete(body) => client
                     .delete(&request_url)
-                    .headers(headers)
+                    .headers(headers.clone())
                     .header("Content-Type", "application/json")
-                    .json(&body)
-                    .send()
-                {
-                    Ok(response) => response,
-                    Err(e) => return Err(SpotifyError::RequestError(e.to_string())),
-                }
+                    .json(body),
+            };
+
+            let started = Instant::now();
+            let response = request.send()?; // no response, i.e. connection refused or timed out
+
+            // the Authorization header is never logged, only the method, url, status and latency
+            log::debug!(
+                "{} {} -> {} ({} ms)",
+                request_method.as_str(),
+                redact_url(&request_url),
+                response.status().as_u16(),
+                started.elapsed().as_millis()
+            );
+
+            if response.status().is_server_error() && retries < max_retries {
+                log::trace!(
+                    "retrying after server error, retry {} of {}",
+                    retries + 1,
+                    max_retries
+                );
+                thread::sleep(retry_delay * 2u32.pow(retries)); // wait i.e. 0.5s, 1s, 2s, ... before retrying
+                retries += 1;
+                continue;
             }
+
+            break response;
         };
 
-        let response_body = json::parse(&response.text().unwrap()); // parse response body
+        let status = response.status(); // keep status, reading the body consumes the response
+        let content_type = match response.headers().get(reqwest::header::CONTENT_TYPE) {
+            Some(content_type) => content_type.to_str().unwrap_or("").to_string(),
+            None => String::new(), // default to empty string
+        };
+
+        let response_text = response.text()?; // fails if the connection dropped while reading the body
+
+        // errors that aren't JSON (i.e. an HTML error page from a proxy) can't be parsed, so report what was received instead
+        if !status.is_success()
+            && !content_type.contains("json")
+            && !response_text.trim().is_empty()
+        {
+            return Err(SpotifyError::RequestError(format!(
+                "Error code: {}, unexpected {} response: {}",
+                status.as_u16(),
+                content_type,
+                response_text
+                    .chars()
+                    .take(BODY_SNIPPET_LENGTH)
+                    .collect::<String>()
+            )));
+        }
+
+        let response_body = json::parse(&response_text); // parse response body
         match response_body {
             // check for errors
             Ok(response_body) => {
@@ -96,12 +273,36 @@ impl Spotify {
                         Some(401) => Err(SpotifyError::BadOrExpiredToken(
                             response_body["error"]["message"].to_string(),
                         )),
+                        Some(403) if is_premium_required_error(&response_body["error"]) => {
+                            Err(SpotifyError::PremiumRequired(
+                                response_body["error"]["message"].to_string(),
+                            ))
+                        } // player commands need a Premium account
                         Some(403) => Err(SpotifyError::BadRequest(
                             response_body["error"]["message"].to_string(),
                         )),
+                        Some(404) if is_no_active_device_error(&response_body["error"]) => {
+                            Err(SpotifyError::NoActiveDevice(
+                                response_body["error"]["message"].to_string(),
+                            ))
+                        } // player command without an active device
+                        Some(404) => Err(SpotifyError::NotFound(
+                            response_body["error"]["message"].to_string(),
+                        )),
                         Some(429) => Err(SpotifyError::RateLimitExceeded(
                             response_body["error"]["message"].to_string(),
                         )),
+                        Some(400)
+                            if url_extension.starts_with("playlists/")
+                                && is_snapshot_error(&response_body["error"]["message"]) =>
+                        {
+                            Err(SpotifyError::SnapshotMismatch(
+                                response_body["error"]["message"].to_string(),
+                            ))
+                        } // stale snapshot id given when modifying a playlist
+                        Some(400) => Err(SpotifyError::MalformedRequest(
+                            response_body["error"]["message"].to_string(),
+                        )),
                         _ => Err(SpotifyError::RequestError(format!(
                             "Error code: {}, message: {}",
                             response_body["error"]["status"], response_body["error"]["message"]
@@ -109,7 +310,43 @@ impl Spotify {
                     }
                 }
             }
+            Err(_) if status.is_server_error() => Err(SpotifyError::RequestError(format!(
+                "Error code: {}, message: server error",
+                status.as_u16()
+            ))), // server errors (i.e. 502 from a gateway) often don't have a json body
             Err(_) => Ok(Null), // on json parsing error just return nothing (temp fix for a potential non-problem)
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{append_query_parameter, redact_url};
+
+    #[test]
+    fn query_parameter_separators() {
+        let mut url_extension = String::from("albums/4aawyAB9vmqN3uQ7FjRGTy/tracks");
+        append_query_parameter(&mut url_extension, "market", "SE");
+        append_query_parameter(&mut url_extension, "limit", 50);
+        assert_eq!(
+            url_extension,
+            "albums/4aawyAB9vmqN3uQ7FjRGTy/tracks?market=SE&limit=50"
+        );
+
+        let mut url_extension = String::from("tracks?ids=7ouMYWpwJ422jRcDASZB7P");
+        append_query_parameter(&mut url_extension, "market", "SE");
+        assert_eq!(url_extension, "tracks?ids=7ouMYWpwJ422jRcDASZB7P&market=SE");
+    }
+
+    #[test]
+    fn credentials_are_redacted_from_urls() {
+        assert_eq!(
+            redact_url("https://accounts.spotify.com/api/token?grant_type=refresh_token&refresh_token=secret&client_id=abc"),
+            "https://accounts.spotify.com/api/token?grant_type=refresh_token&refresh_token=REDACTED&client_id=abc"
+        );
+        assert_eq!(
+            redact_url("https://api.spotify.com/v1/me"),
+            "https://api.spotify.com/v1/me"
+        );
+    }
+}
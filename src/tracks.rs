@@ -1,10 +1,10 @@
+use crate::ids::check_id;
 use crate::spotify::{
-    AnalysisTrack, DatedTrack, FeatureTrack, Spotify, SpotifyCollection, SpotifyError,
-    SpotifyObject, Track,
+    AnalysisTrack, DatedTrack, FeatureTrack, Market, RecommendationParams, Recommendations,
+    Spotify, SpotifyCollection, SpotifyError, SpotifyObject, Track,
 };
-use crate::srequest::RequestMethod;
+use crate::srequest::{append_query_parameter, RequestMethod};
 use json::JsonValue::Boolean;
-use querystring::stringify;
 use serde_json::Value;
 use std::collections::HashMap;
 
@@ -24,30 +24,73 @@ impl Spotify {
         return Ok(Track::new(&response)); // format and return result
     }
 
+    /// Checks in which of several markets a track is available, with a single request: <https://developer.spotify.com/documentation/web-api/reference/#/operations/get-track>
+    /// The track is requested without a market and each market is checked against its `available_markets`.
+    /// `Market::FromToken` is checked as the current user's market (see `user_market`).
+    ///
+    /// Required scope: none (user-read-private to check `Market::FromToken`)
+    ///
+    /// # Arguments
+    /// * `track_id` - The Spotify ID of the track.
+    /// * `markets` - The markets to check.
+    ///
+    pub fn track_availability(
+        &self,
+        track_id: &str,
+        markets: &[Market],
+    ) -> Result<HashMap<Market, bool>, SpotifyError> {
+        let track = self.get_track(track_id)?; // without a market, so available_markets is included
+
+        let mut availability = HashMap::new();
+        for market in markets {
+            let code = match market {
+                Market::FromToken => match self.user_market()? {
+                    Some(user_market) => user_market.code().to_string(),
+                    None => {
+                        return Err(SpotifyError::InvalidRequest(String::from(
+                            "The current user's market is unknown",
+                        )))
+                    } // no country without the user-read-private scope
+                },
+                market => market.code().to_string(),
+            };
+
+            availability.insert(market.clone(), track.available_markets.contains(&code));
+        }
+
+        return Ok(availability);
+    }
+
     /// Get information on many tracks: <https://developer.spotify.com/documentation/web-api/reference/#/operations/get-several-tracks>
+    /// Tracks are requested in batches of 50 (the most Spotify allows per request), and are returned in the same order as `track_ids`.
+    /// When `market` is set, each track's `is_playable` reports whether it can be played in that market.
     ///
     /// Required scope: none
     ///
     /// # Arguments
     /// * `track_ids` - A vector of Spotify track ids.
-    /// * `market` - An ISO 3166-1 alpha-2 country code.
+    /// * `market` - An ISO 3166-1 alpha-2 country code, or `Market::FromToken` for the current user's market.
     ///
     pub fn get_several_tracks(
         &self,
         track_ids: Vec<&str>,
-        market: Option<&str>,
+        market: Option<Market>,
     ) -> Result<Vec<Track>, SpotifyError> {
-        let mut url_extension = format!("tracks/?ids={}", track_ids.join(",")); // base url with track ids added
+        let mut tracks = Vec::new(); // create vector to store tracks
 
-        if let Some(market) = market {
-            // if market is set, add to url
-            url_extension.push_str(&format!("?market={}", market));
-        }
+        // Spotify returns a batch in the order of its ids, so requesting batches in order keeps the overall order
+        for track_ids_batch in track_ids.chunks(50) {
+            let mut url_extension = format!("tracks/?ids={}", track_ids_batch.join(",")); // base url with track ids added
 
-        let response = self.spotify_request(&url_extension, RequestMethod::Get)?; // make request
-        let mut tracks = Vec::new(); // create vector to store tracks
-        for track in response["tracks"].members() {
-            tracks.push(Track::new(&track)); // format track and push to vector
+            if let Some(market) = &market {
+                // if market is set, add to url
+                append_query_parameter(&mut url_extension, "market", market.code());
+            }
+
+            let response = self.spotify_request(&url_extension, RequestMethod::Get)?; // make request
+            for track in response["tracks"].members() {
+                tracks.push(Track::new(&track)); // format track and push to vector
+            }
         }
 
         return Ok(tracks); // return vector of tracks
@@ -59,37 +102,32 @@ impl Spotify {
     ///
     /// # Arguments
     /// * `limit` - The number of tracks to return. Default: 20. Minimum: 1. Maximum: 50.
-    /// * `market` - An ISO 3166-1 alpha-2 country code.
+    /// * `market` - An ISO 3166-1 alpha-2 country code, or `Market::FromToken` for the current user's market.
     /// * `offset` - The index of the first track to return. Default: 0 (i.e., the first track). Use with limit to get the next set of tracks.
     ///
     pub fn get_user_saved_tracks(
         &self,
         limit: Option<u32>,
-        market: Option<&str>,
+        market: Option<Market>,
         offset: Option<u32>,
     ) -> Result<SpotifyCollection<DatedTrack>, SpotifyError> {
         let mut url_extension = String::from("me/tracks"); // base url
 
         self.check_scope("user-library-read")?; // check scope
 
-        if limit != None || market != None || offset != None {
-            // if any optional parameters are set, add ? to url
-            url_extension.push_str("?");
-        }
-
         if let Some(limit) = limit {
             // if limit is set, add to url
-            url_extension.push_str(&format!("&limit={}", limit));
+            append_query_parameter(&mut url_extension, "limit", limit);
         }
 
         if let Some(market) = market {
             // if market is set, add to url
-            url_extension.push_str(&format!("&market={}", market));
+            append_query_parameter(&mut url_extension, "market", market.code());
         }
 
         if let Some(offset) = offset {
             // if offset is set, add to url
-            url_extension.push_str(&format!("&offset={}", offset));
+            append_query_parameter(&mut url_extension, "offset", offset);
         }
 
         let response = self.spotify_request(&url_extension, RequestMethod::Get)?; // make request
@@ -105,6 +143,10 @@ impl Spotify {
     /// * `track_ids` - A vector of Spotify track ids
     ///
     pub fn save_tracks(&self, track_ids: Vec<&str>) -> Result<(), SpotifyError> {
+        if track_ids.is_empty() {
+            return Ok(()); // nothing to request
+        }
+
         let url_extension = format!("me/tracks?ids={}", track_ids.join(",")); // base url
 
         self.check_scope("user-library-modify")?; // check scope
@@ -134,6 +176,10 @@ impl Spotify {
     /// * `track_ids` - A vector of Spotify track IDs
     ///
     pub fn remove_tracks(&self, track_ids: Vec<&str>) -> Result<(), SpotifyError> {
+        if track_ids.is_empty() {
+            return Ok(()); // nothing to request
+        }
+
         let url_extension = format!("me/tracks?ids={}", track_ids.join(",")); // base url
 
         self.check_scope("user-library-modify")?; // check scope
@@ -160,28 +206,74 @@ impl Spotify {
     /// Required scope: user-library-read
     ///
     /// # Arguments
-    /// * `track_ids` - A vector of track ids to check
+    /// * `track_ids` - A vector of track ids to check. Checked in batches of 50.
     ///
     pub fn check_saved_tracks(&self, track_ids: Vec<&str>) -> Result<Vec<bool>, SpotifyError> {
-        let url_extension = format!("me/tracks/contains?ids={}", track_ids.join(",")); // base url
+        if track_ids.is_empty() {
+            return Ok(Vec::new()); // nothing to request
+        }
 
         self.check_scope("user-library-read")?; // check scope
 
-        let response = self.spotify_request(&url_extension, RequestMethod::Get)?; // make request
+        let mut saved_tracks = Vec::new(); // create vector to store saved tracks
+
+        // Spotify checks at most 50 ids per request and answers in the order of the ids, so checking batches in order keeps the overall order
+        for track_ids_batch in track_ids.chunks(50) {
+            let url_extension = format!("me/tracks/contains?ids={}", track_ids_batch.join(",")); // base url
 
-        let mut saved_tracks = Vec::new(); // create vector to store saved albums
+            let response = self.spotify_request(&url_extension, RequestMethod::Get)?; // make request
 
-        for track in response.members() {
-            match track {
-                Boolean(saved) => saved_tracks.push(*saved), // push saved status to vector
-                _ => return Err(SpotifyError::RequestError("Invalid response".to_string())), // return error if invalid response
+            for track in response.members() {
+                match track {
+                    Boolean(saved) => saved_tracks.push(*saved), // push saved status to vector
+                    _ => return Err(SpotifyError::RequestError("Invalid response".to_string())), // return error if invalid response
+                }
             }
         }
 
         return Ok(saved_tracks); // return vector of saved tracks
     }
 
+    /// Get the user's "Liked Songs". These aren't a playlist, but the tracks saved in the user's library, so this is the same as `get_user_saved_tracks`: <https://developer.spotify.com/documentation/web-api/reference/#/operations/get-users-saved-tracks>
+    ///
+    /// Required scope: user-library-read
+    ///
+    /// # Arguments
+    /// * `limit` - The number of tracks to return. Default: 20. Minimum: 1. Maximum: 50.
+    /// * `offset` - The index of the first track to return. Default: 0 (i.e., the first track). Use with limit to get the next set of tracks.
+    ///
+    pub fn get_liked_songs(
+        &self,
+        limit: Option<u32>,
+        offset: Option<u32>,
+    ) -> Result<SpotifyCollection<DatedTrack>, SpotifyError> {
+        return self.get_user_saved_tracks(limit, None, offset);
+    }
+
+    /// Add tracks to the user's "Liked Songs", the same as `save_tracks`: <https://developer.spotify.com/documentation/web-api/reference/#/operations/save-tracks-user>
+    ///
+    /// Required scope: user-library-modify
+    ///
+    /// # Arguments
+    /// * `track_ids` - A vector of Spotify track ids
+    ///
+    pub fn add_to_liked(&self, track_ids: Vec<&str>) -> Result<(), SpotifyError> {
+        return self.save_tracks(track_ids);
+    }
+
+    /// Remove tracks from the user's "Liked Songs", the same as `remove_tracks`: <https://developer.spotify.com/documentation/web-api/reference/#/operations/remove-tracks-user>
+    ///
+    /// Required scope: user-library-modify
+    ///
+    /// # Arguments
+    /// * `track_ids` - A vector of Spotify track ids
+    ///
+    pub fn remove_from_liked(&self, track_ids: Vec<&str>) -> Result<(), SpotifyError> {
+        return self.remove_tracks(track_ids);
+    }
+
     /// Gets audio features for specified track(s): <https://developer.spotify.com/documentation/web-api/reference/#/operations/get-several-audio-features>
+    /// Audio features are requested in batches of 100 (the most Spotify allows per request), and are returned in the same order as `track_ids`.
     ///
     /// Required scope: none
     ///
@@ -192,14 +284,17 @@ impl Spotify {
         &self,
         track_ids: Vec<&str>,
     ) -> Result<Vec<FeatureTrack>, SpotifyError> {
-        let url_extension = format!("audio-features/?ids={}", track_ids.join(",")); // base url
+        let mut feature_tracks = Vec::new(); // create vector to store tracks
 
-        let response = self.spotify_request(&url_extension, RequestMethod::Get)?; // make request
+        // Spotify returns a batch in the order of its ids, so requesting batches in order keeps the overall order
+        for track_ids_batch in track_ids.chunks(100) {
+            let url_extension = format!("audio-features/?ids={}", track_ids_batch.join(",")); // base url
 
-        let mut feature_tracks = Vec::new(); // create vector to store tracks
+            let response = self.spotify_request(&url_extension, RequestMethod::Get)?; // make request
 
-        for track in response["audio_features"].members() {
-            feature_tracks.push(FeatureTrack::new(&track)); // format track and push to vector
+            for track in response["audio_features"].members() {
+                feature_tracks.push(FeatureTrack::new(&track)); // format track and push to vector
+            }
         }
 
         return Ok(feature_tracks); // return vector of tracks
@@ -242,6 +337,7 @@ impl Spotify {
     }
 
     /// Gets track recommendations based on seed artists, tracks, or genres: <https://developer.spotify.com/documentation/web-api/reference/#/operations/get-recommendations>
+    /// Returns the recommended tracks along with the seeds, which tell how many tracks each seed contributed.
     ///
     /// Required scope: none
     ///
@@ -301,7 +397,7 @@ impl Spotify {
         seed_genres: Option<Vec<&str>>,
         seed_tracks: Option<Vec<&str>>,
         optional_parameters: Option<Vec<(&str, &str)>>,
-    ) -> Result<Vec<Track>, SpotifyError> {
+    ) -> Result<Recommendations, SpotifyError> {
         // panic if not supplied with sufficient seed values
         if seed_artists == None && seed_genres == None && seed_tracks == None {
             return Err(SpotifyError::InvalidRequest(String::from(
@@ -326,34 +422,90 @@ impl Spotify {
             )));
         }
 
-        let mut url_extension = String::from("recommendations?");
+        // check seeds are well formed so a bad seed gives a clear error rather than a 400 from the API
+        for artist_id in seed_artists.iter().flatten() {
+            check_id(artist_id, "artist")?;
+        }
+
+        if let Some(seed_genres) = &seed_genres {
+            self.check_genre_seeds(seed_genres)?;
+        }
+
+        for track_id in seed_tracks.iter().flatten() {
+            check_id(track_id, "track")?;
+        }
+
+        let mut url_extension = String::from("recommendations");
 
         // add seed values to url
         if let Some(seed_artists) = seed_artists {
-            url_extension.push_str(&format!("&seed_artists={}", seed_artists.join(",")));
+            append_query_parameter(&mut url_extension, "seed_artists", seed_artists.join(","));
         }
 
         if let Some(seed_genres) = seed_genres {
-            url_extension.push_str(&format!("&seed_genres={}", seed_genres.join(",")));
+            append_query_parameter(&mut url_extension, "seed_genres", seed_genres.join(","));
         }
 
         if let Some(seed_tracks) = seed_tracks {
-            url_extension.push_str(&format!("&seed_tracks={}", seed_tracks.join(",")));
+            append_query_parameter(&mut url_extension, "seed_tracks", seed_tracks.join(","));
         }
 
         // add optional parameters to url
         if let Some(optional_parameters) = optional_parameters {
-            url_extension.push_str("&"); // need to make sure can accept more parameters
-            url_extension.push_str(&stringify(optional_parameters)); // stringify and add optional parameters
+            for (key, value) in optional_parameters {
+                append_query_parameter(&mut url_extension, key, value); // add each parameter to url
+            }
         }
 
         let response = self.spotify_request(&url_extension, RequestMethod::Get)?; // make request
 
-        let mut tracks = Vec::new(); // create vector to hold tracks
-        for track in response["tracks"].members() {
-            tracks.push(Track::new(track)); // format track and push to vector
+        return Ok(Recommendations::new(&response)); // format and return tracks and seeds
+    }
+
+    /// Gets track recommendations from a set of [RecommendationParams](struct.RecommendationParams.html). A wrapper around [get_recommendations](struct.Spotify.html#method.get_recommendations)
+    /// that additionally supports removing explicit tracks. Note: explicit filtering happens client side, so fewer tracks than the requested `limit` may be returned.
+    ///
+    /// Required scope: none
+    ///
+    /// # Arguments
+    /// * `params` - The seeds and tunable parameters for the recommendations
+    ///
+    pub fn get_recommendations_with_params(
+        &self,
+        params: &RecommendationParams,
+    ) -> Result<Recommendations, SpotifyError> {
+        // convert owned parameters into the borrowed form get_recommendations expects. Empty seed lists are passed as None
+        let seed_artists: Vec<&str> = params.seed_artists.iter().map(|s| s.as_str()).collect();
+        let seed_genres: Vec<&str> = params.seed_genres.iter().map(|s| s.as_str()).collect();
+        let seed_tracks: Vec<&str> = params.seed_tracks.iter().map(|s| s.as_str()).collect();
+        let optional_parameters: Vec<(&str, &str)> = params
+            .optional_parameters
+            .iter()
+            .map(|(key, value)| (key.as_str(), value.as_str()))
+            .collect();
+
+        let mut recommendations = self.get_recommendations(
+            Some(seed_artists).filter(|seeds| !seeds.is_empty()),
+            Some(seed_genres).filter(|seeds| !seeds.is_empty()),
+            Some(seed_tracks).filter(|seeds| !seeds.is_empty()),
+            Some(optional_parameters).filter(|parameters| !parameters.is_empty()),
+        )?;
+
+        if params.exclude_explicit {
+            recommendations.tracks.retain(|track| !track.explicit); // remove explicit tracks
         }
 
-        return Ok(tracks); // return vector
+        Ok(recommendations)
+    }
+}
+
+impl SpotifyCollection<Track> {
+    /// Returns the Spotify IDs of the tracks in the collection, in order. Local files are skipped, as they have no Spotify ID
+    pub fn ids(&self) -> Vec<String> {
+        self.items
+            .iter()
+            .filter_map(|track| track.spotify_id())
+            .map(|id| id.to_string())
+            .collect()
     }
 }
@@ -1,10 +1,14 @@
-use crate::spotify::{Artist, Spotify, SpotifyCollection, SpotifyError, TimeRange, Track, User};
-use crate::srequest::RequestMethod;
+use crate::spotify::{
+    Artist, Spotify, SpotifyCollection, SpotifyError, SpotifyObject, TimeRange, TopItemKind, Track,
+    User,
+};
+use crate::srequest::{append_query_parameter, RequestMethod};
 use serde_json::Value;
 use std::collections::HashMap;
+use std::fmt::Debug;
 
 impl Spotify {
-    /// Get information on current user: <https://developer.spotify.com/documentation/web-api/reference/#/operations/get-current-users-profile>
+    /// Get information on current user, including their email address: <https://developer.spotify.com/documentation/web-api/reference/#/operations/get-current-users-profile>
     ///
     /// Requires scope: user-read-private user-read-email
     ///
@@ -18,51 +22,112 @@ impl Spotify {
         return Ok(User::new(&response));
     }
 
-    /// Gets the user's top artists. A derivative of: <https://developer.spotify.com/documentation/web-api/reference/#/operations/get-users-top-artists-and-tracks>
+    /// Get information on current user without their email address: <https://developer.spotify.com/documentation/web-api/reference/#/operations/get-current-users-profile>
+    ///
+    /// Requires scope: user-read-private
+    ///
+    pub fn get_current_user_basic(&self) -> Result<User, SpotifyError> {
+        let url_extension = "me";
+
+        self.check_scope("user-read-private")?;
+
+        let response = self.spotify_request(url_extension, RequestMethod::Get)?; // make request
+
+        return Ok(User::new(&response)); // email is None without the user-read-email scope
+    }
+
+    /// Gets the Spotify ID of the current user. The id is only requested the first time (<https://developer.spotify.com/documentation/web-api/reference/#/operations/get-current-users-profile>), later calls return the cached id.
+    ///
+    /// Requires scope: none
+    ///
+    pub fn current_user_id(&self) -> Result<String, SpotifyError> {
+        if let Some(user_id) = self.cached_user_id() {
+            return Ok(user_id); // already fetched
+        }
+
+        let response = self.spotify_request("me", RequestMethod::Get)?; // make request
+
+        let user_id = match response["id"].as_str() {
+            Some(user_id) => user_id.to_string(),
+            None => {
+                return Err(SpotifyError::RequestError(String::from(
+                    "No user id in response",
+                )))
+            }
+        };
+        self.cache_user_id(user_id.clone()); // store for later calls
+
+        Ok(user_id)
+    }
+
+    /// Checks that the access token is valid and Spotify is reachable by requesting the current user's profile: <https://developer.spotify.com/documentation/web-api/reference/#/operations/get-current-users-profile>
+    /// Returns `SpotifyError::BadOrExpiredToken` if Spotify rejects the token and `SpotifyError::NetworkError` if Spotify couldn't be reached.
+    ///
+    /// Requires scope: none
+    ///
+    pub fn health_check(&self) -> Result<(), SpotifyError> {
+        self.spotify_request("me", RequestMethod::Get)?; // any successful response means the token works
+
+        Ok(())
+    }
+
+    /// Gets the user's top artists or tracks: <https://developer.spotify.com/documentation/web-api/reference/#/operations/get-users-top-artists-and-tracks>
     ///
     /// Requires scope: user-top-read
     ///
     /// # Arguments
-    /// * `time_range` - The time range over which to retrieve top artists: short, medium, long. Default: medium.
-    /// * `limit` - The number of artists to return. Default: 20. Minimum: 1. Maximum: 50.
-    /// * `offset` - The index of the first artist to return. Default: 0 (i.e., the first artist). Use with limit to get the next set of artists.
+    /// * `kind` - The type of top items to retrieve. The type parameter `T` should match it (`Artist` for `TopItemKind::Artists`, `Track` for `TopItemKind::Tracks`).
+    /// * `time_range` - The time range over which to retrieve top items: short, medium, long. Default: medium.
+    /// * `limit` - The number of items to return. Default: 20. Minimum: 1. Maximum: 50.
+    /// * `offset` - The index of the first item to return. Default: 0 (i.e., the first item). Use with limit to get the next set of items.
     ///
-    pub fn get_users_top_artists(
+    pub fn get_users_top<T: SpotifyObject + Debug>(
         &self,
+        kind: TopItemKind,
         time_range: Option<TimeRange>,
         limit: Option<i32>,
         offset: Option<i32>,
-    ) -> Result<SpotifyCollection<Artist>, SpotifyError> {
-        let mut url_extension = String::from("me/top/artists");
+    ) -> Result<SpotifyCollection<T>, SpotifyError> {
+        let mut url_extension = format!("me/top/{}", kind.as_str());
 
         self.check_scope("user-top-read")?;
 
-        // add query section to string if optional parameters supplied
-        if !time_range.is_none() || !limit.is_none() || !offset.is_none() {
-            url_extension.push_str("?");
-        }
-
         // add time range to string if supplied
-        match time_range {
-            Some(TimeRange::ShortTerm) => url_extension.push_str("time_range=short_term&"),
-            Some(TimeRange::MediumTerm) => url_extension.push_str("time_range=medium_term&"),
-            Some(TimeRange::LongTerm) => url_extension.push_str("time_range=long_term&"),
-            None => (),
+        if let Some(time_range) = time_range {
+            append_query_parameter(&mut url_extension, "time_range", time_range.as_str());
         }
 
         // add limit to string if supplied
         if let Some(limit) = limit {
-            url_extension.push_str(&format!("limit={}&", limit));
+            append_query_parameter(&mut url_extension, "limit", limit);
         }
 
         // add offset to string if supplied
         if let Some(offset) = offset {
-            url_extension.push_str(&format!("offset={}&", offset));
+            append_query_parameter(&mut url_extension, "offset", offset);
         }
 
         let response = self.spotify_request(&url_extension, RequestMethod::Get)?; // make request
 
-        return Ok(SpotifyCollection::<Artist>::new(&response));
+        return Ok(SpotifyCollection::<T>::new(&response));
+    }
+
+    /// Gets the user's top artists. A derivative of: <https://developer.spotify.com/documentation/web-api/reference/#/operations/get-users-top-artists-and-tracks>
+    ///
+    /// Requires scope: user-top-read
+    ///
+    /// # Arguments
+    /// * `time_range` - The time range over which to retrieve top artists: short, medium, long. Default: medium.
+    /// * `limit` - The number of artists to return. Default: 20. Minimum: 1. Maximum: 50.
+    /// * `offset` - The index of the first artist to return. Default: 0 (i.e., the first artist). Use with limit to get the next set of artists.
+    ///
+    pub fn get_users_top_artists(
+        &self,
+        time_range: Option<TimeRange>,
+        limit: Option<i32>,
+        offset: Option<i32>,
+    ) -> Result<SpotifyCollection<Artist>, SpotifyError> {
+        self.get_users_top::<Artist>(TopItemKind::Artists, time_range, limit, offset)
     }
 
     /// Gets the user's top tracks. A derivative of: <https://developer.spotify.com/documentation/web-api/reference/#/operations/get-users-top-artists-and-tracks>
@@ -80,36 +145,7 @@ impl Spotify {
         limit: Option<i32>,
         offset: Option<i32>,
     ) -> Result<SpotifyCollection<Track>, SpotifyError> {
-        let mut url_extension = String::from("me/top/tracks");
-
-        self.check_scope("user-top-read")?;
-
-        // add query section to string if optional parameters supplied
-        if !time_range.is_none() || !limit.is_none() || !offset.is_none() {
-            url_extension.push_str("?");
-        }
-
-        // add time range to string if supplied
-        match time_range {
-            Some(TimeRange::ShortTerm) => url_extension.push_str("time_range=short_term&"),
-            Some(TimeRange::MediumTerm) => url_extension.push_str("time_range=medium_term&"),
-            Some(TimeRange::LongTerm) => url_extension.push_str("time_range=long_term&"),
-            None => (),
-        }
-
-        // add limit to string if supplied
-        if let Some(limit) = limit {
-            url_extension.push_str(&format!("limit={}&", limit));
-        }
-
-        // add offset to string if supplied
-        if let Some(offset) = offset {
-            url_extension.push_str(&format!("offset={}&", offset));
-        }
-
-        let response = self.spotify_request(&url_extension, RequestMethod::Get)?; // make request
-
-        return Ok(SpotifyCollection::<Track>::new(&response));
+        self.get_users_top::<Track>(TopItemKind::Tracks, time_range, limit, offset)
     }
 
     /// Gets the public profile for a user: <https://developer.spotify.com/documentation/web-api/reference/#/operations/get-users-profile>
@@ -192,7 +228,7 @@ impl Spotify {
 
         // add limit to string if supplied
         if let Some(limit) = limit {
-            url_extension.push_str(&format!("&limit={}", limit));
+            append_query_parameter(&mut url_extension, "limit", limit);
         }
 
         let response = self.spotify_request(&url_extension, RequestMethod::Get)?; // make request
@@ -208,6 +244,10 @@ impl Spotify {
     /// * `artist_ids` - A vector of the artist Spotify IDs to follow.
     ///
     pub fn follow_artists(&self, artist_ids: Vec<&str>) -> Result<(), SpotifyError> {
+        if artist_ids.is_empty() {
+            return Ok(()); // nothing to request
+        }
+
         let url_extension = format!("me/following?type=artist&ids={}", artist_ids.join(","));
 
         self.check_scope("user-follow-modify")?;
@@ -237,6 +277,10 @@ impl Spotify {
     /// * `user_ids` - A vector of the user Spotify IDs to follow.
     ///
     pub fn follow_users(&self, user_ids: Vec<&str>) -> Result<(), SpotifyError> {
+        if user_ids.is_empty() {
+            return Ok(()); // nothing to request
+        }
+
         let url_extension = format!("me/following?type=user&ids={}", user_ids.join(","));
 
         self.check_scope("user-follow-modify")?;
@@ -266,6 +310,10 @@ impl Spotify {
     /// * `artist_ids` - A vector of the artist Spotify IDs to unfollow.
     ///
     pub fn unfollow_artists(&self, artist_ids: Vec<&str>) -> Result<(), SpotifyError> {
+        if artist_ids.is_empty() {
+            return Ok(()); // nothing to request
+        }
+
         let url_extension = format!("me/following?type=artist&ids={}", artist_ids.join(","));
 
         self.check_scope("user-follow-modify")?;
@@ -295,6 +343,10 @@ impl Spotify {
     /// * `user_ids` - A vector of the user Spotify IDs to unfollow.
     ///
     pub fn unfollow_users(&self, user_ids: Vec<&str>) -> Result<(), SpotifyError> {
+        if user_ids.is_empty() {
+            return Ok(()); // nothing to request
+        }
+
         let url_extension = format!("me/following?type=user&ids={}", user_ids.join(","));
 
         self.check_scope("user-follow-modify")?;
@@ -321,7 +373,7 @@ impl Spotify {
     /// Requires scope: user-follow-read
     ///
     /// # Arguments
-    /// * `artist_ids` - A vector of the artist Spotify IDs to check.
+    /// * `artist_ids` - A vector of the artist Spotify IDs to check. Checked in batches of 50.
     ///
     /// # Panics
     /// Panics if API returned value is not formatted as expected. Shouldn't happen.
@@ -330,19 +382,22 @@ impl Spotify {
         &self,
         artist_ids: Vec<&str>,
     ) -> Result<Vec<bool>, SpotifyError> {
-        let url_extension = format!(
-            "me/following/contains?type=artist&ids={}",
-            artist_ids.join(",")
-        );
-
         self.check_scope("user-follow-read")?;
 
-        let response = self.spotify_request(&url_extension, RequestMethod::Get)?; // make request
-
         let mut follows: Vec<bool> = Vec::new();
 
-        for artist in response.members() {
-            follows.push(artist.as_bool().unwrap());
+        // Spotify checks at most 50 ids per request and answers in the order of the ids, so checking batches in order keeps the overall order
+        for artist_ids_batch in artist_ids.chunks(50) {
+            let url_extension = format!(
+                "me/following/contains?type=artist&ids={}",
+                artist_ids_batch.join(",")
+            );
+
+            let response = self.spotify_request(&url_extension, RequestMethod::Get)?; // make request
+
+            for artist in response.members() {
+                follows.push(artist.as_bool().unwrap());
+            }
         }
 
         return Ok(follows);
@@ -353,7 +408,7 @@ impl Spotify {
     /// Requires scope: user-follow-read
     ///
     /// # Arguments
-    /// * `user_ids` - A vector of the user Spotify IDs to check.
+    /// * `user_ids` - A vector of the user Spotify IDs to check. Checked in batches of 50.
     ///
     /// # Panics
     /// Panics if API returned value is not formatted as expected. Shouldn't happen.
@@ -362,16 +417,22 @@ impl Spotify {
         &self,
         user_ids: Vec<&str>,
     ) -> Result<Vec<bool>, SpotifyError> {
-        let url_extension = format!("me/following/contains?type=user&ids={}", user_ids.join(","));
-
         self.check_scope("user-follow-read")?;
 
-        let response = self.spotify_request(&url_extension, RequestMethod::Get)?; // make request
-
         let mut follows: Vec<bool> = Vec::new();
 
-        for user in response.members() {
-            follows.push(user.as_bool().unwrap());
+        // Spotify checks at most 50 ids per request and answers in the order of the ids, so checking batches in order keeps the overall order
+        for user_ids_batch in user_ids.chunks(50) {
+            let url_extension = format!(
+                "me/following/contains?type=user&ids={}",
+                user_ids_batch.join(",")
+            );
+
+            let response = self.spotify_request(&url_extension, RequestMethod::Get)?; // make request
+
+            for user in response.members() {
+                follows.push(user.as_bool().unwrap());
+            }
         }
 
         return Ok(follows);
@@ -390,6 +451,13 @@ impl Spotify {
         playlist_id: &str,
         user_ids: Vec<&str>,
     ) -> Result<Vec<bool>, SpotifyError> {
+        if user_ids.len() > 5 {
+            return Err(SpotifyError::InvalidRequest(format!(
+                "At most 5 user ids can be checked at once, got {}",
+                user_ids.len()
+            ))); // Spotify rejects more than 5 user ids
+        }
+
         let url_extension = format!(
             "playlists/{}/followers/contains?ids={}",
             playlist_id,
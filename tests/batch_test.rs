@@ -0,0 +1,200 @@
+mod common;
+
+use common::{mock_spotify, MockServer};
+use spotifyrs::{Market, SpotifyError};
+
+/// Builds ids `id0`, `id1`, ... and the batch responses Spotify would send for them
+fn batches(count: usize, batch_size: usize, key: &str) -> (Vec<String>, Vec<String>) {
+    let ids: Vec<String> = (0..count).map(|i| format!("id{}", i)).collect();
+    let responses = ids
+        .chunks(batch_size)
+        .map(|batch| {
+            let items: Vec<String> = batch
+                .iter()
+                .map(|id| format!(r#"{{"id": "{}", "name": "{}"}}"#, id, id))
+                .collect();
+            format!(r#"{{"{}": [{}]}}"#, key, items.join(","))
+        })
+        .collect();
+    (ids, responses)
+}
+
+#[test]
+fn get_albums_keeps_order_across_batches() {
+    let (ids, responses) = batches(45, 20, "albums");
+    let server = MockServer::start(responses.iter().map(|body| (200, body.as_str())).collect());
+    let spotify = mock_spotify(&server);
+
+    let albums = spotify
+        .get_albums(ids.iter().map(|id| id.as_str()).collect(), None)
+        .unwrap();
+
+    let album_ids: Vec<String> = albums.iter().map(|album| album.id.clone()).collect();
+    assert_eq!(album_ids, ids);
+    assert_eq!(server.request_lines().len(), 3);
+}
+
+#[test]
+fn get_several_tracks_keeps_order_across_batches() {
+    let (ids, responses) = batches(51, 50, "tracks");
+    let server = MockServer::start(responses.iter().map(|body| (200, body.as_str())).collect());
+    let spotify = mock_spotify(&server);
+
+    let tracks = spotify
+        .get_several_tracks(ids.iter().map(|id| id.as_str()).collect(), None)
+        .unwrap();
+
+    let track_ids: Vec<String> = tracks.iter().map(|track| track.id.clone()).collect();
+    assert_eq!(track_ids, ids);
+    assert!(server.request_lines()[1].starts_with("GET /v1/tracks/?ids=id50 "));
+}
+
+#[test]
+fn get_several_tracks_reports_playability_in_market() {
+    let ids: Vec<String> = (0..60).map(|i| format!("id{}", i)).collect();
+    let responses: Vec<String> = ids
+        .chunks(50)
+        .map(|batch| {
+            let items: Vec<String> = batch
+                .iter()
+                .enumerate()
+                .map(|(i, id)| format!(r#"{{"id": "{}", "is_playable": {}}}"#, id, i % 2 == 0))
+                .collect();
+            format!(r#"{{"tracks": [{}]}}"#, items.join(","))
+        })
+        .collect();
+    let server = MockServer::start(responses.iter().map(|body| (200, body.as_str())).collect());
+    let spotify = mock_spotify(&server);
+
+    let tracks = spotify
+        .get_several_tracks(
+            ids.iter().map(|id| id.as_str()).collect(),
+            Some(Market::Country("CA".to_string())),
+        )
+        .unwrap();
+
+    let playable: Vec<Option<bool>> = tracks.iter().map(|track| track.is_playable).collect();
+    let expected: Vec<Option<bool>> = [(0..50), (0..10)]
+        .into_iter()
+        .flatten()
+        .map(|i| Some(i % 2 == 0))
+        .collect();
+    assert_eq!(playable, expected);
+    assert_eq!(
+        tracks.iter().filter(|track| track.is_playable()).count(),
+        30
+    );
+    for line in server.request_lines() {
+        assert!(line.contains("market=CA"));
+    }
+}
+
+#[test]
+fn get_tracks_audio_features_keeps_order_across_batches() {
+    let (ids, responses) = batches(150, 100, "audio_features");
+    let server = MockServer::start(responses.iter().map(|body| (200, body.as_str())).collect());
+    let spotify = mock_spotify(&server);
+
+    let features = spotify
+        .get_tracks_audio_features(ids.iter().map(|id| id.as_str()).collect())
+        .unwrap();
+
+    let feature_ids: Vec<String> = features.iter().map(|feature| feature.id.clone()).collect();
+    assert_eq!(feature_ids, ids);
+    assert_eq!(server.request_lines().len(), 2);
+}
+
+#[test]
+fn check_user_follows_artists_keeps_order_across_batches() {
+    let ids: Vec<String> = (0..60).map(|i| format!("id{}", i)).collect();
+    let expected: Vec<bool> = (0..60).map(|i| i % 3 == 0).collect();
+    let responses: Vec<String> = expected
+        .chunks(50)
+        .map(|batch| format!("{:?}", batch))
+        .collect();
+    let server = MockServer::start(responses.iter().map(|body| (200, body.as_str())).collect());
+    let spotify = mock_spotify(&server);
+
+    let follows = spotify
+        .check_user_follows_artists(ids.iter().map(|id| id.as_str()).collect())
+        .unwrap();
+
+    assert_eq!(follows, expected);
+    let request_lines = server.request_lines();
+    assert_eq!(request_lines.len(), 2);
+    assert!(request_lines[1].starts_with("GET /v1/me/following/contains?type=artist&ids=id50,"));
+}
+
+#[test]
+fn check_saved_tracks_keeps_order_across_batches() {
+    let ids: Vec<String> = (0..60).map(|i| format!("id{}", i)).collect();
+    let expected: Vec<bool> = (0..60).map(|i| i % 4 == 1).collect();
+    let responses: Vec<String> = expected
+        .chunks(50)
+        .map(|batch| format!("{:?}", batch))
+        .collect();
+    let server = MockServer::start(responses.iter().map(|body| (200, body.as_str())).collect());
+    let spotify = mock_spotify(&server);
+
+    let saved = spotify
+        .check_saved_tracks(ids.iter().map(|id| id.as_str()).collect())
+        .unwrap();
+
+    assert_eq!(saved, expected);
+    let request_lines = server.request_lines();
+    assert_eq!(request_lines.len(), 2);
+    assert!(request_lines[0].starts_with("GET /v1/me/tracks/contains?ids=id0,"));
+    assert!(request_lines[1].starts_with("GET /v1/me/tracks/contains?ids=id50,"));
+}
+
+#[test]
+fn check_users_follow_playlist_rejects_more_than_five_users() {
+    let server = MockServer::start(vec![]);
+    let spotify = mock_spotify(&server);
+
+    let result =
+        spotify.check_users_follow_playlist("playlist", vec!["a", "b", "c", "d", "e", "f"]);
+
+    assert!(matches!(result, Err(SpotifyError::InvalidRequest(_))));
+    assert!(server.request_lines().is_empty());
+}
+
+#[test]
+fn empty_id_lists_make_no_requests() {
+    let server = MockServer::start(vec![]);
+    let spotify = mock_spotify(&server);
+
+    assert!(spotify.get_several_tracks(vec![], None).unwrap().is_empty());
+    assert!(spotify.get_albums(vec![], None).unwrap().is_empty());
+    assert!(spotify.get_several_artists(vec![]).unwrap().is_empty());
+    assert!(spotify.get_several_shows(vec![], None).unwrap().is_empty());
+    assert!(spotify
+        .get_several_episodes(vec![], None)
+        .unwrap()
+        .is_empty());
+    assert!(spotify
+        .get_several_audiobooks(vec![], None)
+        .unwrap()
+        .is_empty());
+    assert!(spotify
+        .get_tracks_audio_features(vec![])
+        .unwrap()
+        .is_empty());
+    assert!(spotify.check_saved_tracks(vec![]).unwrap().is_empty());
+    assert!(spotify.check_saved_albums(vec![]).unwrap().is_empty());
+    assert!(spotify
+        .check_user_follows_artists(vec![])
+        .unwrap()
+        .is_empty());
+    assert!(spotify.check_user_follows_users(vec![]).unwrap().is_empty());
+    spotify.save_tracks(vec![]).unwrap();
+    spotify.remove_tracks(vec![]).unwrap();
+    spotify.save_albums(vec![]).unwrap();
+    spotify.remove_albums(vec![]).unwrap();
+    spotify.follow_artists(vec![]).unwrap();
+    spotify.unfollow_artists(vec![]).unwrap();
+    spotify.follow_users(vec![]).unwrap();
+    spotify.unfollow_users(vec![]).unwrap();
+
+    assert!(server.request_lines().is_empty());
+}
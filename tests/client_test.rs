@@ -0,0 +1,173 @@
+use chrono::{Duration, Utc};
+use spotifyrs::{Scope, Spotify, SpotifyError};
+
+#[test]
+fn from_tokens() {
+    let spotify = Spotify::from_tokens(
+        String::from("client_id"),
+        String::from("user-read-private user-top-read"),
+        String::from("access_token"),
+        String::from("refresh_token"),
+        Utc::now() + Duration::seconds(3600),
+    );
+
+    assert!(spotify.is_authenticated());
+    assert_eq!(spotify.access_token().unwrap(), "access_token"); // not expired, so no refresh
+    assert!(spotify.check_scope("user-top-read").is_ok());
+    assert!(spotify.check_scope("user-library-read").is_err());
+}
+
+#[test]
+fn ensure_fresh_token() {
+    let spotify = Spotify::from_tokens(
+        String::from("client_id"),
+        String::from("user-read-private"),
+        String::from("access_token"),
+        String::from("refresh_token"),
+        Utc::now() + Duration::seconds(3600),
+    );
+
+    spotify.ensure_fresh_token().unwrap(); // fresh, so no refresh is attempted
+    assert_eq!(spotify.access_token().unwrap(), "access_token");
+
+    assert!(matches!(
+        Spotify::new().ensure_fresh_token(),
+        Err(SpotifyError::NotAuthenticated)
+    ));
+}
+
+#[test]
+fn error_conversions() {
+    let boxed: Box<dyn std::error::Error> = Box::new(SpotifyError::NotAuthenticated);
+    assert_eq!(
+        boxed.to_string(),
+        "Not authenticated; call authenticate() first (or create the client with from_tokens)"
+    );
+    assert!(matches!(
+        SpotifyError::from(boxed),
+        SpotifyError::NotAuthenticated
+    ));
+
+    let io_error: Box<dyn std::error::Error> = Box::new(std::io::Error::new(
+        std::io::ErrorKind::Other,
+        "disk on fire",
+    ));
+    match SpotifyError::from(io_error) {
+        SpotifyError::GeneralError(message) => assert_eq!(message, "disk on fire"),
+        error => panic!("expected general error, got {:?}", error),
+    }
+}
+
+#[test]
+fn build_url() {
+    let spotify = Spotify::new();
+    assert_eq!(
+        spotify.build_url("tracks?ids=7ouMYWpwJ422jRcDASZB7P"),
+        "https://api.spotify.com/v1/tracks?ids=7ouMYWpwJ422jRcDASZB7P"
+    );
+
+    spotify.set_api_url("http://localhost:8080/v1/");
+    assert_eq!(
+        spotify.build_url("me/player"),
+        "http://localhost:8080/v1/me/player"
+    );
+}
+
+#[test]
+fn granted_scopes() {
+    let spotify = Spotify::from_tokens(
+        String::from("client_id"),
+        String::from("user-read-private  playlist-modify-public some-future-scope user-top-read"),
+        String::from("access_token"),
+        String::from("refresh_token"),
+        Utc::now() + Duration::seconds(3600),
+    );
+
+    let scopes = spotify.granted_scopes();
+    assert_eq!(
+        scopes,
+        vec![
+            Scope::UserReadPrivate,
+            Scope::PlaylistModifyPublic,
+            Scope::UserTopRead
+        ]
+    );
+    assert_eq!(
+        Scope::join(&scopes),
+        "user-read-private playlist-modify-public user-top-read"
+    );
+    assert_eq!(
+        Scope::try_from("user-library-read").unwrap(),
+        Scope::UserLibraryRead
+    );
+    assert!(Scope::try_from("some-future-scope").is_err());
+    assert!(Spotify::new().granted_scopes().is_empty());
+}
+
+#[test]
+fn unauthenticated_request_explains_itself() {
+    let spotify = Spotify::new();
+
+    match spotify.get_track("6JWc4iAiJ9FjyK0B59ABb4") {
+        Err(error) => assert!(error.to_string().contains("call authenticate() first")),
+        Ok(track) => panic!("expected not authenticated, got {:?}", track),
+    }
+}
+
+#[test]
+fn clear_auth() {
+    let spotify = Spotify::from_tokens(
+        String::from("client_id"),
+        String::from("user-read-private user-top-read"),
+        String::from("access_token"),
+        String::from("refresh_token"),
+        Utc::now() + Duration::seconds(3600),
+    );
+
+    spotify.clear_auth();
+
+    assert!(!spotify.is_authenticated());
+    assert!(spotify.expires_in().is_none());
+    assert!(spotify.granted_scopes().is_empty());
+    assert!(matches!(
+        spotify.access_token(),
+        Err(SpotifyError::NotAuthenticated)
+    ));
+}
+
+#[test]
+fn transport_error_conversions() {
+    let client = reqwest::blocking::Client::builder()
+        .timeout(std::time::Duration::from_millis(200))
+        .build()
+        .unwrap();
+
+    let silent = std::net::TcpListener::bind("127.0.0.1:0").unwrap(); // accepts connections but never responds
+    let error = client
+        .get(format!("http://{}/", silent.local_addr().unwrap()))
+        .send()
+        .unwrap_err();
+    assert!(matches!(
+        SpotifyError::from(error),
+        SpotifyError::Timeout(_)
+    ));
+
+    let closed_port = std::net::TcpListener::bind("127.0.0.1:0")
+        .unwrap()
+        .local_addr()
+        .unwrap(); // listener is dropped, so nothing accepts connections here
+    let error = client
+        .get(format!("http://{}/", closed_port))
+        .send()
+        .unwrap_err();
+    assert!(matches!(
+        SpotifyError::from(error),
+        SpotifyError::NetworkError(_)
+    ));
+
+    let error = client.get("not a url").send().unwrap_err();
+    assert!(matches!(
+        SpotifyError::from(error),
+        SpotifyError::InvalidRequest(_)
+    ));
+}
@@ -0,0 +1,169 @@
+use chrono::NaiveDate;
+use json::object;
+use spotifyrs::{PlayableItem, PlaylistTrack, SpotifyCollection, SpotifyObject, Track};
+
+/// The track of a playlist item, panicking if it's an episode
+fn track(item: &PlaylistTrack) -> &Track {
+    match &item.track {
+        PlayableItem::Track(track) => track,
+        PlayableItem::Episode(episode) => panic!("expected a track, got episode {}", episode.uri),
+    }
+}
+
+#[test]
+fn playlist_tracks_filter_added() {
+    let playlist_tracks_json = object! {
+        "href": "https://api.spotify.com/v1/playlists/40KoEtcGjMKLZJloarHBGw/tracks",
+        "items": [
+            {
+                "added_at": "2022-10-01T12:00:00Z",
+                "track": { "id": "a", "name": "Before", "type": "track" }
+            },
+            {
+                "added_at": "2022-10-10T12:00:00Z",
+                "track": { "id": "b", "name": "During", "type": "track" }
+            },
+            {
+                "added_at": "2022-10-12T08:30:00Z",
+                "track": { "id": "c", "name": "Also During", "type": "track" }
+            },
+            {
+                "added_at": "2022-10-20T12:00:00Z",
+                "track": { "id": "d", "name": "After", "type": "track" }
+            },
+            {
+                "added_at": null,
+                "track": { "id": "e", "name": "Unknown", "type": "track" }
+            }
+        ],
+        "limit": 100,
+        "offset": 0,
+        "total": 5,
+    };
+
+    let after = NaiveDate::from_ymd_opt(2022, 10, 8)
+        .unwrap()
+        .and_hms_opt(0, 0, 0)
+        .unwrap();
+    let before = NaiveDate::from_ymd_opt(2022, 10, 15)
+        .unwrap()
+        .and_hms_opt(0, 0, 0)
+        .unwrap();
+
+    let filtered = SpotifyCollection::<PlaylistTrack>::new(&playlist_tracks_json)
+        .filter_added(Some(after), Some(before));
+    let ids: Vec<&str> = filtered
+        .items
+        .iter()
+        .map(|item| track(item).id.as_str())
+        .collect();
+    assert_eq!(ids, vec!["b", "c"]);
+    assert_eq!(filtered.total, 5); // total still describes the whole playlist
+
+    let unfiltered =
+        SpotifyCollection::<PlaylistTrack>::new(&playlist_tracks_json).filter_added(None, None);
+    assert_eq!(unfiltered.items.len(), 5);
+
+    let only_after = SpotifyCollection::<PlaylistTrack>::new(&playlist_tracks_json)
+        .filter_added(Some(after), None);
+    assert_eq!(only_after.items.len(), 3);
+}
+
+#[test]
+fn collection_uris_and_ids() {
+    let playlist_tracks_json = object! {
+        "items": [
+            { "track": { "id": "6JWc4iAiJ9FjyK0B59ABb4", "uri": "spotify:track:6JWc4iAiJ9FjyK0B59ABb4" } },
+            { "track": { "id": "6ozxplTAjWO0BlUxN8ia0A", "uri": "spotify:track:6ozxplTAjWO0BlUxN8ia0A" } }
+        ],
+        "total": 2,
+    };
+    let playlist_tracks = SpotifyCollection::<PlaylistTrack>::new(&playlist_tracks_json);
+    assert_eq!(
+        playlist_tracks.uris(),
+        vec![
+            "spotify:track:6JWc4iAiJ9FjyK0B59ABb4",
+            "spotify:track:6ozxplTAjWO0BlUxN8ia0A"
+        ]
+    );
+
+    let tracks_json = object! {
+        "items": [
+            { "id": "6JWc4iAiJ9FjyK0B59ABb4", "uri": "spotify:track:6JWc4iAiJ9FjyK0B59ABb4" },
+            { "id": "6ozxplTAjWO0BlUxN8ia0A", "uri": "spotify:track:6ozxplTAjWO0BlUxN8ia0A" }
+        ],
+        "total": 2,
+    };
+    let tracks = SpotifyCollection::<Track>::new(&tracks_json);
+    assert_eq!(
+        tracks.ids(),
+        vec!["6JWc4iAiJ9FjyK0B59ABb4", "6ozxplTAjWO0BlUxN8ia0A"]
+    );
+}
+
+#[test]
+fn local_files_are_skipped_by_id_based_helpers() {
+    let playlist_tracks_json = object! {
+        "items": [
+            {
+                "is_local": true,
+                "track": {
+                    "id": null,
+                    "name": "Demo Take 3",
+                    "uri": "spotify:local:The+Band:Demos:Demo+Take+3:187",
+                    "is_local": true,
+                    "type": "track"
+                }
+            },
+            { "track": { "id": "6JWc4iAiJ9FjyK0B59ABb4", "uri": "spotify:track:6JWc4iAiJ9FjyK0B59ABb4" } }
+        ],
+        "total": 2,
+    };
+    let playlist_tracks = SpotifyCollection::<PlaylistTrack>::new(&playlist_tracks_json);
+
+    let local = &playlist_tracks.items[0];
+    assert!(local.is_local);
+    assert!(track(local).is_local);
+    assert_eq!(track(local).id, ""); // not the string "null"
+    assert_eq!(track(local).spotify_id(), None);
+    assert_eq!(local.track.spotify_uri(), None);
+    assert_eq!(
+        track(&playlist_tracks.items[1]).spotify_id(),
+        Some("6JWc4iAiJ9FjyK0B59ABb4")
+    );
+
+    assert_eq!(
+        playlist_tracks.uris(),
+        vec!["spotify:track:6JWc4iAiJ9FjyK0B59ABb4"]
+    );
+    let missing_id = Track::new(&object! { "is_local": true });
+    assert!(!track(local).same_recording(&missing_id)); // local files don't match on their missing ids
+}
+
+#[test]
+fn playlist_items_keep_episodes() {
+    let playlist_tracks_json = object! {
+        "items": [
+            { "track": { "id": "6JWc4iAiJ9FjyK0B59ABb4", "uri": "spotify:track:6JWc4iAiJ9FjyK0B59ABb4", "type": "track" } },
+            { "track": { "id": "512ojhOuo1ktJprKbVcKyQ", "uri": "spotify:episode:512ojhOuo1ktJprKbVcKyQ", "type": "episode" } }
+        ],
+        "total": 2,
+    };
+    let playlist_tracks = SpotifyCollection::<PlaylistTrack>::new(&playlist_tracks_json);
+
+    assert!(matches!(
+        playlist_tracks.items[0].track,
+        PlayableItem::Track(_)
+    ));
+    assert!(matches!(
+        &playlist_tracks.items[1].track,
+        PlayableItem::Episode(episode) if episode.id == "512ojhOuo1ktJprKbVcKyQ"
+    ));
+    assert_eq!(
+        playlist_tracks.uris(),
+        vec![
+            "spotify:track:6JWc4iAiJ9FjyK0B59ABb4",
+            "spotify:episode:512ojhOuo1ktJprKbVcKyQ"
+        ]
+    );
+}
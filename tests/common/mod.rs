@@ -0,0 +1,145 @@
+#![allow(dead_code)] // not every test file uses every helper
+
+use chrono::{Duration, Utc};
+use spotifyrs::Spotify;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::TcpListener;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration as StdDuration;
+
+/// Minimal HTTP server standing in for the Spotify API. Answers each connection with the next canned
+/// response and records the request line and body it received.
+pub struct MockServer {
+    pub url: String,                                 // base url to point the client at
+    pub requests: Arc<Mutex<Vec<(String, String)>>>, // (request line, body) of every request received
+    pub headers: Arc<Mutex<Vec<Vec<(String, String)>>>>, // (name, value) headers of every request received
+}
+
+impl MockServer {
+    /// Starts a server answering requests in order with the given (status, body) JSON responses
+    pub fn start(responses: Vec<(u16, &str)>) -> MockServer {
+        MockServer::start_with_content_type(
+            responses
+                .into_iter()
+                .map(|(status, body)| (status, "application/json", body))
+                .collect(),
+        )
+    }
+
+    /// Starts a server answering requests in order with the given (status, content type, body) responses
+    pub fn start_with_content_type(responses: Vec<(u16, &str, &str)>) -> MockServer {
+        let responses: Vec<(u16, String, String)> = responses
+            .into_iter()
+            .map(|(status, content_type, body)| {
+                (status, content_type.to_string(), body.to_string())
+            })
+            .collect();
+        let count = responses.len();
+        let mut responses = responses.into_iter();
+        MockServer::start_with_handler(count, move |_| responses.next().unwrap())
+    }
+
+    /// Starts a server answering `count` requests with the (status, content type, body) response the handler picks for the
+    /// request line. For requests sent concurrently, which may arrive in any order
+    pub fn start_with_handler<F>(count: usize, mut handler: F) -> MockServer
+    where
+        F: FnMut(&str) -> (u16, String, String) + Send + 'static,
+    {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap(); // let the os pick a free port
+        let url = format!("http://{}/v1", listener.local_addr().unwrap());
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let headers = Arc::new(Mutex::new(Vec::new()));
+
+        let recorded_requests = Arc::clone(&requests);
+        let recorded_headers = Arc::clone(&headers);
+        thread::spawn(move || {
+            for _ in 0..count {
+                let (mut stream, _) = match listener.accept() {
+                    Ok(connection) => connection,
+                    Err(_) => return,
+                };
+                let mut reader = BufReader::new(stream.try_clone().unwrap());
+
+                // read request line and headers
+                let mut request_line = String::new();
+                reader.read_line(&mut request_line).unwrap();
+                let mut content_length = 0;
+                let mut request_headers = Vec::new();
+                loop {
+                    let mut header = String::new();
+                    reader.read_line(&mut header).unwrap();
+                    if header.trim().is_empty() {
+                        break;
+                    }
+                    if let Some((name, value)) = header.split_once(':') {
+                        if name.eq_ignore_ascii_case("content-length") {
+                            content_length = value.trim().parse().unwrap();
+                        }
+                        request_headers.push((name.to_lowercase(), value.trim().to_string()));
+                    }
+                }
+                let mut request_body = vec![0; content_length];
+                reader.read_exact(&mut request_body).unwrap();
+                recorded_headers.lock().unwrap().push(request_headers);
+                recorded_requests.lock().unwrap().push((
+                    request_line.trim().to_string(),
+                    String::from_utf8(request_body).unwrap(),
+                ));
+
+                let (status, content_type, body) = handler(request_line.trim());
+                let response = format!(
+                    "HTTP/1.1 {} Mock\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    status,
+                    content_type,
+                    body.len(),
+                    body
+                );
+                stream.write_all(response.as_bytes()).unwrap();
+            }
+        });
+
+        MockServer {
+            url,
+            requests,
+            headers,
+        }
+    }
+
+    /// Request lines (i.e. `GET /v1/albums/id HTTP/1.1`) of every request received so far
+    pub fn request_lines(&self) -> Vec<String> {
+        self.requests
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(request_line, _)| request_line.clone())
+            .collect()
+    }
+
+    /// Value of a header (name case insensitive) of the request with the given index, if it was sent
+    pub fn header(&self, request: usize, name: &str) -> Option<String> {
+        self.headers.lock().unwrap()[request]
+            .iter()
+            .find(|(header_name, _)| header_name.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.clone())
+    }
+}
+
+/// Creates an authenticated client with every scope that sends its requests to the mock server
+pub fn mock_spotify(server: &MockServer) -> Spotify {
+    let spotify = Spotify::from_tokens(
+        String::from("client_id"),
+        String::from(
+            "user-read-private user-read-email user-library-read user-library-modify user-top-read \
+             user-read-playback-state user-modify-playback-state user-read-currently-playing \
+             user-read-recently-played user-follow-read user-follow-modify playlist-read-private playlist-read-collaborative \
+             playlist-modify-public playlist-modify-private ugc-image-upload",
+        ),
+        String::from("access_token"),
+        String::from("refresh_token"),
+        Utc::now() + Duration::seconds(3600),
+    );
+    spotify.set_api_url(&server.url);
+    spotify.set_retry_delay(StdDuration::ZERO); // retry right away, so tests don't sleep
+    spotify
+}
@@ -1,5 +1,9 @@
 use json::object;
-use spotifyrs::{Album, Artist, Playlist, SpotifyObject, Track};
+use spotifyrs::{
+    Album, Artist, Audiobook, CopyrightType, Episode, FeatureTrack, Market, PlayableItem, Playback,
+    Playlist, RestrictionReason, SpotifyContext, SpotifyObject, Track, User,
+};
+use std::collections::HashSet;
 
 // I am not sure what these songs are, or if they exist, but Copilot thought they did
 #[test]
@@ -28,6 +32,100 @@ fn album_formatting() {
     assert_eq!(Album::new(&album_json).name, "The Suburbs");
 }
 
+#[test]
+fn album_copyright_formatting() {
+    let album_json = object! {
+        "id": "6JWc4iAiJ9FjyK0B59ABb4",
+        "name": "The Suburbs",
+        "copyrights": [
+            {
+                "text": "2010 Arcade Fire",
+                "type": "C"
+            },
+            {
+                "text": "2010 Arcade Fire",
+                "type": "P"
+            }
+        ],
+    };
+
+    let album = Album::new(&album_json);
+    assert_eq!(album.copyrights.len(), 2);
+    assert_eq!(album.copyrights[0].text, "2010 Arcade Fire");
+    assert!(matches!(
+        album.copyrights[0].copyright_type,
+        CopyrightType::Copyright
+    ));
+    assert!(matches!(
+        album.copyrights[1].copyright_type,
+        CopyrightType::Performance
+    ));
+}
+
+#[test]
+fn album_tracks_by_disc() {
+    let album_json = object! {
+        "id": "2noRn2Aes5aoNVsU6iWThc",
+        "name": "Discovery",
+        "tracks": {
+            "items": [
+                { "id": "1", "disc_number": 1, "track_number": 1 },
+                { "id": "2", "disc_number": 1, "track_number": 2 },
+                { "id": "3", "disc_number": 2, "track_number": 1 },
+                { "id": "4", "disc_number": 2, "track_number": 2 }
+            ],
+            "total": 4
+        }
+    };
+
+    let album = Album::new(&album_json);
+    let discs = album.tracks_by_disc();
+    assert_eq!(discs.keys().copied().collect::<Vec<i32>>(), vec![1, 2]);
+    let disc_two: Vec<&str> = discs[&2].iter().map(|track| track.id.as_str()).collect();
+    assert_eq!(disc_two, vec!["3", "4"]);
+
+    let no_tracks = Album::new(&object! { "id": "2noRn2Aes5aoNVsU6iWThc" });
+    assert!(no_tracks.tracks_by_disc().is_empty());
+}
+
+#[test]
+fn image_size_helpers() {
+    let user_json = object! {
+        "id": "smedjan",
+        "images": [
+            { "url": "https://i.scdn.co/image/large", "height": 640, "width": 640 },
+            { "url": "https://i.scdn.co/image/medium", "height": 300, "width": 300 },
+            { "url": "https://i.scdn.co/image/small", "height": 64, "width": 64 }
+        ]
+    };
+
+    let user = User::new(&user_json);
+    assert_eq!(
+        user.image_closest_to(200).unwrap().url,
+        "https://i.scdn.co/image/medium"
+    );
+    assert_eq!(
+        user.image_closest_to(100).unwrap().url,
+        "https://i.scdn.co/image/small"
+    );
+    assert_eq!(
+        user.image_closest_to(1000).unwrap().url,
+        "https://i.scdn.co/image/large"
+    );
+    assert_eq!(
+        user.largest_image().unwrap().url,
+        "https://i.scdn.co/image/large"
+    );
+    assert_eq!(
+        user.smallest_image().unwrap().url,
+        "https://i.scdn.co/image/small"
+    );
+
+    let playlist = Playlist::new(&object! { "id": "3cEYpjA9oz9GiPac4AsH4n", "images": [] });
+    assert!(playlist.image_closest_to(300).is_none());
+    assert!(playlist.largest_image().is_none());
+}
+
 #[test]
 fn artist_formatting() {
     let artist_json = object! {
@@ -51,6 +149,30 @@ fn artist_formatting() {
     assert_eq!(Artist::new(&artist_json).name, "Arcade Fire");
 }
 
+#[test]
+fn followers_formatting() {
+    let artist = Artist::new(&object! {
+        "id": "0OdUWJ0sBjDrqHygGUXeCF",
+        "followers": { "href": null, "total": 1234 },
+    });
+    assert_eq!(artist.followers.total, 1234);
+    assert_eq!(artist.followers.href, None);
+    assert_eq!(artist.total_followers(), 1234);
+
+    let playlist = Playlist::new(&object! {
+        "id": "37i9dQZF1DXcBWIGoYBM5M",
+        "followers": { "href": "https://api.spotify.com/v1/playlists/37i9dQZF1DXcBWIGoYBM5M/followers", "total": 5 },
+    });
+    assert_eq!(
+        playlist.followers.href.as_deref(),
+        Some("https://api.spotify.com/v1/playlists/37i9dQZF1DXcBWIGoYBM5M/followers")
+    );
+    assert_eq!(playlist.total_followers(), 5);
+
+    let user = User::new(&object! { "id": "smedjan" });
+    assert_eq!(user.total_followers(), 0); // default to 0
+}
+
 #[test]
 fn track_formatting() {
     let track_json = object! {
@@ -119,6 +241,176 @@ fn track_formatting() {
 
     assert_eq!(Track::new(&track_json).name, "The Suburbs");
     assert_eq!(Track::new(&track_json).album.unwrap().name, "The Suburbs");
+    assert_eq!(Track::new(&track_json).is_playable, None);
+    assert!(Track::new(&track_json).is_playable());
+}
+
+#[test]
+fn long_durations_dont_overflow() {
+    let duration_ms: i64 = 3_000_000_000; // almost 35 days, more milliseconds than fit in an i32
+    let track_json = object! { "id": "11dFghVXANMlKmJXsNCbNl", "duration_ms": duration_ms };
+    assert_eq!(Track::new(&track_json).duration, duration_ms);
+
+    let features_json = object! { "id": "11dFghVXANMlKmJXsNCbNl", "duration_ms": duration_ms };
+    assert_eq!(FeatureTrack::new(&features_json).duration, duration_ms);
+
+    assert_eq!(
+        Track::new(&object! { "id": "11dFghVXANMlKmJXsNCbNl" }).duration,
+        0
+    ); // missing still defaults to 0
+}
+
+#[test]
+fn typed_available_markets() {
+    let markets_json = object! {
+        "id": "6JWc4iAiJ9FjyK0B59ABb4",
+        "available_markets": ["CA", "US", "XX", "ES"],
+    };
+
+    let expected = vec![
+        Market::try_from("CA").unwrap(),
+        Market::try_from("US").unwrap(),
+        Market::try_from("ES").unwrap(),
+    ];
+    assert_eq!(
+        Track::new(&markets_json).available_markets_typed(),
+        expected
+    );
+    assert_eq!(
+        Album::new(&markets_json).available_markets_typed(),
+        expected
+    );
+    assert_eq!(Track::new(&markets_json).available_markets.len(), 4); // raw codes are kept
+}
+
+#[test]
+fn restricted_track_formatting() {
+    let track_json = object! {
+        "id": "6JWc4iAiJ9FjyK0B59ABb4",
+        "name": "The Suburbs",
+        "is_playable": false,
+        "restrictions": {
+            "reason": "market"
+        },
+        "type": "track",
+    };
+
+    let track = Track::new(&track_json);
+    assert_eq!(track.is_playable, Some(false));
+    assert!(!track.is_playable());
+}
+
+#[test]
+fn unknown_restriction_reason() {
+    let restricted = object! {
+        "id": "6JWc4iAiJ9FjyK0B59ABb4",
+        "restrictions": {
+            "reason": "payment_required"
+        },
+    };
+
+    let track = Track::new(&restricted);
+    assert!(
+        matches!(&track.restriction_reason, RestrictionReason::Other(reason) if reason == "payment_required")
+    );
+    assert!(!track.is_playable());
+    assert!(matches!(
+        Album::new(&restricted).restriction_reason,
+        RestrictionReason::Other(_)
+    ));
+    assert!(matches!(
+        Track::new(&object! { "id": "6JWc4iAiJ9FjyK0B59ABb4" }).restriction_reason,
+        RestrictionReason::None
+    ));
+}
+
+#[test]
+fn unplayable_and_externally_hosted_episodes() {
+    let unplayable = Episode::new(&object! {
+        "id": "512ojhOuo1ktJprKbVcKyQ",
+        "name": "Restricted episode",
+        "is_externally_hosted": false,
+        "is_playable": false,
+        "restrictions": { "reason": "market" },
+    });
+    assert_eq!(unplayable.is_playable, Some(false));
+    assert!(matches!(
+        unplayable.restriction_reason,
+        RestrictionReason::Market
+    ));
+    assert!(!unplayable.is_playable());
+    assert!(!unplayable.is_externally_hosted);
+
+    let externally_hosted = Episode::new(&object! {
+        "id": "4GI3dxEafwap1sFiTGPKd1",
+        "name": "Hosted elsewhere",
+        "is_externally_hosted": true,
+        "is_playable": true,
+    });
+    assert!(externally_hosted.is_externally_hosted);
+    assert!(matches!(
+        externally_hosted.restriction_reason,
+        RestrictionReason::None
+    ));
+    assert!(externally_hosted.is_playable());
+}
+
+#[test]
+fn relinked_track_formatting() {
+    let track_json = object! {
+        "id": "6kLCHFM39wkFjOuyPGLGeQ",
+        "name": "Heaven and Hell",
+        "is_playable": true,
+        "linked_from": {
+            "external_urls": {
+                "spotify": "https://open.spotify.com/track/6ozxplTAjWO0BlUxN8ia0A"
+            },
+            "href": "https://api.spotify.com/v1/tracks/6ozxplTAjWO0BlUxN8ia0A",
+            "id": "6ozxplTAjWO0BlUxN8ia0A",
+            "type": "track",
+            "uri": "spotify:track:6ozxplTAjWO0BlUxN8ia0A"
+        },
+        "type": "track",
+        "uri": "spotify:track:6kLCHFM39wkFjOuyPGLGeQ",
+    };
+
+    let track = Track::new(&track_json);
+    let linked_from = track.linked_from.expect("linked_from should be captured");
+    assert_eq!(linked_from.id, "6ozxplTAjWO0BlUxN8ia0A");
+    assert_eq!(linked_from.uri, "spotify:track:6ozxplTAjWO0BlUxN8ia0A");
+    assert_eq!(linked_from.object_type, "track");
+    assert!(Track::new(&object! { "id": "6kLCHFM39wkFjOuyPGLGeQ" })
+        .linked_from
+        .is_none());
+}
+
+#[test]
+fn relinked_tracks_are_the_same_recording() {
+    let original = Track::new(&object! {
+        "id": "6ozxplTAjWO0BlUxN8ia0A",
+        "external_ids": { "isrc": "GBAYE0601498" },
+        "type": "track",
+    });
+    let relinked = Track::new(&object! {
+        "id": "6kLCHFM39wkFjOuyPGLGeQ",
+        "external_ids": { "isrc": "GBAYE0601498" },
+        "type": "track",
+    });
+    let other = Track::new(&object! {
+        "id": "6JWc4iAiJ9FjyK0B59ABb4",
+        "external_ids": { "isrc": "USWB11000689" },
+        "type": "track",
+    });
+    let without_isrc = Track::new(&object! {
+        "id": "6kLCHFM39wkFjOuyPGLGeQ",
+        "linked_from": { "id": "6ozxplTAjWO0BlUxN8ia0A", "type": "track" },
+        "type": "track",
+    });
+
+    assert!(original.same_recording(&relinked));
+    assert!(!original.same_recording(&other));
+    assert!(without_isrc.same_recording(&original));
+    assert!(!without_isrc.same_recording(&other));
 }
 
 #[test]
@@ -165,4 +457,299 @@ fn playlist_formatting() {
     };
 
     assert_eq!(Playlist::new(&playlist_json).name, "Arcade Fire");
+    assert_eq!(Playlist::new(&playlist_json).primary_color, None);
+    assert_eq!(
+        Playlist::new(&playlist_json).tracks_href,
+        Some(String::from(
+            "https://api.spotify.com/v1/playlists/37i9dQZF1DX0XUsuxWHRQd/tracks"
+        ))
+    );
+}
+
+#[test]
+fn playlist_primary_color() {
+    let playlist_json = object! {
+        "id": "37i9dQZF1DX0XUsuxWHRQd",
+        "name": "RapCaviar",
+        "primary_color": "#FFC864",
+        "type": "playlist",
+    };
+
+    let playlist = Playlist::new(&playlist_json);
+    assert_eq!(playlist.primary_color, Some(String::from("#FFC864")));
+    assert_eq!(playlist.tracks_href, None);
+}
+
+#[test]
+fn queue_formatting() {
+    let queue_json = object! {
+        "currently_playing": {
+            "type": "track",
+            "id": "6JWc4iAiJ9FjyK0B59ABb4",
+            "name": "The Suburbs",
+            "duration_ms": 238373,
+            "uri": "spotify:track:6JWc4iAiJ9FjyK0B59ABb4"
+        },
+        "queue": [
+            {
+                "type": "track",
+                "id": "6JWc4iAiJ9FjyK0B59ABb4",
+                "name": "The Suburbs",
+                "duration_ms": 238373,
+                "uri": "spotify:track:6JWc4iAiJ9FjyK0B59ABb4"
+            },
+            {
+                "type": "episode",
+                "id": "512ojhOuo1ktJprKbVcKyQ",
+                "name": "Episode 1",
+                "description": "The first episode",
+                "duration_ms": 1686230,
+                "languages": ["en"],
+                "release_date": "2020-05-01",
+                "release_date_precision": "day",
+                "uri": "spotify:episode:512ojhOuo1ktJprKbVcKyQ"
+            }
+        ]
+    };
+
+    match PlayableItem::new(&queue_json["currently_playing"]) {
+        PlayableItem::Track(track) => assert_eq!(track.name, "The Suburbs"),
+        PlayableItem::Episode(_) => panic!("expected currently playing item to be a track"),
+    }
+
+    let queue: Vec<PlayableItem> = queue_json["queue"]
+        .members()
+        .map(|item| PlayableItem::new(item))
+        .collect();
+
+    assert_eq!(queue.len(), 2);
+    assert!(matches!(queue[0], PlayableItem::Track(_)));
+    match &queue[1] {
+        PlayableItem::Episode(episode) => {
+            assert_eq!(episode.name, "Episode 1");
+            assert_eq!(episode.duration, 1686230);
+            assert!(episode.release_date.is_some());
+        }
+        PlayableItem::Track(_) => panic!("expected second queue item to be an episode"),
+    }
+}
+
+#[test]
+fn audiobook_formatting() {
+    let audiobook_json = object! {
+        "authors": [{ "name": "Frank Herbert" }],
+        "available_markets": ["US", "GB"],
+        "chapters": {
+            "items": [
+                {
+                    "chapter_number": 0,
+                    "duration_ms": 1000,
+                    "id": "0D5wENdkdwbqlrHoaJ9g29",
+                    "name": "Opening Credits",
+                    "release_date": "2019-11-18",
+                    "release_date_precision": "day",
+                    "type": "chapter"
+                }
+            ],
+            "total": 1
+        },
+        "id": "7iHfbu1YPACw6oZPAFJtqe",
+        "name": "Dune",
+        "narrators": [{ "name": "Scott Brick" }, { "name": "Euan Morton" }],
+        "publisher": "Frank Herbert",
+        "total_chapters": 1,
+        "type": "audiobook",
+    };
+
+    let audiobook = Audiobook::new(&audiobook_json);
+    assert_eq!(audiobook.name, "Dune");
+    assert_eq!(audiobook.authors, vec!["Frank Herbert"]);
+    assert_eq!(audiobook.narrators, vec!["Scott Brick", "Euan Morton"]);
+    let chapters = audiobook.chapters.expect("chapters should be included");
+    assert_eq!(chapters.items[0].name, "Opening Credits");
+    assert!(chapters.items[0].audiobook.is_none());
+}
+
+#[test]
+fn playback_progress_fraction() {
+    let playback = Playback::new(&object! {
+        "progress_ms": 60000,
+        "is_playing": true,
+        "item": { "id": "6JWc4iAiJ9FjyK0B59ABb4", "duration_ms": 240000, "type": "track" },
+    });
+    assert_eq!(playback.progress_fraction(), Some(0.25));
+
+    let past_end = Playback::new(&object! {
+        "progress_ms": 240500,
+        "item": { "id": "6JWc4iAiJ9FjyK0B59ABb4", "duration_ms": 240000, "type": "track" },
+    });
+    assert_eq!(past_end.progress_fraction(), Some(1.0));
+
+    let nothing_playing = Playback::new(&object! { "progress_ms": 1000 });
+    assert_eq!(nothing_playing.progress_fraction(), None);
+}
+
+#[test]
+fn playback_context_formatting() {
+    let playback = Playback::new(&object! {
+        "is_playing": true,
+        "context": {
+            "type": "playlist",
+            "href": "https://api.spotify.com/v1/playlists/37i9dQZF1DXcBWIGoYBM5M",
+            "uri": "spotify:playlist:37i9dQZF1DXcBWIGoYBM5M"
+        },
+        "item": { "id": "6JWc4iAiJ9FjyK0B59ABb4", "type": "track" },
+    });
+    match playback.context {
+        Some(SpotifyContext::Playlist(id)) => assert_eq!(id, "37i9dQZF1DXcBWIGoYBM5M"),
+        other => panic!("expected playlist context, got {:?}", other),
+    }
+
+    let no_context = Playback::new(&object! { "is_playing": true, "context": null });
+    assert!(no_context.context.is_none());
+}
+
+#[test]
+fn objects_deduplicate_by_id() {
+    let first = Track::new(&object! { "id": "6JWc4iAiJ9FjyK0B59ABb4", "name": "The Suburbs" });
+    let relisted =
+        Track::new(&object! { "id": "6JWc4iAiJ9FjyK0B59ABb4", "name": "The Suburbs - Remastered" });
+    let other = Track::new(&object! { "id": "7ouMYWpwJ422jRcDASZB7P", "name": "Ready to Start" });
+
+    assert_eq!(first, relisted); // equality only looks at the id
+
+    let tracks: HashSet<Track> = vec![first, relisted, other].into_iter().collect();
+    assert_eq!(tracks.len(), 2);
+
+    let demo = Track::new(&object! {
+        "id": null,
+        "uri": "spotify:local:The+Band:Demos:Demo+Take+3:187",
+        "is_local": true
+    });
+    let other_demo = Track::new(&object! {
+        "id": null,
+        "uri": "spotify:local:The+Band:Demos:Demo+Take+4:201",
+        "is_local": true
+    });
+    assert_ne!(demo, other_demo); // local files have no id, so their uris are compared
+
+    let local_tracks: HashSet<Track> = vec![demo, other_demo].into_iter().collect();
+    assert_eq!(local_tracks.len(), 2);
+
+    let artists: HashSet<Artist> = vec![
+        Artist::new(&object! { "id": "3kjuyTCjPG1WMFCiyc5IuB", "name": "Arcade Fire" }),
+        Artist::new(&object! { "id": "3kjuyTCjPG1WMFCiyc5IuB", "name": "Arcade Fire" }),
+    ]
+    .into_iter()
+    .collect();
+    assert_eq!(artists.len(), 1);
+}
+
+#[test]
+fn feature_averages() {
+    let features = vec![
+        FeatureTrack::new(&object! {
+            "id": "6JWc4iAiJ9FjyK0B59ABb4",
+            "danceability": 0.4,
+            "energy": 0.8,
+            "valence": 0.2,
+            "tempo": 100.0,
+            "duration_ms": 200000,
+        }),
+        FeatureTrack::new(&object! {}), // track without audio features
+        FeatureTrack::new(&object! {
+            "id": "7ouMYWpwJ422jRcDASZB7P",
+            "danceability": 0.6,
+            "energy": 0.4,
+            "valence": 0.6,
+            "tempo": 140.0,
+            "duration_ms": 300000,
+        }),
+    ];
+
+    let summary = FeatureTrack::average(&features);
+    assert_eq!(summary.track_count, 2);
+    assert!((summary.danceability - 0.5).abs() < 1e-9);
+    assert!((summary.energy - 0.6).abs() < 1e-9);
+    assert!((summary.valence - 0.4).abs() < 1e-9);
+    assert!((summary.tempo - 120.0).abs() < 1e-9);
+
+    assert_eq!(FeatureTrack::average(&[]).track_count, 0);
+}
+
+#[test]
+fn external_urls_formatting() {
+    let artist = Artist::new(&object! {
+        "id": "3kjuyTCjPG1WMFCiyc5IuB",
+        "name": "Arcade Fire",
+        "external_urls": {
+            "spotify": "https://open.spotify.com/artist/3kjuyTCjPG1WMFCiyc5IuB",
+            "website": "https://arcadefire.com"
+        },
+    });
+
+    assert_eq!(artist.external_urls.len(), 2);
+    assert_eq!(artist.external_urls["website"], "https://arcadefire.com");
+    assert_eq!(
+        artist.spotify_url(),
+        "https://open.spotify.com/artist/3kjuyTCjPG1WMFCiyc5IuB"
+    );
+
+    let track = Track::new(&object! { "id": "6JWc4iAiJ9FjyK0B59ABb4" });
+    assert!(track.external_urls.is_empty());
+    assert_eq!(track.spotify_url(), "");
+}
+
+#[test]
+fn web_urls() {
+    let track = Track::new(&object! {
+        "id": "6JWc4iAiJ9FjyK0B59ABb4",
+        "external_urls": { "spotify": "https://open.spotify.com/track/6JWc4iAiJ9FjyK0B59ABb4?si=shared" },
+    });
+    assert_eq!(
+        track.web_url(),
+        "https://open.spotify.com/track/6JWc4iAiJ9FjyK0B59ABb4?si=shared"
+    ); // returned url is kept as is
+
+    let track = Track::new(&object! { "id": "6JWc4iAiJ9FjyK0B59ABb4" });
+    assert_eq!(
+        track.web_url(),
+        "https://open.spotify.com/track/6JWc4iAiJ9FjyK0B59ABb4"
+    );
+
+    let album = Album::new(&object! { "id": "3DrgM6ccFqrpuJ5SA9YfB2" });
+    assert_eq!(
+        album.web_url(),
+        "https://open.spotify.com/album/3DrgM6ccFqrpuJ5SA9YfB2"
+    );
+
+    let artist = Artist::new(&object! { "id": "3kjuyTCjPG1WMFCiyc5IuB" });
+    assert_eq!(
+        artist.web_url(),
+        "https://open.spotify.com/artist/3kjuyTCjPG1WMFCiyc5IuB"
+    );
+
+    let playlist = Playlist::new(&object! { "id": "37i9dQZF1DXcBWIGoYBM5M" });
+    assert_eq!(
+        playlist.web_url(),
+        "https://open.spotify.com/playlist/37i9dQZF1DXcBWIGoYBM5M"
+    );
+}
+
+#[test]
+fn playlist_editability() {
+    let owned = Playlist::new(&object! {
+        "id": "3cEYpjA9oz9GiPac4AsH4n",
+        "collaborative": false,
+        "owner": { "id": "jmperezperez" },
+    });
+    assert!(owned.is_editable_by("jmperezperez")); // owner
+    assert!(!owned.is_editable_by("smedjan")); // read-only
+
+    let collaborative = Playlist::new(&object! {
+        "id": "3cEYpjA9oz9GiPac4AsH4n",
+        "collaborative": true,
+        "owner": { "id": "jmperezperez" },
+    });
+    assert!(collaborative.is_editable_by("smedjan")); // collaborator
 }
@@ -0,0 +1,75 @@
+mod common;
+
+use chrono::{Duration, Utc};
+use common::MockServer;
+use log::{Level, LevelFilter, Log, Metadata, Record};
+use spotifyrs::Spotify;
+use std::sync::Mutex;
+
+/// Logger that keeps every logged line so the test can inspect them
+struct CapturingLogger {
+    lines: Mutex<Vec<(Level, String)>>,
+}
+
+impl Log for CapturingLogger {
+    fn enabled(&self, _metadata: &Metadata) -> bool {
+        true
+    }
+
+    fn log(&self, record: &Record) {
+        self.lines
+            .lock()
+            .unwrap()
+            .push((record.level(), record.args().to_string()));
+    }
+
+    fn flush(&self) {}
+}
+
+static LOGGER: CapturingLogger = CapturingLogger {
+    lines: Mutex::new(Vec::new()),
+};
+
+#[test]
+fn requests_are_logged_without_credentials() {
+    log::set_logger(&LOGGER).unwrap();
+    log::set_max_level(LevelFilter::Trace);
+
+    let server = MockServer::start(vec![
+        (
+            200,
+            r#"{"access_token": "fresh_access_token", "expires_in": 3600}"#,
+        ),
+        (200, r#"{"id": "smedjan", "display_name": "smedjan"}"#),
+    ]);
+    let spotify = Spotify::from_tokens(
+        String::from("client_id"),
+        String::from("user-read-private user-read-email"),
+        String::from("stale_access_token"),
+        String::from("secret_refresh_token"),
+        Utc::now() - Duration::seconds(1), // expired, so the token endpoint is called first
+    );
+    spotify.set_accounts_url(server.url.trim_end_matches("/v1"));
+    spotify.set_api_url(&server.url);
+
+    spotify.get_current_users_profile().unwrap();
+
+    let lines = LOGGER.lines.lock().unwrap();
+    let request_line = lines
+        .iter()
+        .find(|(_, line)| line.starts_with("GET ") && line.contains("/v1/me"))
+        .expect("request wasn't logged");
+    assert_eq!(request_line.0, Level::Debug);
+    assert!(request_line.1.contains("-> 200 ("));
+    assert!(lines
+        .iter()
+        .any(|(_, line)| line.contains("refresh_token=REDACTED")));
+    for (_, line) in lines.iter() {
+        assert!(!line.contains("access_token"), "token logged: {}", line);
+        assert!(
+            !line.contains("secret_refresh_token"),
+            "token logged: {}",
+            line
+        );
+    }
+}
@@ -0,0 +1,129 @@
+mod common;
+
+use common::{mock_spotify, MockServer};
+use spotifyrs::{Genre, RecommendationParams, SpotifyError};
+
+#[test]
+fn genre_validation() {
+    assert_eq!(Genre::try_from("hip-hop").unwrap().as_str(), "hip-hop");
+    assert!(Genre::try_from("not-a-genre").is_err());
+    assert!(Genre::try_from("Hip-Hop").is_err()); // genre seeds are lowercase
+
+    let params = RecommendationParams::new().seed_genre(Genre::try_from("jazz").unwrap());
+    assert_eq!(params.seed_genres, vec!["jazz"]);
+}
+
+#[test]
+fn genre_seeds_are_cached() {
+    let server = MockServer::start(vec![(200, r#"{"genres": ["acoustic", "afrobeat"]}"#)]);
+    let spotify = mock_spotify(&server);
+
+    assert_eq!(spotify.genre_seeds().unwrap(), vec!["acoustic", "afrobeat"]);
+    assert_eq!(spotify.genre_seeds().unwrap(), vec!["acoustic", "afrobeat"]); // served from cache
+    assert_eq!(server.request_lines().len(), 1);
+}
+
+#[test]
+fn genre_is_validated_against_genre_seeds() {
+    let server = MockServer::start(vec![(200, r#"{"genres": ["acoustic", "new-genre"]}"#)]);
+    let spotify = mock_spotify(&server);
+
+    assert_eq!(spotify.genre("new-genre").unwrap().as_str(), "new-genre"); // not in the built in list
+    match spotify.genre("jazz") {
+        Err(SpotifyError::InvalidRequest(message)) => assert!(message.contains("jazz")),
+        other => panic!("expected invalid request, got {:?}", other),
+    }
+    assert_eq!(server.request_lines().len(), 1); // genre seeds fetched once
+}
+
+#[test]
+fn malformed_track_seed_is_rejected() {
+    let server = MockServer::start(vec![]);
+    let spotify = mock_spotify(&server);
+
+    match spotify.get_recommendations(
+        None,
+        None,
+        Some(vec!["spotify:track:6rqhFgbbKwnb9MLmUQDhG6"]),
+        None,
+    ) {
+        Err(SpotifyError::InvalidRequest(message)) => {
+            assert!(message.contains("spotify:track:6rqhFgbbKwnb9MLmUQDhG6"))
+        }
+        other => panic!("expected invalid request, got {:?}", other),
+    }
+    assert!(server.request_lines().is_empty()); // rejected before sending
+}
+
+#[test]
+fn unknown_genre_seed_is_rejected() {
+    let server = MockServer::start(vec![]);
+    let spotify = mock_spotify(&server);
+
+    match spotify.get_recommendations(None, Some(vec!["jazz", "not-a-genre"]), None, None) {
+        Err(SpotifyError::InvalidRequest(message)) => assert!(message.contains("not-a-genre")),
+        other => panic!("expected invalid request, got {:?}", other),
+    }
+    assert!(server.request_lines().is_empty());
+}
+
+#[test]
+fn unknown_genre_seeds_are_named_against_cached_list() {
+    let server = MockServer::start(vec![(200, r#"{"genres": ["acoustic", "jazz"]}"#)]);
+    let spotify = mock_spotify(&server);
+    spotify.genre_seeds().unwrap(); // fetch and cache the genre seeds
+
+    match spotify.get_recommendations(
+        None,
+        Some(vec!["jazz", "bogus-genre", "hip-hop"]),
+        None,
+        None,
+    ) {
+        Err(SpotifyError::InvalidRequest(message)) => {
+            assert_eq!(
+                message,
+                "bogus-genre, hip-hop are not available genre seeds"
+            ) // hip-hop isn't in the cached list
+        }
+        other => panic!("expected invalid request, got {:?}", other),
+    }
+    assert_eq!(server.request_lines().len(), 1); // only the genre seeds request
+}
+
+#[test]
+fn recommendations_include_seeds() {
+    let server = MockServer::start(vec![(
+        200,
+        r#"{
+            "seeds": [
+                {"afterFilteringSize": 250, "afterRelinkingSize": 250, "href": "https://api.spotify.com/v1/artists/4NHQUGzhtTLFvgF5SZesLK", "id": "4NHQUGzhtTLFvgF5SZesLK", "initialPoolSize": 250, "type": "ARTIST"},
+                {"afterFilteringSize": 180, "afterRelinkingSize": 179, "href": null, "id": "jazz", "initialPoolSize": 200, "type": "GENRE"}
+            ],
+            "tracks": [{"id": "6rqhFgbbKwnb9MLmUQDhG6", "name": "Speak To Me", "type": "track"}]
+        }"#,
+    )]);
+    let spotify = mock_spotify(&server);
+
+    let recommendations = spotify
+        .get_recommendations(
+            Some(vec!["4NHQUGzhtTLFvgF5SZesLK"]),
+            Some(vec!["jazz"]),
+            None,
+            None,
+        )
+        .unwrap();
+
+    assert_eq!(recommendations.tracks.len(), 1);
+    assert_eq!(recommendations.tracks[0].name, "Speak To Me");
+
+    let artist_seed = &recommendations.seeds[0];
+    assert_eq!(artist_seed.id, "4NHQUGzhtTLFvgF5SZesLK");
+    assert_eq!(artist_seed.seed_type, "artist");
+    assert_eq!(artist_seed.initial_pool_size, 250);
+    assert!(artist_seed.href.is_some());
+
+    let genre_seed = &recommendations.seeds[1];
+    assert_eq!(genre_seed.seed_type, "genre");
+    assert_eq!(genre_seed.after_relinking_size, 179);
+    assert_eq!(genre_seed.href, None);
+}
@@ -0,0 +1,1681 @@
+mod common;
+
+use chrono::{Duration, Utc};
+use common::{mock_spotify, MockServer};
+use spotifyrs::{
+    Market, PlayableItem, PlaybackOffset, RequestMethod, ResolvedItem, SnapshotId, Spotify,
+    SpotifyBuilder, SpotifyContext, SpotifyError,
+};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration as StdDuration, Instant};
+
+const ALBUM_JSON: &str = r#"{"id": "6JWc4iAiJ9FjyK0B59ABb4", "name": "The Suburbs", "album_type": "album", "release_date": "2010-08-02", "release_date_precision": "day"}"#;
+
+#[test]
+fn retries_on_server_error() {
+    let server = MockServer::start(vec![
+        (
+            503,
+            r#"{"error": {"status": 503, "message": "Service unavailable"}}"#,
+        ),
+        (503, "Service unavailable"), // non-json body, as sent by some gateways
+        (200, ALBUM_JSON),
+    ]);
+    let spotify = mock_spotify(&server);
+
+    let album = spotify.get_album("6JWc4iAiJ9FjyK0B59ABb4", None).unwrap();
+
+    assert_eq!(album.name, "The Suburbs");
+    assert_eq!(server.request_lines().len(), 3); // first request and two retries
+    assert!(server.request_lines()[2].starts_with("GET /v1/albums/6JWc4iAiJ9FjyK0B59ABb4 "));
+}
+
+#[test]
+fn gives_up_after_max_retries() {
+    let server = MockServer::start(vec![(502, "Bad gateway"), (502, "Bad gateway")]);
+    let spotify = mock_spotify(&server);
+    spotify.set_max_retries(1);
+
+    match spotify.get_album("6JWc4iAiJ9FjyK0B59ABb4", None) {
+        Err(SpotifyError::RequestError(message)) => assert!(message.contains("502")),
+        result => panic!("expected request error, got {:?}", result),
+    }
+    assert_eq!(server.request_lines().len(), 2);
+}
+
+#[test]
+fn writes_are_not_retried() {
+    let server = MockServer::start(vec![
+        (502, "Bad gateway"),
+        (200, r#"{"snapshot_id": "abc"}"#),
+    ]);
+    let spotify = mock_spotify(&server);
+
+    match spotify.add_tracks_to_playlist(
+        "3cEYpjA9oz9GiPac4AsH4n",
+        vec!["6JWc4iAiJ9FjyK0B59ABb4"],
+        None,
+    ) {
+        Err(SpotifyError::RequestError(message)) => assert!(message.contains("502")),
+        result => panic!("expected request error, got {:?}", result),
+    }
+    assert_eq!(server.request_lines().len(), 1); // the tracks may already have been added
+}
+
+#[test]
+fn unavailable_audiobook_is_not_found() {
+    let server = MockServer::start(vec![(
+        404,
+        r#"{"error": {"status": 404, "message": "Non existing id"}}"#,
+    )]);
+    let spotify = mock_spotify(&server);
+
+    match spotify.get_audiobook(
+        "7iHfbu1YPACw6oZPAFJtqe",
+        Some(Market::try_from("SE").unwrap()),
+    ) {
+        Err(SpotifyError::NotFound(message)) => assert_eq!(message, "Non existing id"),
+        result => panic!("expected not found error, got {:?}", result),
+    }
+    assert!(server.request_lines()[0]
+        .starts_with("GET /v1/audiobooks/7iHfbu1YPACw6oZPAFJtqe?market=SE "));
+}
+
+#[test]
+fn market_from_token() {
+    let server = MockServer::start(vec![(200, ALBUM_JSON)]);
+    let spotify = mock_spotify(&server);
+
+    spotify
+        .get_album("6JWc4iAiJ9FjyK0B59ABb4", Some(Market::FromToken))
+        .unwrap();
+
+    assert!(server.request_lines()[0]
+        .starts_with("GET /v1/albums/6JWc4iAiJ9FjyK0B59ABb4?market=from_token "));
+}
+
+#[test]
+fn market_parsing() {
+    assert_eq!(
+        Market::try_from("us").unwrap(),
+        Market::Country(String::from("US"))
+    );
+    assert_eq!(Market::try_from("US").unwrap().code(), "US");
+    assert_eq!(Market::try_from("from_token").unwrap(), Market::FromToken);
+    assert_eq!(Market::FromToken.code(), "from_token");
+    assert!(Market::try_from("ZZ").is_err());
+}
+
+#[test]
+fn several_shows_market_defaults_to_user_market() {
+    let shows_json = r#"{"shows": [{"id": "5CfCWKI5pZ28U0uOzXkDHe", "name": "Show"}, null]}"#;
+
+    // user token: default to the user's market
+    let server = MockServer::start(vec![(200, shows_json)]);
+    let spotify = mock_spotify(&server);
+    let shows = spotify
+        .get_several_shows(
+            vec!["5CfCWKI5pZ28U0uOzXkDHe", "5as3aKmN2k11yfDDDSrvaZ"],
+            None,
+        )
+        .unwrap();
+    assert_eq!(shows[0].as_ref().unwrap().name, "Show");
+    assert!(shows[1].is_none());
+    assert!(server.request_lines()[0].contains("&market=from_token "));
+
+    // explicit market opts out of the default
+    let server = MockServer::start(vec![(200, shows_json)]);
+    let spotify = mock_spotify(&server);
+    spotify
+        .get_several_shows(
+            vec!["5CfCWKI5pZ28U0uOzXkDHe"],
+            Some(Market::try_from("SE").unwrap()),
+        )
+        .unwrap();
+    assert!(server.request_lines()[0].contains("&market=SE "));
+}
+
+#[test]
+fn list_playlists_summary_pages_through_everything() {
+    let server = MockServer::start(vec![
+        (
+            200,
+            r#"{"items": [{"id": "a", "name": "First", "tracks": {"total": 12}}, {"id": "b", "name": "Second", "tracks": {"total": 3}}], "next": "https://api.spotify.com/v1/me/playlists?offset=2&limit=2", "total": 3}"#,
+        ),
+        (
+            200,
+            r#"{"items": [{"id": "c", "name": "Third", "tracks": {"total": 0}}], "next": null, "total": 3}"#,
+        ),
+    ]);
+    let spotify = mock_spotify(&server);
+
+    let playlists = spotify.list_playlists_summary().unwrap();
+
+    let summaries: Vec<(&str, &str, i32)> = playlists
+        .iter()
+        .map(|playlist| {
+            (
+                playlist.id.as_str(),
+                playlist.name.as_str(),
+                playlist.track_count,
+            )
+        })
+        .collect();
+    assert_eq!(
+        summaries,
+        vec![("a", "First", 12), ("b", "Second", 3), ("c", "Third", 0)]
+    );
+    assert!(server.request_lines()[1].starts_with("GET /v1/me/playlists?limit=50&offset=2 "));
+}
+
+#[test]
+fn get_all_album_tracks_follows_next() {
+    let server = MockServer::start(vec![
+        (
+            200,
+            r#"{"items": [{"id": "1", "name": "One"}, {"id": "2", "name": "Two"}], "next": "https://api.spotify.com/v1/albums/x/tracks?offset=2&limit=2", "total": 3}"#,
+        ),
+        (
+            200,
+            r#"{"items": [{"id": "3", "name": "Three"}], "next": null, "total": 3}"#,
+        ),
+    ]);
+    let spotify = mock_spotify(&server);
+
+    let tracks = spotify
+        .get_all_album_tracks("6JWc4iAiJ9FjyK0B59ABb4", None)
+        .unwrap();
+
+    let ids: Vec<&str> = tracks.iter().map(|track| track.id.as_str()).collect();
+    assert_eq!(ids, vec!["1", "2", "3"]);
+    assert!(tracks.iter().all(|track| track.album.is_none())); // simplified tracks
+    assert!(server.request_lines()[1]
+        .starts_with("GET /v1/albums/6JWc4iAiJ9FjyK0B59ABb4/tracks?limit=50&offset=2 "));
+}
+
+#[test]
+fn album_tracks_are_simplified() {
+    let server = MockServer::start(vec![(
+        200,
+        r#"{"items": [{"id": "1", "name": "One", "disc_number": 1, "duration_ms": 1000, "type": "track"}], "next": null, "total": 1}"#,
+    )]);
+    let spotify = mock_spotify(&server);
+
+    let tracks = spotify
+        .get_album_tracks("6JWc4iAiJ9FjyK0B59ABb4", None, None, None)
+        .unwrap();
+
+    let track = &tracks.items[0];
+    assert!(track.album.is_none());
+    assert_eq!(track.popularity, None);
+    assert_eq!(track.external_ids.isrc, None);
+    assert_eq!(track.duration, 1000);
+}
+
+#[test]
+fn create_playlist_for_me_uses_cached_user_id() {
+    let playlist_json = r#"{"id": "3cEYpjA9oz9GiPac4AsH4n", "name": "New Playlist"}"#;
+    let server = MockServer::start(vec![
+        (200, r#"{"id": "smedjan", "display_name": "Smedjan"}"#),
+        (201, playlist_json),
+        (201, playlist_json),
+    ]);
+    let spotify = mock_spotify(&server);
+
+    let playlist = spotify
+        .create_playlist_for_me("New Playlist", Some(false), None, None)
+        .unwrap();
+    spotify
+        .create_playlist_for_me("New Playlist", None, None, None)
+        .unwrap();
+
+    assert_eq!(playlist.name, "New Playlist");
+    assert_eq!(spotify.current_user_id().unwrap(), "smedjan"); // cached, no extra request
+    let request_lines = server.request_lines();
+    assert_eq!(request_lines.len(), 3); // profile fetched once
+    assert!(request_lines[0].starts_with("GET /v1/me "));
+    assert!(request_lines[1].starts_with("POST /v1/users/smedjan/playlists "));
+    assert!(request_lines[2].starts_with("POST /v1/users/smedjan/playlists "));
+}
+
+#[test]
+fn create_playlist_with_tracks_adds_in_batches() {
+    let playlist_json = r#"{"id": "3cEYpjA9oz9GiPac4AsH4n", "name": "New Playlist"}"#;
+    let snapshot_json = r#"{"snapshot_id": "abc"}"#;
+    let bad_request = r#"{"error": {"status": 400, "message": "Invalid track uri"}}"#;
+    let server = MockServer::start(vec![
+        (200, r#"{"id": "smedjan"}"#),
+        (201, playlist_json),
+        (201, snapshot_json),
+        (201, snapshot_json),
+        (
+            200,
+            r#"{"id": "3cEYpjA9oz9GiPac4AsH4n", "name": "New Playlist", "tracks": {"total": 150}}"#,
+        ),
+        (201, playlist_json),
+        (201, snapshot_json),
+        (400, bad_request),
+        (200, ""),
+    ]);
+    let spotify = mock_spotify(&server);
+    let uris: Vec<String> = (0..150)
+        .map(|i| format!("spotify:track:{:022}", i))
+        .collect();
+    let uris: Vec<&str> = uris.iter().map(|uri| uri.as_str()).collect();
+
+    let playlist = spotify
+        .create_playlist_with_tracks("New Playlist", uris.clone(), None, None)
+        .unwrap();
+    assert_eq!(playlist.tracks.unwrap().total, 150);
+
+    {
+        let requests = server.requests.lock().unwrap();
+        let first_batch: serde_json::Value = serde_json::from_str(&requests[2].1).unwrap();
+        let second_batch: serde_json::Value = serde_json::from_str(&requests[3].1).unwrap();
+        assert_eq!(first_batch["uris"].as_array().unwrap().len(), 100);
+        assert_eq!(second_batch["uris"][0], uris[100]);
+    }
+    let request_lines = server.request_lines();
+    assert!(request_lines[2].starts_with("POST /v1/playlists/3cEYpjA9oz9GiPac4AsH4n/tracks "));
+    assert!(request_lines[4].starts_with("GET /v1/playlists/3cEYpjA9oz9GiPac4AsH4n?"));
+
+    // a failing batch deletes the playlist again
+    match spotify.create_playlist_with_tracks("New Playlist", uris, None, None) {
+        Err(SpotifyError::MalformedRequest(message)) => assert_eq!(message, "Invalid track uri"),
+        other => panic!("expected malformed request, got {:?}", other),
+    }
+    assert!(server.request_lines()[8]
+        .starts_with("DELETE /v1/playlists/3cEYpjA9oz9GiPac4AsH4n/followers "));
+}
+
+#[test]
+fn featured_playlists_message() {
+    let server = MockServer::start(vec![(
+        200,
+        r#"{"message": "Monday morning vibes", "playlists": {"items": [{"id": "37i9dQZF1DXcBWIGoYBM5M", "name": "Today's Top Hits"}], "total": 1}}"#,
+    )]);
+    let spotify = mock_spotify(&server);
+
+    let (message, playlists) = spotify
+        .get_featured_playlists(None, None, None, None, None)
+        .unwrap();
+
+    assert_eq!(message, "Monday morning vibes");
+    assert_eq!(playlists.items[0].name, "Today's Top Hits");
+}
+
+#[test]
+fn current_user_basic_only_needs_private_scope() {
+    let server = MockServer::start(vec![(
+        200,
+        r#"{"id": "smedjan", "display_name": "Smedjan", "country": "SE", "product": "premium"}"#,
+    )]);
+    let spotify = Spotify::from_tokens(
+        String::from("client_id"),
+        String::from("user-read-private"),
+        String::from("access_token"),
+        String::from("refresh_token"),
+        Utc::now() + Duration::seconds(3600),
+    );
+    spotify.set_api_url(&server.url);
+
+    match spotify.get_current_users_profile() {
+        Err(SpotifyError::InsufficientScope(scope)) => assert_eq!(scope, "user-read-email"),
+        other => panic!("expected insufficient scope, got {:?}", other),
+    }
+
+    let user = spotify.get_current_user_basic().unwrap();
+    assert_eq!(user.id, "smedjan");
+    assert_eq!(user.country, Some(String::from("SE")));
+    assert_eq!(user.email, None);
+}
+
+#[test]
+fn user_market_is_fetched_once() {
+    let server = MockServer::start(vec![(
+        200,
+        r#"{"id": "smedjan", "display_name": "Smedjan", "country": "se"}"#,
+    )]);
+    let spotify = mock_spotify(&server);
+
+    assert_eq!(
+        spotify.user_market().unwrap(),
+        Some(Market::Country(String::from("SE")))
+    );
+    assert_eq!(
+        spotify.user_market().unwrap(),
+        Some(Market::try_from("SE").unwrap())
+    );
+    assert_eq!(spotify.current_user_id().unwrap(), "smedjan");
+    assert_eq!(server.request_lines().len(), 1);
+}
+
+#[test]
+fn move_playlist_track_by_uri() {
+    let tracks_json = r#"{"items": [
+        {"track": {"uri": "spotify:track:6JWc4iAiJ9FjyK0B59ABb4", "type": "track"}},
+        {"track": {"uri": "spotify:track:7ouMYWpwJ422jRcDASZB7P", "type": "track"}},
+        {"track": {"uri": "spotify:track:4VqPOruhp5EdPBeR92t6lQ", "type": "track"}}
+    ], "next": null, "offset": 0, "total": 3}"#;
+    let server = MockServer::start(vec![
+        (200, tracks_json),
+        (200, r#"{"snapshot_id": "abc"}"#),
+        (200, tracks_json),
+    ]);
+    let spotify = mock_spotify(&server);
+
+    let snapshot_id = spotify
+        .move_playlist_track(
+            "3cEYpjA9oz9GiPac4AsH4n",
+            "spotify:track:6JWc4iAiJ9FjyK0B59ABb4",
+            2,
+        )
+        .unwrap();
+    assert_eq!(snapshot_id.as_str(), "abc");
+
+    let body: serde_json::Value =
+        serde_json::from_str(&server.requests.lock().unwrap()[1].1).unwrap();
+    assert_eq!(body["range_start"], 0);
+    assert_eq!(body["insert_before"], 3); // after the last track
+
+    match spotify.move_playlist_track(
+        "3cEYpjA9oz9GiPac4AsH4n",
+        "spotify:track:0000000000000000000000",
+        0,
+    ) {
+        Err(SpotifyError::NotFound(_)) => (),
+        other => panic!("expected not found, got {:?}", other),
+    }
+}
+
+#[test]
+fn move_playlist_track_counts_local_files() {
+    let server = MockServer::start(vec![
+        (
+            200,
+            r#"{"items": [
+                {"is_local": true, "track": {"id": null, "uri": "spotify:local:The+Band:Demos:Demo+Take+3:187", "type": "track", "is_local": true}},
+                {"track": {"uri": "spotify:track:6JWc4iAiJ9FjyK0B59ABb4", "type": "track"}},
+                {"track": {"uri": "spotify:track:7ouMYWpwJ422jRcDASZB7P", "type": "track"}}
+            ], "next": null, "offset": 0, "total": 3}"#,
+        ),
+        (200, r#"{"snapshot_id": "abc"}"#),
+    ]);
+    let spotify = mock_spotify(&server);
+
+    spotify
+        .move_playlist_track(
+            "3cEYpjA9oz9GiPac4AsH4n",
+            "spotify:track:6JWc4iAiJ9FjyK0B59ABb4",
+            0,
+        )
+        .unwrap();
+
+    let body: serde_json::Value =
+        serde_json::from_str(&server.requests.lock().unwrap()[1].1).unwrap();
+    assert_eq!(body["range_start"], 1); // the local file before it still counts
+    assert_eq!(body["insert_before"], 0);
+}
+
+#[test]
+fn token_refresher_refreshes_before_expiry() {
+    let server = MockServer::start(vec![(
+        200,
+        r#"{"access_token": "new_access_token", "expires_in": 3600}"#,
+    )]);
+    let spotify = Arc::new(Spotify::from_tokens(
+        String::from("client_id"),
+        String::from("user-read-private"),
+        String::from("access_token"),
+        String::from("refresh_token"),
+        Utc::now() + Duration::seconds(5), // close enough to expiry to be refreshed right away
+    ));
+    spotify.set_accounts_url(server.url.trim_end_matches("/v1"));
+
+    let refresher = spotify.spawn_token_refresher();
+
+    let start = Instant::now();
+    while spotify.expires_in().unwrap() < Duration::seconds(60) {
+        assert!(
+            start.elapsed() < StdDuration::from_secs(5),
+            "refresher never fired"
+        );
+        thread::sleep(StdDuration::from_millis(50));
+    }
+    assert_eq!(spotify.access_token().unwrap(), "new_access_token");
+    assert!(server.request_lines()[0].starts_with("POST /api/token?"));
+
+    drop(spotify);
+    refresher.join().unwrap(); // stops once the client is dropped
+}
+
+#[test]
+fn token_refresher_stops_when_refresh_token_is_revoked() {
+    let server = MockServer::start(vec![(
+        400,
+        r#"{"error": "invalid_grant", "error_description": "Refresh token revoked"}"#,
+    )]);
+    let spotify = Arc::new(Spotify::from_tokens(
+        String::from("client_id"),
+        String::from("user-read-private"),
+        String::from("access_token"),
+        String::from("refresh_token"),
+        Utc::now() + Duration::seconds(5),
+    ));
+    spotify.set_accounts_url(server.url.trim_end_matches("/v1"));
+
+    let refresher = spotify.spawn_token_refresher();
+
+    let start = Instant::now();
+    while !refresher.is_finished() {
+        assert!(
+            start.elapsed() < StdDuration::from_secs(5),
+            "refresher kept running"
+        );
+        thread::sleep(StdDuration::from_millis(50));
+    }
+    assert_eq!(server.request_lines().len(), 1); // stopped while the client is still alive
+}
+
+#[test]
+fn ensure_fresh_token_refreshes_before_expiry() {
+    let server = MockServer::start(vec![(
+        200,
+        r#"{"access_token": "new_access_token", "expires_in": 3600}"#,
+    )]);
+    let spotify = Spotify::from_tokens(
+        String::from("client_id"),
+        String::from("user-read-private"),
+        String::from("access_token"),
+        String::from("refresh_token"),
+        Utc::now() + Duration::seconds(30), // not expired yet, but within a minute of it
+    );
+    spotify.set_accounts_url(server.url.trim_end_matches("/v1"));
+
+    spotify.ensure_fresh_token().unwrap();
+
+    assert!(spotify.expires_in().unwrap() > Duration::seconds(60));
+    assert_eq!(spotify.access_token().unwrap(), "new_access_token");
+    assert_eq!(server.request_lines().len(), 1);
+    assert!(server.request_lines()[0].starts_with("POST /api/token?"));
+}
+
+#[test]
+fn refresh_updates_granted_scope() {
+    let server = MockServer::start(vec![(
+        200,
+        r#"{"access_token": "new_access_token", "expires_in": 3600, "scope": "user-read-private"}"#,
+    )]);
+    let spotify = Spotify::from_tokens(
+        String::from("client_id"),
+        String::from("user-read-private playlist-modify-public"),
+        String::from("access_token"),
+        String::from("refresh_token"),
+        Utc::now() - Duration::seconds(1), // expired, so the next use refreshes
+    );
+    spotify.set_accounts_url(server.url.trim_end_matches("/v1"));
+    assert!(spotify.check_scope("playlist-modify-public").is_ok());
+
+    assert_eq!(spotify.access_token().unwrap(), "new_access_token");
+
+    assert!(spotify.check_scope("user-read-private").is_ok());
+    match spotify.check_scope("playlist-modify-public") {
+        Err(SpotifyError::InsufficientScope(scope)) => assert_eq!(scope, "playlist-modify-public"),
+        other => panic!("expected insufficient scope, got {:?}", other),
+    }
+}
+
+#[test]
+fn playlist_cover_url() {
+    let server = MockServer::start(vec![
+        (
+            200,
+            r#"[{"url": "https://mosaic.scdn.co/300/cover", "height": 300, "width": 300}, {"url": "https://mosaic.scdn.co/640/cover", "height": 640, "width": 640}]"#,
+        ),
+        (200, "[]"),
+    ]);
+    let spotify = mock_spotify(&server);
+
+    assert_eq!(
+        spotify
+            .get_playlist_cover_url("3cEYpjA9oz9GiPac4AsH4n")
+            .unwrap(),
+        Some(String::from("https://mosaic.scdn.co/640/cover"))
+    );
+    assert_eq!(
+        spotify
+            .get_playlist_cover_url("3cEYpjA9oz9GiPac4AsH4n")
+            .unwrap(),
+        None
+    ); // no custom cover
+    assert!(
+        server.request_lines()[0].starts_with("GET /v1/playlists/3cEYpjA9oz9GiPac4AsH4n/images ")
+    );
+}
+
+#[test]
+fn single_field_playlist_edits() {
+    let server = MockServer::start(vec![(200, ""), (200, ""), (200, "")]);
+    let spotify = mock_spotify(&server);
+
+    spotify
+        .set_playlist_public("3cEYpjA9oz9GiPac4AsH4n", false)
+        .unwrap();
+    spotify
+        .rename_playlist("3cEYpjA9oz9GiPac4AsH4n", "Road Trip")
+        .unwrap();
+    spotify
+        .set_playlist_description("3cEYpjA9oz9GiPac4AsH4n", "Songs for the car")
+        .unwrap();
+
+    let bodies: Vec<serde_json::Value> = server
+        .requests
+        .lock()
+        .unwrap()
+        .iter()
+        .map(|(_, body)| serde_json::from_str(body).unwrap())
+        .collect();
+    assert_eq!(bodies[0], serde_json::json!({"public": false}));
+    assert_eq!(bodies[1], serde_json::json!({"name": "Road Trip"}));
+    assert_eq!(
+        bodies[2],
+        serde_json::json!({"description": "Songs for the car"})
+    );
+}
+
+#[test]
+fn volume_guard_rejects_unsupported_device() {
+    let devices_json = r#"{"devices": [
+        {"id": "cast", "is_active": true, "is_restricted": false, "name": "Living Room", "type": "CastAudio", "volume_percent": null},
+        {"id": "laptop", "is_active": false, "is_restricted": false, "name": "Laptop", "type": "Computer", "volume_percent": 40}
+    ]}"#;
+    let server = MockServer::start(vec![
+        (200, devices_json),
+        (200, devices_json),
+        (200, devices_json),
+        (204, ""),
+    ]);
+    let spotify = mock_spotify(&server);
+
+    let devices = spotify.get_available_devices().unwrap();
+    assert!(!devices[0].supports_volume());
+    assert!(devices[1].supports_volume());
+
+    match spotify.set_playback_volume_checked(50, None) {
+        Err(SpotifyError::InvalidRequest(_)) => (), // active device is the cast target
+        other => panic!("expected invalid request, got {:?}", other),
+    }
+
+    spotify
+        .set_playback_volume_checked(50, Some("laptop"))
+        .unwrap();
+    let request_lines = server.request_lines();
+    assert_eq!(request_lines.len(), 4); // no volume request for the cast target
+    assert!(request_lines[3]
+        .starts_with("PUT /v1/me/player/volume?volume_percent=50&device_id=laptop "));
+}
+
+#[test]
+fn arbitrary_request() {
+    let server = MockServer::start(vec![
+        (200, r#"{"devices": [{"id": "laptop", "name": "Laptop"}]}"#),
+        (204, ""),
+    ]);
+    let spotify = mock_spotify(&server);
+
+    let response = spotify
+        .request(RequestMethod::Get, "me/player/devices")
+        .unwrap();
+    assert_eq!(response["devices"][0]["name"], "Laptop");
+
+    let mut body = HashMap::new();
+    body.insert(String::from("device_ids"), serde_json::json!(["laptop"]));
+    let response = spotify
+        .request(RequestMethod::Put(body), "me/player")
+        .unwrap();
+    assert!(response.is_null());
+
+    let requests = server.requests.lock().unwrap();
+    assert!(requests[1].0.starts_with("PUT /v1/me/player "));
+    assert_eq!(requests[1].1, r#"{"device_ids":["laptop"]}"#);
+}
+
+#[test]
+fn collaborative_playlists_must_be_private() {
+    let playlist_json = r#"{"id": "3cEYpjA9oz9GiPac4AsH4n", "name": "Shared", "collaborative": true, "public": false}"#;
+    let server = MockServer::start(vec![(201, playlist_json), (200, "")]);
+    let spotify = mock_spotify(&server);
+
+    for result in [
+        spotify
+            .create_playlist("smedjan", "Shared", Some(true), Some(true), None)
+            .map(|_| ()),
+        spotify
+            .create_playlist("smedjan", "Shared", None, Some(true), None)
+            .map(|_| ()), // public by default
+        spotify.change_playlist_details(
+            "3cEYpjA9oz9GiPac4AsH4n",
+            None,
+            Some(true),
+            Some(true),
+            None,
+        ),
+    ] {
+        match result {
+            Err(SpotifyError::InvalidRequest(message)) => {
+                assert_eq!(message, "collaborative playlists must be private")
+            }
+            other => panic!("expected invalid request, got {:?}", other),
+        }
+    }
+    assert!(server.request_lines().is_empty()); // rejected before sending
+
+    let playlist = spotify
+        .create_playlist("smedjan", "Shared", Some(false), Some(true), None)
+        .unwrap();
+    assert!(playlist.collaborative);
+    spotify
+        .change_playlist_details(
+            "3cEYpjA9oz9GiPac4AsH4n",
+            None,
+            Some(false),
+            Some(true),
+            None,
+        )
+        .unwrap();
+    assert_eq!(server.request_lines().len(), 2);
+}
+
+#[test]
+fn toggle_playback_follows_state() {
+    let playing_json =
+        r#"{"device": {"id": "laptop", "is_active": true, "name": "Laptop"}, "is_playing": true}"#;
+    let paused_json =
+        r#"{"device": {"id": "laptop", "is_active": true, "name": "Laptop"}, "is_playing": false}"#;
+    let server = MockServer::start(vec![
+        (200, playing_json),
+        (204, ""),
+        (200, paused_json),
+        (204, ""),
+        (204, ""), // no active playback
+    ]);
+    let spotify = mock_spotify(&server);
+
+    spotify.toggle_playback(None).unwrap();
+    spotify.toggle_playback(Some("laptop")).unwrap();
+    match spotify.toggle_playback(None) {
+        Err(SpotifyError::InvalidRequest(_)) => (),
+        other => panic!("expected invalid request, got {:?}", other),
+    }
+
+    let request_lines = server.request_lines();
+    assert_eq!(request_lines.len(), 5);
+    assert!(request_lines[1].starts_with("PUT /v1/me/player/pause "));
+    assert!(request_lines[3].starts_with("PUT /v1/me/player/play?device_id=laptop "));
+}
+
+#[test]
+fn positions_accept_durations() {
+    let server = MockServer::start(vec![(204, ""), (204, "")]);
+    let spotify = mock_spotify(&server);
+
+    spotify.seek_position(Duration::seconds(30), None).unwrap();
+    spotify
+        .start_resume_playback(None, None, None, None, Some(Duration::seconds(30)))
+        .unwrap();
+    match spotify.seek_position(Duration::seconds(-1), None) {
+        Err(SpotifyError::InvalidRequest(_)) => (),
+        other => panic!("expected invalid request, got {:?}", other),
+    }
+
+    let requests = server.requests.lock().unwrap();
+    assert_eq!(requests.len(), 2); // negative position never sent
+    assert!(requests[0]
+        .0
+        .starts_with("PUT /v1/me/player/seek?position_ms=30000 "));
+    assert_eq!(requests[1].1, r#"{"position_ms":30000}"#);
+}
+
+#[test]
+fn owned_and_followed_playlists() {
+    let playlists_json = r#"{"items": [
+        {"id": "mine", "name": "Mine", "owner": {"id": "smedjan"}},
+        {"id": "theirs", "name": "Theirs", "owner": {"id": "spotify"}}
+    ], "next": null, "offset": 0, "total": 2}"#;
+    let server = MockServer::start(vec![
+        (200, r#"{"id": "smedjan"}"#),
+        (200, playlists_json),
+        (200, playlists_json),
+    ]);
+    let spotify = mock_spotify(&server);
+
+    let owned = spotify.get_owned_playlists().unwrap();
+    assert_eq!(owned.len(), 1);
+    assert_eq!(owned[0].id, "mine");
+
+    let followed = spotify.get_followed_playlists().unwrap(); // user id is cached
+    assert_eq!(followed.len(), 1);
+    assert_eq!(followed[0].id, "theirs");
+    assert_eq!(server.request_lines().len(), 3);
+}
+
+#[test]
+fn stale_snapshot_id_is_reported() {
+    let server = MockServer::start(vec![
+        (
+            400,
+            r#"{"error": {"status": 400, "message": "Invalid snapshot id"}}"#,
+        ),
+        (
+            500,
+            r#"{"error": {"status": 500, "message": "Failed to store snapshot"}}"#,
+        ),
+    ]);
+    let spotify = mock_spotify(&server);
+
+    match spotify.reorder_playlist_tracks("3cEYpjA9oz9GiPac4AsH4n", 0, 3, None, Some("stale")) {
+        Err(SpotifyError::SnapshotMismatch(message)) => assert_eq!(message, "Invalid snapshot id"),
+        other => panic!("expected snapshot mismatch, got {:?}", other),
+    }
+
+    match spotify.reorder_playlist_tracks("3cEYpjA9oz9GiPac4AsH4n", 0, 3, None, Some("current")) {
+        Err(SpotifyError::RequestError(message)) => assert!(message.contains("500")), // only a 400 is a mismatch
+        other => panic!("expected request error, got {:?}", other),
+    }
+}
+
+#[test]
+fn user_agent_header() {
+    let server = MockServer::start(vec![(200, r#"{"genres": []}"#), (200, r#"{"genres": []}"#)]);
+    let spotify = mock_spotify(&server);
+
+    spotify
+        .request(RequestMethod::Get, "recommendations/available-genre-seeds")
+        .unwrap();
+    spotify.set_user_agent("my-app/1.0").unwrap();
+    match spotify.set_user_agent("my-app/1.0\r\nX-Injected: 1") {
+        Err(SpotifyError::InvalidRequest(_)) => (),
+        other => panic!("expected invalid request, got {:?}", other),
+    }
+    spotify
+        .request(RequestMethod::Get, "recommendations/available-genre-seeds")
+        .unwrap();
+
+    let default_user_agent = server.header(0, "user-agent").unwrap();
+    assert!(default_user_agent.starts_with("spotify.rs/"));
+    assert_eq!(
+        server.header(1, "User-Agent"),
+        Some(String::from("my-app/1.0"))
+    ); // the invalid User-Agent was rejected, the previous one is kept
+
+    assert!(SpotifyBuilder::new()
+        .user_agent("my-app/1.0\n")
+        .build()
+        .is_err());
+}
+
+#[test]
+fn browse_category_falls_back_to_default_locale() {
+    let missing = r#"{"error": {"status": 404, "message": "Not found."}}"#;
+    let invalid_locale = r#"{"error": {"status": 400, "message": "Invalid locale"}}"#;
+    let category = r#"{"id": "dinner", "name": "Dinner", "href": "https://api.spotify.com/v1/browse/categories/dinner"}"#;
+    let server = MockServer::start(vec![
+        (404, missing),
+        (200, category),
+        (404, missing),
+        (400, invalid_locale),
+        (200, category),
+    ]);
+    let spotify = mock_spotify(&server);
+
+    let category = spotify
+        .get_single_browse_category("dinner", None, Some("xx_XX"), true)
+        .unwrap();
+    assert_eq!(category.name, "Dinner");
+
+    let request_lines = server.request_lines();
+    assert!(request_lines[0].starts_with("GET /v1/browse/categories/dinner?locale=xx_XX "));
+    assert!(request_lines[1].starts_with("GET /v1/browse/categories/dinner "));
+
+    match spotify.get_single_browse_category("dinner", None, Some("xx_XX"), false) {
+        Err(SpotifyError::NotFound(_)) => (),
+        other => panic!("expected not found, got {:?}", other),
+    }
+    assert_eq!(server.request_lines().len(), 3);
+
+    let category = spotify
+        .get_single_browse_category("dinner", None, Some("xx"), true)
+        .unwrap(); // a 400 for the locale falls back as well
+    assert_eq!(category.name, "Dinner");
+
+    let request_lines = server.request_lines();
+    assert!(request_lines[3].starts_with("GET /v1/browse/categories/dinner?locale=xx "));
+    assert!(request_lines[4].starts_with("GET /v1/browse/categories/dinner "));
+}
+
+#[test]
+fn playlist_tracks_carry_playlist_total() {
+    let server = MockServer::start(vec![(
+        200,
+        r#"{"items": [
+            {"added_at": "2020-01-01T00:00:00Z", "track": {"id": "6JWc4iAiJ9FjyK0B59ABb4", "type": "track"}},
+            {"added_at": "2023-01-01T00:00:00Z", "track": {"id": "7ouMYWpwJ422jRcDASZB7P", "type": "track"}}
+        ], "limit": 2, "next": "https://api.spotify.com/v1/playlists/3cEYpjA9oz9GiPac4AsH4n/tracks?offset=2&limit=2", "offset": 0, "total": 57}"#,
+    )]);
+    let spotify = mock_spotify(&server);
+
+    let added_after = chrono::NaiveDate::from_ymd_opt(2022, 1, 1)
+        .unwrap()
+        .and_hms_opt(0, 0, 0)
+        .unwrap();
+    let page = spotify
+        .get_playlist_tracks("3cEYpjA9oz9GiPac4AsH4n", None, Some(2), None, None)
+        .unwrap();
+    assert_eq!(page.total, 57);
+
+    let filtered = page.tracks.filter_added(Some(added_after), None);
+    assert_eq!(filtered.total, 57); // still the size of the whole playlist
+    assert_eq!(filtered.items.len(), 1); // filtered client side
+}
+
+#[test]
+fn player_getters_default_to_user_market() {
+    let playback =
+        r#"{"is_playing": true, "item": {"id": "6JWc4iAiJ9FjyK0B59ABb4", "type": "track"}}"#;
+    let server = MockServer::start(vec![(200, playback), (200, playback), (200, playback)]);
+    let spotify = mock_spotify(&server);
+
+    spotify.get_playback_state(None, None).unwrap();
+    spotify.get_currently_playing_track(None, None).unwrap();
+    spotify
+        .get_playback_state(Some(Market::try_from("SE").unwrap()), None)
+        .unwrap();
+
+    let request_lines = server.request_lines();
+    assert!(request_lines[0]
+        .starts_with("GET /v1/me/player?additional_types=track,episode&market=from_token "));
+    assert!(request_lines[1].starts_with(
+        "GET /v1/me/player/currently-playing?additional_types=track,episode&market=from_token "
+    ));
+    assert!(
+        request_lines[2].starts_with("GET /v1/me/player?additional_types=track,episode&market=SE ")
+    );
+}
+
+#[test]
+fn playback_of_episode() {
+    let server = MockServer::start(vec![(
+        200,
+        r#"{"is_playing": true, "progress_ms": 60000, "item": {"id": "512ojhOuo1ktJprKbVcKyQ", "duration_ms": 240000, "type": "episode"}}"#,
+    )]);
+    let spotify = mock_spotify(&server);
+
+    let playback = spotify.get_playback_state(None, None).unwrap();
+
+    assert!(matches!(
+        playback.track,
+        Some(PlayableItem::Episode(ref episode)) if episode.id == "512ojhOuo1ktJprKbVcKyQ"
+    ));
+    assert_eq!(playback.progress_fraction(), Some(0.25));
+}
+
+#[test]
+fn queue_without_currently_playing() {
+    let server = MockServer::start(vec![
+        (200, r#"{"currently_playing": null, "queue": []}"#),
+        (
+            200,
+            r#"{"currently_playing": {"id": "6JWc4iAiJ9FjyK0B59ABb4", "name": "The Suburbs", "type": "track"}, "queue": [{"id": "512ojhOuo1ktJprKbVcKyQ", "type": "episode"}]}"#,
+        ),
+    ]);
+    let spotify = mock_spotify(&server);
+
+    let (currently_playing, queue) = spotify.get_users_queue().unwrap();
+    assert!(currently_playing.is_none());
+    assert!(queue.is_empty());
+
+    let (currently_playing, queue) = spotify.get_users_queue().unwrap();
+    assert!(matches!(currently_playing, Some(PlayableItem::Track(_))));
+    assert!(matches!(queue[0], PlayableItem::Episode(_)));
+}
+
+#[test]
+fn html_error_page_is_reported() {
+    let server = MockServer::start_with_content_type(vec![(
+        502,
+        "text/html",
+        "<html><head><title>502 Bad Gateway</title></head><body>nginx</body></html>",
+    )]);
+    let spotify = mock_spotify(&server);
+    spotify.set_max_retries(0);
+
+    match spotify.get_track("6JWc4iAiJ9FjyK0B59ABb4") {
+        Err(SpotifyError::RequestError(message)) => {
+            assert!(message.starts_with("Error code: 502, unexpected text/html response: "));
+            assert!(message.contains("502 Bad Gateway"));
+        }
+        other => panic!("expected request error, got {:?}", other),
+    }
+}
+
+#[test]
+fn get_album_ex_fetches_all_tracks() {
+    let server = MockServer::start(vec![
+        (
+            200,
+            r#"{"id": "6JWc4iAiJ9FjyK0B59ABb4", "name": "The Suburbs", "album_type": "album", "release_date": "2010-08-02", "release_date_precision": "day", "total_tracks": 3, "tracks": {"items": [{"id": "1", "name": "One"}, {"id": "2", "name": "Two"}], "limit": 2, "offset": 0, "next": "https://api.spotify.com/v1/albums/6JWc4iAiJ9FjyK0B59ABb4/tracks?offset=2&limit=2", "total": 3}}"#,
+        ),
+        (
+            200,
+            r#"{"items": [{"id": "3", "name": "Three"}], "limit": 50, "offset": 2, "next": null, "total": 3}"#,
+        ),
+    ]);
+    let spotify = mock_spotify(&server);
+
+    let album = spotify
+        .get_album_ex("6JWc4iAiJ9FjyK0B59ABb4", None, true)
+        .unwrap();
+
+    let tracks = album.tracks.unwrap();
+    let ids: Vec<&str> = tracks.items.iter().map(|track| track.id.as_str()).collect();
+    assert_eq!(ids, vec!["1", "2", "3"]);
+    assert_eq!(tracks.next, None);
+    assert_eq!(tracks.total, 3);
+    assert!(server.request_lines()[1]
+        .starts_with("GET /v1/albums/6JWc4iAiJ9FjyK0B59ABb4/tracks?limit=50&offset=2 "));
+}
+
+#[test]
+fn get_album_tracks_by_disc_pages_and_groups() {
+    let server = MockServer::start(vec![
+        (
+            200,
+            r#"{"id": "2noRn2Aes5aoNVsU6iWThc", "name": "Discovery", "album_type": "album", "total_tracks": 3, "tracks": {"items": [{"id": "1", "disc_number": 1, "track_number": 1}, {"id": "2", "disc_number": 1, "track_number": 2}], "limit": 2, "offset": 0, "next": "https://api.spotify.com/v1/albums/2noRn2Aes5aoNVsU6iWThc/tracks?offset=2&limit=2", "total": 3}}"#,
+        ),
+        (
+            200,
+            r#"{"items": [{"id": "3", "disc_number": 2, "track_number": 1}], "limit": 50, "offset": 2, "next": null, "total": 3}"#,
+        ),
+    ]);
+    let spotify = mock_spotify(&server);
+
+    let discs = spotify
+        .get_album_tracks_by_disc("2noRn2Aes5aoNVsU6iWThc", None)
+        .unwrap();
+
+    let ids: Vec<(i32, Vec<&str>)> = discs
+        .iter()
+        .map(|(disc, tracks)| {
+            (
+                *disc,
+                tracks.iter().map(|track| track.id.as_str()).collect(),
+            )
+        })
+        .collect();
+    assert_eq!(ids, vec![(1, vec!["1", "2"]), (2, vec!["3"])]);
+    assert_eq!(server.request_lines().len(), 2);
+}
+
+#[test]
+fn get_album_ex_can_drop_tracks() {
+    let server = MockServer::start(vec![(
+        200,
+        r#"{"id": "6JWc4iAiJ9FjyK0B59ABb4", "name": "The Suburbs", "album_type": "album", "release_date": "2010-08-02", "release_date_precision": "day", "tracks": {"items": [{"id": "1", "name": "One"}], "next": "https://api.spotify.com/v1/albums/6JWc4iAiJ9FjyK0B59ABb4/tracks?offset=1&limit=1", "total": 2}}"#,
+    )]);
+    let spotify = mock_spotify(&server);
+
+    let album = spotify
+        .get_album_ex("6JWc4iAiJ9FjyK0B59ABb4", None, false)
+        .unwrap();
+
+    assert!(album.tracks.is_none());
+    assert_eq!(server.request_lines().len(), 1); // no paging requests
+}
+
+#[test]
+fn health_check_reports_token_and_network_problems() {
+    let server = MockServer::start(vec![
+        (200, r#"{"id": "user", "display_name": "User"}"#),
+        (
+            401,
+            r#"{"error": {"status": 401, "message": "The access token expired"}}"#,
+        ),
+    ]);
+    let spotify = mock_spotify(&server);
+
+    assert!(spotify.health_check().is_ok());
+    assert!(matches!(
+        spotify.health_check(),
+        Err(SpotifyError::BadOrExpiredToken(_))
+    ));
+    assert!(server.request_lines()[0].starts_with("GET /v1/me "));
+
+    let closed_port = std::net::TcpListener::bind("127.0.0.1:0")
+        .unwrap()
+        .local_addr()
+        .unwrap(); // listener is dropped, so nothing accepts connections here
+    spotify.set_api_url(&format!("http://{}/v1", closed_port));
+    assert!(matches!(
+        spotify.health_check(),
+        Err(SpotifyError::NetworkError(_))
+    ));
+}
+
+#[test]
+fn premium_required_is_reported_distinctly() {
+    let server = MockServer::start(vec![
+        (
+            403,
+            r#"{"error": {"status": 403, "message": "Player command failed: Premium required", "reason": "PREMIUM_REQUIRED"}}"#,
+        ),
+        (
+            403,
+            r#"{"error": {"status": 403, "message": "Insufficient client scope"}}"#,
+        ),
+    ]);
+    let spotify = mock_spotify(&server);
+
+    match spotify.pause_playback(None) {
+        Err(SpotifyError::PremiumRequired(message)) => {
+            assert!(message.contains("Premium required"))
+        }
+        other => panic!("expected premium required, got {:?}", other),
+    }
+    assert!(matches!(
+        spotify.pause_playback(None),
+        Err(SpotifyError::BadRequest(_))
+    )); // other 403s are unchanged
+}
+
+#[test]
+fn recently_played_cursors_continue_paging() {
+    let server = MockServer::start(vec![
+        (
+            200,
+            r#"{"items": [{"track": {"id": "1", "name": "One"}, "played_at": "2016-12-13T20:44:04.589Z"}], "next": "https://api.spotify.com/v1/me/player/recently-played?before=1481661737016&limit=1", "cursors": {"after": "1481661844589", "before": "1481661737016"}, "limit": 1}"#,
+        ),
+        (
+            200,
+            r#"{"items": [], "next": null, "cursors": null, "limit": 1}"#,
+        ),
+    ]);
+    let spotify = mock_spotify(&server);
+
+    let recently_played = spotify
+        .get_recently_played_tracks(None, None, Some(1))
+        .unwrap();
+    assert_eq!(recently_played.tracks.items[0].track.id, "1");
+    assert_eq!(
+        recently_played.cursors.after,
+        chrono::DateTime::from_timestamp_millis(1481661844589).map(|time| time.naive_utc())
+    );
+
+    let older = spotify
+        .get_recently_played_tracks(None, recently_played.cursors.before, Some(1))
+        .unwrap();
+    assert!(older.tracks.items.is_empty());
+    assert!(older.cursors.before.is_none());
+    assert!(server.request_lines()[1]
+        .starts_with("GET /v1/me/player/recently-played?before=1481661737016&limit=1 "));
+}
+
+#[test]
+fn diff_playlists_compares_recordings() {
+    let server = MockServer::start(vec![
+        (
+            200,
+            r#"{"items": [{"track": {"id": "a1", "uri": "spotify:track:a1", "external_ids": {"isrc": "USUM71703861"}}}, {"track": {"id": "a2", "uri": "spotify:track:a2", "external_ids": {"isrc": "GBUM71029604"}}}], "next": "https://api.spotify.com/v1/playlists/a/tracks?offset=2&limit=2", "total": 3}"#,
+        ),
+        (
+            200,
+            r#"{"items": [{"track": {"id": "a3", "uri": "spotify:track:a3"}}], "next": null, "total": 3}"#,
+        ),
+        (
+            200,
+            r#"{"items": [{"track": {"id": "b1", "uri": "spotify:track:b1", "external_ids": {"isrc": "USUM71703861"}}}, {"track": {"id": "b2", "uri": "spotify:track:b2", "linked_from": {"id": "a3"}}}, {"track": {"id": "b3", "uri": "spotify:track:b3"}}], "next": null, "total": 3}"#,
+        ),
+    ]);
+    let spotify = mock_spotify(&server);
+
+    let diff = spotify.diff_playlists("a", "b").unwrap();
+
+    assert_eq!(diff.common, vec!["spotify:track:a1", "spotify:track:a3"]); // same isrc, relinked track
+    assert_eq!(diff.only_in_a, vec!["spotify:track:a2"]);
+    assert_eq!(diff.only_in_b, vec!["spotify:track:b3"]);
+    let request_lines = server.request_lines();
+    assert_eq!(request_lines.len(), 3);
+    assert!(request_lines[1].contains("offset=2"));
+    assert!(request_lines[2].starts_with("GET /v1/playlists/b/tracks?"));
+}
+
+#[test]
+fn playlist_listing_with_fields() {
+    let server = MockServer::start(vec![
+        (
+            200,
+            r#"{"items": [{"id": "37i9dQZF1DXcBWIGoYBM5M", "name": "Today's Top Hits", "tracks": {"total": 50}}]}"#,
+        ),
+        (
+            200,
+            r#"{"items": [{"id": "3cEYpjA9oz9GiPac4AsH4n", "name": "Spotify Web API Testing playlist"}]}"#,
+        ),
+    ]);
+    let spotify = mock_spotify(&server);
+
+    let playlists = spotify
+        .get_current_users_playlists(Some(50), None, Some("items(id,name,tracks.total)"))
+        .unwrap();
+    assert_eq!(playlists.items[0].name, "Today's Top Hits");
+    assert!(playlists.items[0].images.is_empty()); // pruned fields are defaulted
+    assert_eq!(playlists.next, None);
+
+    let playlists = spotify
+        .get_users_playlists("smedjan", None, None, Some("items(id,name)"))
+        .unwrap();
+    assert_eq!(playlists.items[0].id, "3cEYpjA9oz9GiPac4AsH4n");
+
+    let request_lines = server.request_lines();
+    assert!(request_lines[0]
+        .starts_with("GET /v1/me/playlists?limit=50&fields=items(id,name,tracks.total) "));
+    assert!(request_lines[1].starts_with("GET /v1/users/smedjan/playlists?fields=items(id,name) "));
+}
+
+#[test]
+fn shared_client_reads_from_multiple_threads() {
+    let server = MockServer::start(vec![(200, ALBUM_JSON); 4]);
+    let spotify = Arc::new(mock_spotify(&server));
+
+    let handles: Vec<_> = (0..4)
+        .map(|_| {
+            let spotify = Arc::clone(&spotify);
+            thread::spawn(move || spotify.get_album("6JWc4iAiJ9FjyK0B59ABb4", None))
+        })
+        .collect();
+
+    for handle in handles {
+        assert_eq!(handle.join().unwrap().unwrap().name, "The Suburbs");
+    }
+    assert_eq!(server.request_lines().len(), 4);
+}
+
+#[test]
+fn playback_offsets() {
+    let server = MockServer::start(vec![(204, ""); 3]);
+    let spotify = mock_spotify(&server);
+
+    for offset in [
+        PlaybackOffset::Position(2),
+        PlaybackOffset::Track("4iV5W9uYEdYUVa79Axb7Rh"),
+        PlaybackOffset::Uri("spotify:episode:512ojhOuo1ktJprKbVcKyQ"),
+    ] {
+        spotify
+            .start_resume_playback_ms(None, None, None, Some(offset), None)
+            .unwrap();
+    }
+
+    let requests = server.requests.lock().unwrap();
+    assert_eq!(requests[0].1, r#"{"offset":{"position":2}}"#);
+    assert_eq!(
+        requests[1].1,
+        r#"{"offset":{"uri":"spotify:track:4iV5W9uYEdYUVa79Axb7Rh"}}"#
+    ); // the track id is turned into a uri
+    assert_eq!(
+        requests[2].1,
+        r#"{"offset":{"uri":"spotify:episode:512ojhOuo1ktJprKbVcKyQ"}}"#
+    ); // the uri is used as is
+}
+
+#[test]
+fn track_availability_checks_markets_with_one_request() {
+    let server = MockServer::start(vec![
+        (
+            200,
+            r#"{"id": "11dFghVXANMlKmJXsNCbNl", "name": "Cut To The Feeling", "available_markets": ["CA", "MX", "US"]}"#,
+        ),
+        (200, r#"{"id": "smedjan", "country": "MX"}"#),
+    ]);
+    let spotify = mock_spotify(&server);
+
+    let availability = spotify
+        .track_availability(
+            "11dFghVXANMlKmJXsNCbNl",
+            &[
+                Market::try_from("US").unwrap(),
+                Market::try_from("SE").unwrap(),
+                Market::FromToken,
+            ],
+        )
+        .unwrap();
+
+    assert_eq!(availability.len(), 3);
+    assert!(availability[&Market::try_from("US").unwrap()]);
+    assert!(!availability[&Market::try_from("SE").unwrap()]);
+    assert!(availability[&Market::FromToken]); // the user's market, MX
+    assert!(server.request_lines()[0].starts_with("GET /v1/tracks/11dFghVXANMlKmJXsNCbNl "));
+    assert_eq!(server.request_lines().len(), 2); // the track and the user's profile
+}
+
+#[test]
+fn liked_songs_aliases_use_the_saved_tracks_endpoints() {
+    let server = MockServer::start(vec![
+        (
+            200,
+            r#"{"items": [{"added_at": "2023-01-02T03:04:05Z", "track": {"id": "11dFghVXANMlKmJXsNCbNl"}}], "limit": 1, "offset": 5, "total": 6}"#,
+        ),
+        (200, ""),
+        (200, ""),
+    ]);
+    let spotify = mock_spotify(&server);
+
+    let liked = spotify.get_liked_songs(Some(1), Some(5)).unwrap();
+    assert_eq!(liked.items[0].track.id, "11dFghVXANMlKmJXsNCbNl");
+    spotify
+        .add_to_liked(vec!["11dFghVXANMlKmJXsNCbNl"])
+        .unwrap();
+    spotify
+        .remove_from_liked(vec!["11dFghVXANMlKmJXsNCbNl"])
+        .unwrap();
+
+    let lines = server.request_lines();
+    assert!(lines[0].starts_with("GET /v1/me/tracks?limit=1&offset=5 "));
+    assert!(lines[1].starts_with("PUT /v1/me/tracks?ids=11dFghVXANMlKmJXsNCbNl "));
+    assert!(lines[2].starts_with("DELETE /v1/me/tracks?ids=11dFghVXANMlKmJXsNCbNl "));
+}
+
+#[test]
+fn resolve_link_fetches_each_kind() {
+    let server = MockServer::start(vec![
+        (
+            200,
+            r#"{"id": "6rqhFgbbKwnb9MLmUQDhG6", "name": "Speak To Me"}"#,
+        ),
+        (200, ALBUM_JSON),
+        (
+            200,
+            r#"{"id": "0TnOYISbd1XYRBk9myaseg", "name": "Pitbull"}"#,
+        ),
+        (
+            200,
+            r#"{"id": "3cEYpjA9oz9GiPac4AsH4n", "name": "Spotify Web API Testing playlist"}"#,
+        ),
+        (
+            200,
+            r#"{"episodes": [{"id": "512ojhOuo1ktJprKbVcKyQ", "name": "Episode One"}]}"#,
+        ),
+        (
+            200,
+            r#"{"shows": [{"id": "5CfCWKI5pZ28U0uOzXkDHe", "name": "Show One"}]}"#,
+        ),
+        (200, r#"{"shows": [null]}"#),
+    ]);
+    let spotify = mock_spotify(&server);
+
+    let resolve = |link: &str| spotify.resolve_link(link).unwrap();
+    assert!(matches!(
+        resolve("https://open.spotify.com/track/6rqhFgbbKwnb9MLmUQDhG6?si=a1b2c3d4e5f6"),
+        ResolvedItem::Track(track) if track.name == "Speak To Me"
+    ));
+    assert!(matches!(
+        resolve("https://open.spotify.com/album/6JWc4iAiJ9FjyK0B59ABb4?si=a1b2c3d4e5f6"),
+        ResolvedItem::Album(album) if album.name == "The Suburbs"
+    ));
+    assert!(matches!(
+        resolve("https://open.spotify.com/intl-es/artist/0TnOYISbd1XYRBk9myaseg"),
+        ResolvedItem::Artist(artist) if artist.name == "Pitbull"
+    ));
+    assert!(matches!(
+        resolve("https://open.spotify.com/playlist/3cEYpjA9oz9GiPac4AsH4n?si=a1b2c3d4e5f6&pi=u-x"),
+        ResolvedItem::Playlist(playlist) if playlist.id == "3cEYpjA9oz9GiPac4AsH4n"
+    ));
+    assert!(matches!(
+        resolve("https://open.spotify.com/episode/512ojhOuo1ktJprKbVcKyQ"),
+        ResolvedItem::Episode(episode) if episode.name == "Episode One"
+    ));
+    assert!(matches!(
+        resolve("spotify:show:5CfCWKI5pZ28U0uOzXkDHe"),
+        ResolvedItem::Show(show) if show.name == "Show One"
+    ));
+    assert!(matches!(
+        spotify.resolve_link("https://open.spotify.com/show/5CfCWKI5pZ28U0uOzXkDHe"),
+        Err(SpotifyError::NotFound(_))
+    )); // unavailable show
+
+    let lines = server.request_lines();
+    assert!(lines[0].starts_with("GET /v1/tracks/6rqhFgbbKwnb9MLmUQDhG6 "));
+    assert!(lines[1].starts_with("GET /v1/albums/6JWc4iAiJ9FjyK0B59ABb4 "));
+    assert!(lines[2].starts_with("GET /v1/artists/0TnOYISbd1XYRBk9myaseg "));
+    assert!(lines[3].starts_with("GET /v1/playlists/3cEYpjA9oz9GiPac4AsH4n"));
+    assert!(lines[4].starts_with("GET /v1/episodes?ids=512ojhOuo1ktJprKbVcKyQ"));
+    assert!(lines[5].starts_with("GET /v1/shows?ids=5CfCWKI5pZ28U0uOzXkDHe"));
+}
+
+#[test]
+fn resolve_link_rejects_malformed_links() {
+    let server = MockServer::start(vec![]);
+    let spotify = mock_spotify(&server);
+
+    for link in [
+        "https://example.com/track/6rqhFgbbKwnb9MLmUQDhG6",
+        "https://open.spotify.com/track/",
+        "https://open.spotify.com/track/6rqhFgbb?si=a1b2c3d4e5f6",
+        "https://open.spotify.com/user/smedjan",
+        "not a link",
+    ] {
+        assert!(
+            matches!(
+                spotify.resolve_link(link),
+                Err(SpotifyError::InvalidRequest(_))
+            ),
+            "{} was accepted",
+            link
+        );
+    }
+    assert!(server.request_lines().is_empty()); // rejected before sending
+}
+
+/// Answers a playlist items request with the page at its offset of a playlist with `total` items, numbered from 0
+fn playlist_page(request_line: &str, total: usize) -> (u16, String, String) {
+    let offset: usize = request_line
+        .split(|c| c == '?' || c == '&' || c == ' ')
+        .find_map(|parameter| parameter.strip_prefix("offset="))
+        .unwrap()
+        .parse()
+        .unwrap();
+    let items: Vec<String> = (offset..total.min(offset + 100))
+        .map(|i| format!(r#"{{"track": {{"id": "t{}"}}}}"#, i))
+        .collect();
+    let body = format!(
+        r#"{{"items": [{}], "limit": 100, "offset": {}, "total": {}}}"#,
+        items.join(","),
+        offset,
+        total
+    );
+    (200, String::from("application/json"), body)
+}
+
+#[test]
+fn concurrent_playlist_paging_keeps_order() {
+    let server = MockServer::start_with_handler(5, |request_line| playlist_page(request_line, 450));
+    let spotify = mock_spotify(&server);
+
+    let items = spotify
+        .get_all_playlist_tracks_concurrent("3cEYpjA9oz9GiPac4AsH4n", 3)
+        .unwrap();
+
+    let ids: Vec<String> = items
+        .iter()
+        .map(|item| match &item.track {
+            PlayableItem::Track(track) => track.id.clone(),
+            PlayableItem::Episode(episode) => episode.id.clone(),
+        })
+        .collect();
+    let expected: Vec<String> = (0..450).map(|i| format!("t{}", i)).collect();
+    assert_eq!(ids, expected);
+
+    let mut offsets: Vec<String> = server
+        .request_lines()
+        .iter()
+        .map(|line| {
+            line.split("offset=")
+                .nth(1)
+                .unwrap()
+                .split(' ')
+                .next()
+                .unwrap()
+                .to_string()
+        })
+        .collect();
+    offsets.sort_by_key(|offset| offset.parse::<i32>().unwrap());
+    assert_eq!(offsets, vec!["0", "100", "200", "300", "400"]);
+
+    assert!(matches!(
+        spotify.get_all_playlist_tracks_concurrent("3cEYpjA9oz9GiPac4AsH4n", 0),
+        Err(SpotifyError::InvalidRequest(_))
+    ));
+}
+
+#[test]
+fn concurrent_playlist_paging_returns_failed_page_error() {
+    let server = MockServer::start_with_handler(3, |request_line| {
+        if request_line.contains("offset=100") {
+            (
+                404,
+                String::from("application/json"),
+                String::from(r#"{"error": {"status": 404, "message": "Page went missing"}}"#),
+            )
+        } else {
+            playlist_page(request_line, 250)
+        }
+    });
+    let spotify = mock_spotify(&server);
+
+    match spotify.get_all_playlist_tracks_concurrent("3cEYpjA9oz9GiPac4AsH4n", 1) {
+        Err(SpotifyError::NotFound(message)) => assert_eq!(message, "Page went missing"),
+        other => panic!("expected not found, got {:?}", other),
+    }
+    assert_eq!(server.request_lines().len(), 2); // no pages requested after the failed one
+}
+
+#[test]
+fn queue_context_queues_album_tracks_in_order() {
+    let server = MockServer::start(vec![
+        (
+            200,
+            r#"{"items": [{"id": "1", "uri": "spotify:track:1"}, {"id": "2", "uri": "spotify:track:2"}], "limit": 50, "offset": 0, "next": "https://api.spotify.com/v1/albums/6JWc4iAiJ9FjyK0B59ABb4/tracks?offset=2&limit=2", "total": 3}"#,
+        ),
+        (
+            200,
+            r#"{"items": [{"id": "3", "uri": "spotify:track:3"}], "limit": 50, "offset": 2, "next": null, "total": 3}"#,
+        ),
+        (204, ""),
+        (204, ""),
+        (204, ""),
+    ]);
+    let spotify = mock_spotify(&server);
+
+    spotify
+        .queue_context(
+            SpotifyContext::Album(String::from("6JWc4iAiJ9FjyK0B59ABb4")),
+            Some("device"),
+        )
+        .unwrap();
+
+    let lines = server.request_lines();
+    assert_eq!(lines.len(), 5);
+    for (line, track_id) in lines[2..].iter().zip(["1", "2", "3"]) {
+        assert!(line.starts_with(&format!(
+            "POST /v1/me/player/queue?uri=spotify:track:{}&device_id=device ",
+            track_id
+        )));
+    }
+
+    assert!(matches!(
+        spotify.queue_context(
+            SpotifyContext::Artist(String::from("0TnOYISbd1XYRBk9myaseg")),
+            None
+        ),
+        Err(SpotifyError::InvalidRequest(_))
+    ));
+}
+
+#[test]
+fn queue_context_queues_playlist_episodes_by_uri() {
+    let server = MockServer::start(vec![
+        (
+            200,
+            r#"{"items": [{"track": {"id": "6JWc4iAiJ9FjyK0B59ABb4", "uri": "spotify:track:6JWc4iAiJ9FjyK0B59ABb4", "type": "track"}}, {"is_local": true, "track": {"id": null, "uri": "spotify:local:The+Band:Demos:Demo+Take+3:187", "is_local": true, "type": "track"}}, {"track": {"id": "512ojhOuo1ktJprKbVcKyQ", "uri": "spotify:episode:512ojhOuo1ktJprKbVcKyQ", "type": "episode"}}], "limit": 100, "offset": 0, "next": null, "total": 3}"#,
+        ),
+        (204, ""),
+        (204, ""),
+    ]);
+    let spotify = mock_spotify(&server);
+
+    spotify
+        .queue_context(
+            SpotifyContext::Playlist(String::from("3cEYpjA9oz9GiPac4AsH4n")),
+            None,
+        )
+        .unwrap();
+
+    let lines = server.request_lines();
+    assert_eq!(lines.len(), 3); // the local file is skipped
+    assert!(
+        lines[1].starts_with("POST /v1/me/player/queue?uri=spotify:track:6JWc4iAiJ9FjyK0B59ABb4 ")
+    );
+    assert!(lines[2]
+        .starts_with("POST /v1/me/player/queue?uri=spotify:episode:512ojhOuo1ktJprKbVcKyQ "));
+}
+
+#[test]
+fn player_commands_auto_select_device() {
+    let no_active_device = r#"{"error": {"status": 404, "message": "Player command failed: No active device found", "reason": "NO_ACTIVE_DEVICE"}}"#;
+    let devices = r#"{"devices": [{"id": "speaker", "is_active": false, "is_restricted": true, "name": "Speaker", "type": "Speaker"}, {"id": "laptop", "is_active": false, "is_restricted": false, "name": "Laptop", "type": "Computer"}]}"#;
+    let server = MockServer::start(vec![
+        (404, no_active_device),
+        (404, no_active_device),
+        (200, devices),
+        (204, ""),
+    ]);
+    let spotify = mock_spotify(&server);
+
+    match spotify.pause_playback(None) {
+        Err(SpotifyError::NoActiveDevice(_)) => (),
+        other => panic!("expected no active device, got {:?}", other),
+    } // off by default
+
+    spotify.set_auto_select_device(true);
+    spotify.skip_next(None).unwrap();
+
+    let request_lines = server.request_lines();
+    assert_eq!(request_lines.len(), 4);
+    assert!(request_lines[1].starts_with("POST /v1/me/player/next "));
+    assert!(request_lines[2].starts_with("GET /v1/me/player/devices "));
+    assert!(request_lines[3].starts_with("POST /v1/me/player/next?device_id=laptop "));
+    // restricted device skipped
+}
+
+#[test]
+fn recently_played_since_pages_forward() {
+    let server = MockServer::start(vec![
+        (
+            200,
+            r#"{"items": [{"track": {"id": "2"}, "played_at": "2016-12-13T20:46:00.000Z"}, {"track": {"id": "1"}, "played_at": "2016-12-13T20:44:00.000Z"}], "cursors": {"after": "1481661960000", "before": "1481661840000"}}"#,
+        ),
+        (
+            200,
+            r#"{"items": [{"track": {"id": "3"}, "played_at": "2016-12-13T20:50:00.000Z"}], "cursors": {"after": "1481662200000", "before": "1481662200000"}}"#,
+        ),
+        (200, r#"{"items": [], "cursors": null}"#),
+    ]);
+    let spotify = mock_spotify(&server);
+
+    let after = chrono::NaiveDate::from_ymd_opt(2016, 12, 13)
+        .unwrap()
+        .and_hms_opt(20, 40, 0)
+        .unwrap();
+    let played_tracks = spotify.recently_played_since(after).unwrap();
+
+    let ids: Vec<&str> = played_tracks
+        .iter()
+        .map(|played_track| played_track.track.id.as_str())
+        .collect();
+    assert_eq!(ids, vec!["1", "2", "3"]); // oldest first
+
+    let request_lines = server.request_lines();
+    assert_eq!(request_lines.len(), 3); // stops once the cursor is exhausted
+    assert!(request_lines[0]
+        .starts_with("GET /v1/me/player/recently-played?after=1481661600000&limit=50 "));
+    assert!(request_lines[1]
+        .starts_with("GET /v1/me/player/recently-played?after=1481661960000&limit=50 "));
+    assert!(request_lines[2]
+        .starts_with("GET /v1/me/player/recently-played?after=1481662200000&limit=50 "));
+}
+
+#[test]
+fn builder_configures_client() {
+    let server = MockServer::start(vec![(500, ""), (200, ALBUM_JSON)]);
+
+    let spotify = SpotifyBuilder::new()
+        .api_url(&server.url)
+        .max_retries(0)
+        .retry_delay(StdDuration::ZERO)
+        .user_agent("my-app/1.0")
+        .timeout(StdDuration::from_secs(5))
+        .from_tokens(
+            "client_id",
+            "user-read-private",
+            "access_token",
+            "refresh_token",
+            Utc::now() + Duration::seconds(3600),
+        )
+        .build()
+        .unwrap();
+
+    assert!(spotify.is_authenticated());
+    assert!(spotify.get_album("6JWc4iAiJ9FjyK0B59ABb4", None).is_err()); // not retried
+    assert_eq!(
+        spotify
+            .get_album("6JWc4iAiJ9FjyK0B59ABb4", None)
+            .unwrap()
+            .name,
+        "The Suburbs"
+    );
+    assert_eq!(
+        server.header(0, "User-Agent"),
+        Some(String::from("my-app/1.0"))
+    );
+    assert_eq!(server.request_lines().len(), 2);
+}
+
+#[test]
+fn builder_authorizes_from_file() {
+    let server = MockServer::start(vec![(
+        200,
+        r#"{"access_token": "new_access_token", "expires_in": 3600, "refresh_token": "new_refresh_token"}"#,
+    )]);
+    let file_name = std::env::temp_dir().join(format!("spotifyrs_builder_{}", std::process::id()));
+    std::fs::write(&file_name, "client_id\nuser-read-private\nrefresh_token\n").unwrap();
+
+    let spotify = SpotifyBuilder::new()
+        .accounts_url(server.url.trim_end_matches("/v1"))
+        .from_file(file_name.to_str().unwrap())
+        .build()
+        .unwrap();
+    std::fs::remove_file(&file_name).unwrap();
+
+    assert_eq!(spotify.access_token().unwrap(), "new_access_token");
+    assert!(server.request_lines()[0].starts_with("POST /api/token?"));
+}
+
+#[test]
+fn playlist_modifications_return_snapshot_ids() {
+    let server = MockServer::start(vec![
+        (201, r#"{"snapshot_id": "MTAsMWEzOWM0ZjE2"}"#),
+        (200, r#"{"snapshot_id": "MTEsMmI0YWQ1ZjI3"}"#),
+    ]);
+    let spotify = mock_spotify(&server);
+
+    let added = spotify
+        .add_tracks_to_playlist(
+            "3cEYpjA9oz9GiPac4AsH4n",
+            vec!["4iV5W9uYEdYUVa79Axb7Rh"],
+            None,
+        )
+        .unwrap();
+    assert_eq!(added, SnapshotId(String::from("MTAsMWEzOWM0ZjE2")));
+
+    let removed = spotify
+        .remove_playlist_tracks(
+            "3cEYpjA9oz9GiPac4AsH4n",
+            vec!["4iV5W9uYEdYUVa79Axb7Rh"],
+            Some(added.as_str()),
+        )
+        .unwrap();
+    assert_eq!(removed.as_str(), "MTEsMmI0YWQ1ZjI3");
+
+    let body: serde_json::Value =
+        serde_json::from_str(&server.requests.lock().unwrap()[1].1).unwrap();
+    assert_eq!(body["snapshot_id"], "MTAsMWEzOWM0ZjE2");
+}
@@ -0,0 +1,65 @@
+mod common;
+
+use common::{mock_spotify, MockServer};
+use spotifyrs::{SearchItem, SearchType};
+
+/// Builds a page of track search results with the given number of items
+fn track_page(count: usize, has_next: bool) -> String {
+    let items: Vec<String> = (0..count)
+        .map(|i| {
+            format!(
+                r#"{{"id": "track{}", "name": "Track {}", "type": "track"}}"#,
+                i, i
+            )
+        })
+        .collect();
+    let next = if has_next {
+        r#""https://api.spotify.com/v1/search?offset=50""#
+    } else {
+        "null"
+    };
+    format!(
+        r#"{{"tracks": {{"items": [{}], "limit": 50, "next": {}, "offset": 0, "total": 5000}}}}"#,
+        items.join(","),
+        next
+    )
+}
+
+#[test]
+fn search_iter_follows_pages() {
+    let first_page = track_page(50, true);
+    let last_page = track_page(3, false);
+    let server = MockServer::start(vec![(200, &first_page), (200, &last_page)]);
+    let spotify = mock_spotify(&server);
+
+    let results: Vec<SearchItem> = spotify
+        .search_iter("the suburbs", SearchType::Track, None)
+        .map(|item| item.unwrap())
+        .collect();
+
+    assert_eq!(results.len(), 53);
+    assert!(matches!(results[0], SearchItem::Track(_)));
+    let request_lines = server.request_lines();
+    assert_eq!(request_lines.len(), 2);
+    assert!(request_lines[0]
+        .starts_with("GET /v1/search?q=the%20suburbs&type=track&limit=50&offset=0 "));
+    assert!(request_lines[1]
+        .starts_with("GET /v1/search?q=the%20suburbs&type=track&limit=50&offset=50 "));
+}
+
+#[test]
+fn search_iter_stops_at_offset_cap() {
+    let page = track_page(50, true); // every page claims there is more
+    let server = MockServer::start(vec![(200, page.as_str()); 21]);
+    let spotify = mock_spotify(&server);
+
+    let count = spotify
+        .search_iter("love", SearchType::Track, None)
+        .map(|item| item.unwrap())
+        .count();
+
+    assert_eq!(count, 1000);
+    let request_lines = server.request_lines();
+    assert_eq!(request_lines.len(), 20); // no request past the cap
+    assert!(request_lines[19].contains("&limit=50&offset=950 "));
+}